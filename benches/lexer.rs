@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use interpreter::Lexer;
+
+//tokens per second on a few megabytes read line by line, `cargo bench --bench lexer`
+//the state machine lexer this one replaced took 667 ms for `next` here, this one 140-200 ms
+
+const SNIPPET: &str = r#"let fib = fn(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } };
+// adds all numbers of the array
+let total = fn(xs) { reduce(xs, 0, fn(sum, x) { sum + x * 0x10 }) };
+let names = {"first": "Ada", "last": "Lovelace", :born: 1815};
+puts(total([1, 2, 3]) != 96, names["first"] + " " + names["last"]);
+"#;
+
+const SIZE: usize = 4 * 1024 * 1024;
+const RUNS: usize = 5;
+const CHUNK: usize = 8 * 1024;
+
+fn main() {
+    let source = SNIPPET.repeat(SIZE / SNIPPET.len());
+    let by_lines = || {
+        let mut lexer = Lexer::new();
+        for line in source.lines() {
+            lexer.process(line);
+        }
+        lexer
+    };
+    measure("next", source.len(), by_lines, |lexer| {
+        lexer.next().is_some()
+    });
+    measure("peek and next", source.len(), by_lines, |lexer| {
+        lexer.peek();
+        lexer.peek();
+        lexer.next().is_some()
+    });
+    //long string and comment read in chunks, they must not be scanned again for every chunk
+    let long = format!(
+        "\"{}\" // {}",
+        "ab\n".repeat(SIZE / 6),
+        "x".repeat(SIZE / 2)
+    );
+    let in_chunks = || {
+        let chunks: Vec<String> = long
+            .as_bytes()
+            .chunks(CHUNK)
+            .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+            .collect();
+        Lexer::from_chunks(chunks)
+    };
+    measure("long in chunks", long.len(), in_chunks, |lexer| {
+        lexer.next().is_some()
+    });
+}
+
+fn measure<'a>(
+    name: &str,
+    size: usize,
+    lexer: impl Fn() -> Lexer<'a>,
+    step: impl Fn(&mut Lexer) -> bool,
+) {
+    let mut best = Duration::MAX;
+    let mut tokens = 0;
+    for _ in 0..RUNS {
+        let started = Instant::now();
+        let mut lexer = lexer();
+        tokens = 0;
+        while step(&mut lexer) {
+            tokens += 1;
+        }
+        best = best.min(started.elapsed());
+    }
+    println!(
+        "{:<14} {:>6.1} MB {:>9} tokens {:>8.1} ms {:>6.2} M tokens/s",
+        name,
+        size as f64 / 1e6,
+        tokens,
+        best.as_secs_f64() * 1e3,
+        tokens as f64 / best.as_secs_f64() / 1e6
+    );
+}
@@ -1,7 +1,17 @@
-use std::any::Any;
+use super::{
+    expression::Expression,
+    statements::{Program, Statement},
+};
 
-pub(crate) trait Node: ToString + Any + std::fmt::Debug {
-    fn as_any(&self) -> &dyn Any;
+pub(crate) trait Node: ToString + std::fmt::Debug {
+    fn as_node(&self) -> NodeRef<'_>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum NodeRef<'a> {
+    Program(&'a Program),
+    Statement(&'a Statement),
+    Expression(&'a Expression),
 }
 
 #[macro_export]
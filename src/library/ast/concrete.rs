@@ -0,0 +1,154 @@
+use std::rc::Rc;
+
+use crate::tokens::{PureTokenKind, Token};
+
+use super::{
+    expression::Expression,
+    statements::{Program, Statement},
+};
+
+//lossless tree, every token keeps its trivia, so the tree prints back to the exact source
+#[derive(Debug, Clone)]
+pub struct SyntaxNode {
+    pub kind: SyntaxKind,
+    pub children: Vec<SyntaxElement>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(Rc<Token>),
+}
+
+#[derive(Debug, Clone)]
+pub enum SyntaxKind {
+    Program,
+    Statement(Box<Statement>), // parsed from tokens of the node
+    Invalid,                   // tokens which could not be parsed into a statement
+    Group,                     // tokens between matching brackets, brackets included
+}
+
+impl SyntaxNode {
+    pub(crate) fn program(children: Vec<SyntaxElement>) -> Self {
+        SyntaxNode {
+            kind: SyntaxKind::Program,
+            children,
+        }
+    }
+
+    pub(crate) fn statement(statement: Option<Statement>, tokens: &[Rc<Token>]) -> Self {
+        SyntaxNode {
+            kind: statement.map_or(SyntaxKind::Invalid, |statement| {
+                SyntaxKind::Statement(Box::new(statement))
+            }),
+            children: group(tokens),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.write_text(&mut text);
+        text
+    }
+
+    fn write_text(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.write_text(text),
+                SyntaxElement::Token(token) => text.push_str(&token.source_text()),
+            }
+        }
+    }
+
+    pub fn tokens(&self) -> Vec<Rc<Token>> {
+        let mut tokens = vec![];
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => tokens.extend(node.tokens()),
+                SyntaxElement::Token(token) => tokens.push(token.clone()),
+            }
+        }
+        tokens
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    pub fn to_statement(&self) -> Option<Statement> {
+        match &self.kind {
+            SyntaxKind::Statement(statement) => Some(statement.as_ref().clone()),
+            _ => None,
+        }
+    }
+
+    pub fn to_expression(&self) -> Option<Expression> {
+        match &self.kind {
+            SyntaxKind::Statement(statement) => match statement.as_ref() {
+                Statement::AExpression { expression, .. } => Some(expression.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn to_program(&self) -> Program {
+        Program {
+            statements: self.nodes().filter_map(SyntaxNode::to_statement).collect(),
+        }
+    }
+}
+
+//closing bracket without an opening one stays in place, unclosed groups end with the tokens
+fn group(tokens: &[Rc<Token>]) -> Vec<SyntaxElement> {
+    let mut open: Vec<(PureTokenKind, Vec<SyntaxElement>)> = vec![];
+    let mut elements = vec![];
+    for token in tokens {
+        let kind = PureTokenKind::from(&token.kind);
+        if let Some(closing) = closing_bracket(kind) {
+            open.push((closing, vec![SyntaxElement::Token(token.clone())]));
+            continue;
+        }
+        match open.last_mut() {
+            Some((closing, children)) => {
+                children.push(SyntaxElement::Token(token.clone()));
+                if *closing == kind {
+                    let (_, children) = open.pop().unwrap();
+                    close_group(&mut open, &mut elements, children);
+                }
+            }
+            None => elements.push(SyntaxElement::Token(token.clone())),
+        }
+    }
+    while let Some((_, children)) = open.pop() {
+        close_group(&mut open, &mut elements, children);
+    }
+    elements
+}
+
+fn close_group(
+    open: &mut [(PureTokenKind, Vec<SyntaxElement>)],
+    elements: &mut Vec<SyntaxElement>,
+    children: Vec<SyntaxElement>,
+) {
+    let node = SyntaxElement::Node(SyntaxNode {
+        kind: SyntaxKind::Group,
+        children,
+    });
+    match open.last_mut() {
+        Some((_, outer)) => outer.push(node),
+        None => elements.push(node),
+    }
+}
+
+fn closing_bracket(opening: PureTokenKind) -> Option<PureTokenKind> {
+    match opening {
+        PureTokenKind::LeftParen => Some(PureTokenKind::RightParen),
+        PureTokenKind::LeftBracket => Some(PureTokenKind::RightBracket),
+        PureTokenKind::LeftBrace | PureTokenKind::SetStart => Some(PureTokenKind::RightBrace),
+        _ => None,
+    }
+}
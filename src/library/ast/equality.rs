@@ -0,0 +1,254 @@
+use crate::tokens::TokenKind;
+
+use super::{
+    expression::Expression,
+    statements::{Program, Statement},
+};
+
+//spans and tokens of brackets and keywords are not compared, only structure, names and values
+
+impl PartialEq for Program {
+    fn eq(&self, other: &Self) -> bool {
+        self.statements == other.statements
+    }
+}
+
+impl PartialEq for Statement {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Statement::Let {
+                    name,
+                    value,
+                    annotation,
+                    ..
+                },
+                Statement::Let {
+                    name: other_name,
+                    value: other_value,
+                    annotation: other_annotation,
+                    ..
+                },
+            ) => name == other_name && value == other_value && annotation == other_annotation,
+            (
+                Statement::Return { return_value, .. },
+                Statement::Return {
+                    return_value: other,
+                    ..
+                },
+            ) => return_value == other,
+            (
+                Statement::Assign { name, value, .. },
+                Statement::Assign {
+                    name: other_name,
+                    value: other_value,
+                    ..
+                },
+            )
+            | (
+                Statement::Function { name, value, .. },
+                Statement::Function {
+                    name: other_name,
+                    value: other_value,
+                    ..
+                },
+            ) => name == other_name && value == other_value,
+            (
+                Statement::Yield {
+                    value, delegate, ..
+                },
+                Statement::Yield {
+                    value: other_value,
+                    delegate: other_delegate,
+                    ..
+                },
+            ) => value == other_value && delegate == other_delegate,
+            (
+                Statement::AExpression { expression, .. },
+                Statement::AExpression {
+                    expression: other, ..
+                },
+            ) => expression == other,
+            (
+                Statement::Block { statements, .. },
+                Statement::Block {
+                    statements: other, ..
+                },
+            ) => statements == other,
+            (
+                Statement::Operator {
+                    fixity,
+                    name,
+                    value,
+                    ..
+                },
+                Statement::Operator {
+                    fixity: other_fixity,
+                    name: other_name,
+                    value: other_value,
+                    ..
+                },
+            ) => fixity == other_fixity && name == other_name && value == other_value,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Expression::Identifier(token), Expression::Identifier(other))
+            | (Expression::IntegerLiteral(token), Expression::IntegerLiteral(other))
+            | (Expression::StringLiteral(token), Expression::StringLiteral(other))
+            | (Expression::SymbolLiteral(token), Expression::SymbolLiteral(other)) => {
+                token == other
+            }
+            (
+                Expression::BooleanLiteral { value, .. },
+                Expression::BooleanLiteral { value: other, .. },
+            ) => value == other,
+            (
+                Expression::PrefixOperator {
+                    operator, right, ..
+                },
+                Expression::PrefixOperator {
+                    operator: other_operator,
+                    right: other_right,
+                    ..
+                },
+            ) => operator == other_operator && right == other_right,
+            (
+                Expression::Infix {
+                    left,
+                    operator,
+                    right,
+                    ..
+                },
+                Expression::Infix {
+                    left: other_left,
+                    operator: other_operator,
+                    right: other_right,
+                    ..
+                },
+            ) => operator == other_operator && left == other_left && right == other_right,
+            //`a <+> b` is the same call as `<+>(a, b)` would be, but it is written differently
+            (
+                Expression::Call {
+                    token,
+                    function,
+                    arguments,
+                    ..
+                },
+                Expression::Call {
+                    token: other_token,
+                    function: other_function,
+                    arguments: other_arguments,
+                    ..
+                },
+            ) => {
+                let same_syntax = match (&token.kind, &other_token.kind) {
+                    (TokenKind::Operator(_), _) | (_, TokenKind::Operator(_)) => {
+                        token == other_token
+                    }
+                    _ => true,
+                };
+                same_syntax && function == other_function && arguments == other_arguments
+            }
+            (
+                Expression::AIf {
+                    condition,
+                    consequence,
+                    alternative,
+                    ..
+                },
+                Expression::AIf {
+                    condition: other_condition,
+                    consequence: other_consequence,
+                    alternative: other_alternative,
+                    ..
+                },
+            ) => {
+                condition == other_condition
+                    && consequence == other_consequence
+                    && alternative == other_alternative
+            }
+            (
+                Expression::FunctionLiteral {
+                    parameters,
+                    body,
+                    name,
+                    generator,
+                    signature,
+                    ..
+                },
+                Expression::FunctionLiteral {
+                    parameters: other_parameters,
+                    body: other_body,
+                    name: other_name,
+                    generator: other_generator,
+                    signature: other_signature,
+                    ..
+                },
+            ) => {
+                parameters == other_parameters
+                    && body == other_body
+                    && name == other_name
+                    && generator == other_generator
+                    && signature == other_signature
+            }
+            (
+                Expression::MacroLiteral {
+                    parameters, body, ..
+                },
+                Expression::MacroLiteral {
+                    parameters: other_parameters,
+                    body: other_body,
+                    ..
+                },
+            ) => parameters == other_parameters && body == other_body,
+            (
+                Expression::ArrayLiteral { elements, .. },
+                Expression::ArrayLiteral {
+                    elements: other, ..
+                },
+            )
+            | (
+                Expression::TupleLiteral { elements, .. },
+                Expression::TupleLiteral {
+                    elements: other, ..
+                },
+            )
+            | (
+                Expression::SetLiteral { elements, .. },
+                Expression::SetLiteral {
+                    elements: other, ..
+                },
+            ) => elements == other,
+            (
+                Expression::Decorated {
+                    decorators, value, ..
+                },
+                Expression::Decorated {
+                    decorators: other_decorators,
+                    value: other_value,
+                    ..
+                },
+            ) => decorators == other_decorators && value == other_value,
+            (
+                Expression::Index { array, index, .. },
+                Expression::Index {
+                    array: other_array,
+                    index: other_index,
+                    ..
+                },
+            ) => array == other_array && index == other_index,
+            (
+                Expression::MapLiteral { elements, .. },
+                Expression::MapLiteral {
+                    elements: other, ..
+                },
+            ) => elements == other,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,525 @@
+use std::rc::Rc;
+
+use crate::{
+    lines::{SourceSpan, Span, TextPosition, TokenPosition},
+    tokens::{PureTokenKind, Token, TokenKind},
+};
+
+use super::{
+    super::{
+        expression::{Expression, InfixOperatorType, PrefixOperatorType},
+        statements::{Associativity, Fixity, Program, Statement},
+        types::{Signature, TypeAnnotation},
+    },
+    ImportError,
+    json::Json,
+};
+
+const PREFIX_OPERATORS: [PrefixOperatorType; 2] =
+    [PrefixOperatorType::Bang, PrefixOperatorType::Minus];
+
+const INFIX_OPERATORS: [InfixOperatorType; 8] = [
+    InfixOperatorType::Plus,
+    InfixOperatorType::Minus,
+    InfixOperatorType::NotEqual,
+    InfixOperatorType::Multiply,
+    InfixOperatorType::Divide,
+    InfixOperatorType::LessThan,
+    InfixOperatorType::GreaterThan,
+    InfixOperatorType::Equal,
+];
+
+//kinds of tokens whose text is always the same, the others are built from their literal
+const FIXED_TOKENS: [TokenKind; 32] = [
+    TokenKind::Minus,
+    TokenKind::Comma,
+    TokenKind::Semicolon,
+    TokenKind::LeftParen,
+    TokenKind::RightParen,
+    TokenKind::LeftBrace,
+    TokenKind::RightBrace,
+    TokenKind::SetStart,
+    TokenKind::LeftBracket,
+    TokenKind::RightBracket,
+    TokenKind::Function,
+    TokenKind::Let,
+    TokenKind::Assign,
+    TokenKind::Plus,
+    TokenKind::Equal,
+    TokenKind::Inequal,
+    TokenKind::Negation,
+    TokenKind::LessThen,
+    TokenKind::GreaterThen,
+    TokenKind::Slash,
+    TokenKind::Asterisk,
+    TokenKind::True,
+    TokenKind::False,
+    TokenKind::If,
+    TokenKind::Else,
+    TokenKind::Return,
+    TokenKind::Collon,
+    TokenKind::Macro,
+    TokenKind::Yield,
+    TokenKind::At,
+    TokenKind::Arrow,
+    TokenKind::EndOfInput,
+];
+
+const STATEMENTS: [&str; 8] = [
+    "Let",
+    "Return",
+    "Assign",
+    "Function",
+    "Yield",
+    "ExpressionStatement",
+    "Block",
+    "Operator",
+];
+
+//expressions with a span of their own, literals only have the one of their token
+const COMPOSITES: [&str; 12] = [
+    "Prefix",
+    "Infix",
+    "Call",
+    "If",
+    "FunctionLiteral",
+    "MacroLiteral",
+    "ArrayLiteral",
+    "TupleLiteral",
+    "SetLiteral",
+    "Decorated",
+    "Index",
+    "MapLiteral",
+];
+
+struct At<'a> {
+    json: &'a Json,
+    path: String,
+}
+
+type Decoded<T> = Result<T, ImportError>;
+
+impl<'a> At<'a> {
+    fn error(&self, message: &str) -> ImportError {
+        ImportError::Schema {
+            path: self.path.clone(),
+            message: message.to_string(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Decoded<At<'a>> {
+        match self.json {
+            Json::Object(_) => self
+                .json
+                .get(name)
+                .map(|json| At {
+                    json,
+                    path: format!("{}.{}", self.path, name),
+                })
+                .ok_or_else(|| self.error(&format!("missing member `{}`", name))),
+            _ => Err(self.error("expected an object")),
+        }
+    }
+
+    //missing member is taken as `null`
+    fn optional(&self, name: &str) -> Decoded<Option<At<'a>>> {
+        match self.json {
+            Json::Object(_) => Ok(self
+                .get(name)
+                .ok()
+                .filter(|member| *member.json != Json::Null)),
+            _ => Err(self.error("expected an object")),
+        }
+    }
+
+    fn items(&self) -> Decoded<Vec<At<'a>>> {
+        match self.json {
+            Json::Array(elements) => Ok(elements
+                .iter()
+                .enumerate()
+                .map(|(index, json)| At {
+                    json,
+                    path: format!("{}[{}]", self.path, index),
+                })
+                .collect()),
+            _ => Err(self.error("expected an array")),
+        }
+    }
+
+    fn string(&self) -> Decoded<&'a str> {
+        match self.json {
+            Json::String(value) => Ok(value),
+            _ => Err(self.error("expected a string")),
+        }
+    }
+
+    fn bool(&self) -> Decoded<bool> {
+        match self.json {
+            Json::Bool(value) => Ok(*value),
+            _ => Err(self.error("expected a boolean")),
+        }
+    }
+
+    fn number<T: TryFrom<i64>>(&self) -> Decoded<T> {
+        match self.json {
+            Json::Number(value) => {
+                T::try_from(*value).map_err(|_| self.error("number out of range"))
+            }
+            _ => Err(self.error("expected a number")),
+        }
+    }
+
+    fn kind(&self) -> Decoded<&'a str> {
+        self.get("kind")?.string()
+    }
+}
+
+pub(super) fn program(json: &Json) -> Decoded<Program> {
+    let at = At {
+        json,
+        path: "program".to_string(),
+    };
+    if at.kind()? != "Program" {
+        return Err(at.error("expected a `Program`"));
+    }
+    Ok(Program {
+        statements: statements(&at.get("statements")?)?,
+    })
+}
+
+fn statements(at: &At) -> Decoded<Vec<Statement>> {
+    at.items()?.iter().map(statement).collect()
+}
+
+fn expressions(at: &At) -> Decoded<Vec<Expression>> {
+    at.items()?.iter().map(expression).collect()
+}
+
+fn statement(at: &At) -> Decoded<Statement> {
+    let kind = at.kind()?;
+    if !STATEMENTS.contains(&kind) {
+        return Err(at.error(&format!("unknown statement `{}`", kind)));
+    }
+    let token = token(&at.get("token")?)?;
+    let span = span(&at.get("span")?)?;
+    let statement = match kind {
+        "Let" => Statement::Let {
+            token,
+            span,
+            name: expression(&at.get("name")?)?,
+            value: expression(&at.get("value")?)?,
+            annotation: at
+                .optional("annotation")?
+                .map(|annotation| type_annotation(&annotation))
+                .transpose()?,
+        },
+        "Return" => Statement::Return {
+            token,
+            span,
+            return_value: expression(&at.get("value")?)?,
+        },
+        "Assign" => Statement::Assign {
+            token,
+            span,
+            name: expression(&at.get("name")?)?,
+            value: expression(&at.get("value")?)?,
+        },
+        "Function" => Statement::Function {
+            token,
+            span,
+            name: identifier(&at.get("name")?)?,
+            value: expression(&at.get("value")?)?,
+        },
+        "Yield" => Statement::Yield {
+            token,
+            span,
+            value: expression(&at.get("value")?)?,
+            delegate: at.get("delegate")?.bool()?,
+        },
+        "ExpressionStatement" => Statement::AExpression {
+            token,
+            span,
+            expression: expression(&at.get("expression")?)?,
+        },
+        "Block" => Statement::Block {
+            token,
+            span,
+            statements: Rc::new(statements(&at.get("statements")?)?),
+        },
+        "Operator" => {
+            let associativity = at.get("associativity")?;
+            let associativity = match associativity.string()? {
+                "left" => Associativity::Left,
+                "right" => Associativity::Right,
+                _ => return Err(associativity.error("expected `left` or `right`")),
+            };
+            Statement::Operator {
+                token,
+                span,
+                fixity: Fixity {
+                    precedence: at.get("precedence")?.number()?,
+                    associativity,
+                },
+                name: identifier(&at.get("name")?)?,
+                value: expression(&at.get("value")?)?,
+            }
+        }
+        _ => unreachable!("Kinds of statements are checked"),
+    };
+    Ok(statement)
+}
+
+fn block(at: &At) -> Decoded<Statement> {
+    match at.kind()? {
+        "Block" => statement(at),
+        _ => Err(at.error("expected a `Block`")),
+    }
+}
+
+fn identifier(at: &At) -> Decoded<Expression> {
+    match at.kind()? {
+        "Identifier" => expression(at),
+        _ => Err(at.error("expected an `Identifier`")),
+    }
+}
+
+fn parameters(at: &At) -> Decoded<Rc<Vec<Expression>>> {
+    let parameters: Decoded<Vec<Expression>> = at.items()?.iter().map(identifier).collect();
+    parameters.map(Rc::new)
+}
+
+fn leaf(at: &At, expected: PureTokenKind) -> Decoded<Rc<Token>> {
+    let member = at.get("token")?;
+    let token = token(&member)?;
+    match PureTokenKind::from(&token.kind) == expected {
+        true => Ok(token),
+        false => Err(member.error(&format!("expected a token of kind `{:?}`", expected))),
+    }
+}
+
+fn expression(at: &At) -> Decoded<Expression> {
+    let kind = at.kind()?;
+    let expression = match kind {
+        "Identifier" => Expression::Identifier(leaf(at, PureTokenKind::Identifier)?),
+        "IntegerLiteral" => Expression::IntegerLiteral(leaf(at, PureTokenKind::Integer)?),
+        "StringLiteral" => Expression::StringLiteral(leaf(at, PureTokenKind::StringLiteral)?),
+        "SymbolLiteral" => Expression::SymbolLiteral(leaf(at, PureTokenKind::Symbol)?),
+        "BooleanLiteral" => Expression::BooleanLiteral {
+            token: token(&at.get("token")?)?,
+            value: at.get("value")?.bool()?,
+        },
+        _ => return composite(at, kind),
+    };
+    Ok(expression)
+}
+
+fn composite(at: &At, kind: &str) -> Decoded<Expression> {
+    if !COMPOSITES.contains(&kind) {
+        return Err(at.error(&format!("unknown expression `{}`", kind)));
+    }
+    let token = token(&at.get("token")?)?;
+    let span = span(&at.get("span")?)?;
+    let boxed =
+        |name: &str| -> Decoded<Box<Expression>> { Ok(Box::new(expression(&at.get(name)?)?)) };
+    let expression = match kind {
+        "Prefix" => Expression::PrefixOperator {
+            token,
+            span,
+            operator: operator(&at.get("operator")?, &PREFIX_OPERATORS)?,
+            right: boxed("right")?,
+        },
+        "Infix" => Expression::Infix {
+            token,
+            span,
+            left: boxed("left")?,
+            operator: operator(&at.get("operator")?, &INFIX_OPERATORS)?,
+            right: boxed("right")?,
+        },
+        "Call" => Expression::Call {
+            token,
+            span,
+            function: boxed("function")?,
+            arguments: expressions(&at.get("arguments")?)?,
+        },
+        "If" => Expression::AIf {
+            token,
+            span,
+            condition: boxed("condition")?,
+            consequence: Box::new(block(&at.get("consequence")?)?),
+            alternative: at
+                .optional("alternative")?
+                .map(|alternative| block(&alternative).map(Box::new))
+                .transpose()?,
+        },
+        "FunctionLiteral" => Expression::FunctionLiteral {
+            token,
+            span,
+            parameters: parameters(&at.get("parameters")?)?,
+            body: Box::new(block(&at.get("body")?)?),
+            name: at
+                .optional("name")?
+                .map(|name| name.string().map(str::to_string))
+                .transpose()?,
+            generator: at.get("generator")?.bool()?,
+            signature: at
+                .optional("signature")?
+                .map(|signature| self::signature(&signature).map(Rc::new))
+                .transpose()?,
+        },
+        "MacroLiteral" => Expression::MacroLiteral {
+            token,
+            span,
+            parameters: parameters(&at.get("parameters")?)?,
+            body: Box::new(block(&at.get("body")?)?),
+        },
+        "ArrayLiteral" => Expression::ArrayLiteral {
+            token,
+            span,
+            elements: expressions(&at.get("elements")?)?,
+        },
+        "TupleLiteral" => Expression::TupleLiteral {
+            token,
+            span,
+            elements: expressions(&at.get("elements")?)?,
+        },
+        "SetLiteral" => Expression::SetLiteral {
+            token,
+            span,
+            elements: expressions(&at.get("elements")?)?,
+        },
+        "Decorated" => Expression::Decorated {
+            token,
+            span,
+            decorators: expressions(&at.get("decorators")?)?,
+            value: boxed("value")?,
+        },
+        "Index" => Expression::Index {
+            token,
+            span,
+            array: boxed("array")?,
+            index: boxed("index")?,
+        },
+        "MapLiteral" => Expression::MapLiteral {
+            token,
+            span,
+            elements: at
+                .get("elements")?
+                .items()?
+                .iter()
+                .map(entry)
+                .collect::<Decoded<_>>()?,
+        },
+        _ => unreachable!("Kinds of expressions are checked"),
+    };
+    Ok(expression)
+}
+
+fn entry(at: &At) -> Decoded<(Expression, Expression)> {
+    match at.items()?.as_slice() {
+        [key, value] => Ok((expression(key)?, expression(value)?)),
+        _ => Err(at.error("expected a key and a value")),
+    }
+}
+
+fn operator<T: Clone + ToString>(at: &At, operators: &[T]) -> Decoded<T> {
+    let written = at.string()?;
+    operators
+        .iter()
+        .find(|operator| operator.to_string() == written)
+        .cloned()
+        .ok_or_else(|| at.error(&format!("unknown operator `{}`", written)))
+}
+
+fn token(at: &At) -> Decoded<Rc<Token>> {
+    let name = at.get("kind")?.string()?;
+    let literal = at.get("literal")?;
+    let text = literal.string()?;
+    let kind = match name {
+        "Identifier" => TokenKind::Identifier(text.to_string()),
+        "StringLiteral" => TokenKind::StringLiteral(text.to_string()),
+        "Operator" => TokenKind::Operator(text.to_string()),
+        "Symbol" => match text.strip_prefix(':') {
+            Some(name) => TokenKind::Symbol(name.to_string()),
+            None => return Err(literal.error("symbol has to start with `:`")),
+        },
+        "Integer" => match text.parse() {
+            Ok(value) if value >= 0 => TokenKind::Integer(value),
+            _ => return Err(literal.error("expected an integer")),
+        },
+        name => {
+            let kind = FIXED_TOKENS
+                .iter()
+                .find(|kind| format!("{:?}", PureTokenKind::from(*kind)) == name)
+                .ok_or_else(|| at.error(&format!("unknown token kind `{}`", name)))?;
+            if kind.literal() != text {
+                return Err(literal.error(&format!("expected `{}`", kind.literal())));
+            }
+            kind.clone()
+        }
+    };
+    Ok(Rc::new(Token::at(span(&at.get("span")?)?, kind)))
+}
+
+fn span(at: &At) -> Decoded<SourceSpan> {
+    let position = match (at.optional("from")?, at.optional("to")?) {
+        (Some(from), Some(to)) => Some(TokenPosition::new(position(&from)?, position(&to)?)),
+        (None, None) => None,
+        _ => return Err(at.error("expected both `from` and `to`")),
+    };
+    let bytes = match at.optional("bytes")? {
+        Some(bytes) => match bytes.items()?.as_slice() {
+            [start, end] => Some(Span::new(start.number()?, end.number()?)),
+            _ => return Err(bytes.error("expected the start and the end")),
+        },
+        None => None,
+    };
+    Ok(SourceSpan::new(position, bytes))
+}
+
+fn position(at: &At) -> Decoded<TextPosition> {
+    match at.items()?.as_slice() {
+        [line, column] => Ok(TextPosition::new(line.number()?, column.number()?)),
+        _ => Err(at.error("expected a line and a column")),
+    }
+}
+
+fn signature(at: &At) -> Decoded<Signature> {
+    let parameters = at
+        .get("parameters")?
+        .items()?
+        .iter()
+        .map(|parameter| match parameter.json {
+            Json::Null => Ok(None),
+            _ => type_annotation(parameter).map(Some),
+        })
+        .collect::<Decoded<_>>()?;
+    Ok(Signature {
+        parameters,
+        result: at
+            .optional("result")?
+            .map(|result| type_annotation(&result))
+            .transpose()?,
+    })
+}
+
+fn type_annotation(at: &At) -> Decoded<TypeAnnotation> {
+    if let Json::String(name) = at.json {
+        return TypeAnnotation::named(name)
+            .ok_or_else(|| at.error(&format!("unknown type `{}`", name)));
+    }
+    let boxed = |name: &str| -> Decoded<Box<TypeAnnotation>> {
+        Ok(Box::new(type_annotation(&at.get(name)?)?))
+    };
+    let types = |name: &str| -> Decoded<Vec<TypeAnnotation>> {
+        at.get(name)?.items()?.iter().map(type_annotation).collect()
+    };
+    let annotation = match at.kind()? {
+        "Array" => TypeAnnotation::Array(boxed("element")?),
+        "Map" => TypeAnnotation::Map(boxed("key")?, boxed("value")?),
+        "Set" => TypeAnnotation::Set(boxed("element")?),
+        "Tuple" => TypeAnnotation::Tuple(types("elements")?),
+        "Function" => TypeAnnotation::Function(types("parameters")?, boxed("result")?),
+        kind => return Err(at.error(&format!("unknown type `{}`", kind))),
+    };
+    Ok(annotation)
+}
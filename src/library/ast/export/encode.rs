@@ -0,0 +1,433 @@
+use std::rc::Rc;
+
+use crate::{
+    lines::{SourceSpan, TextPosition},
+    tokens::{PureTokenKind, Token},
+};
+
+use super::{
+    super::{
+        expression::Expression,
+        statements::{Associativity, Program, Statement},
+        types::{Signature, TypeAnnotation},
+    },
+    json::Json,
+};
+
+pub(super) fn program(program: &Program) -> Json {
+    Json::object(vec![
+        ("kind", kind("Program")),
+        ("statements", statements(&program.statements)),
+    ])
+}
+
+fn statements(statements: &[Statement]) -> Json {
+    Json::Array(statements.iter().map(statement).collect())
+}
+
+fn expressions(expressions: &[Expression]) -> Json {
+    Json::Array(expressions.iter().map(expression).collect())
+}
+
+fn kind(name: &str) -> Json {
+    Json::String(name.to_string())
+}
+
+fn node(name: &str, token: &Token, span: SourceSpan, members: Vec<(&str, Json)>) -> Json {
+    let mut all = vec![
+        ("kind", kind(name)),
+        ("token", self::token(token)),
+        ("span", self::span(span)),
+    ];
+    all.extend(members);
+    Json::object(all)
+}
+
+fn leaf(name: &str, token: &Token) -> Json {
+    Json::object(vec![("kind", kind(name)), ("token", self::token(token))])
+}
+
+pub(super) fn statement(statement: &Statement) -> Json {
+    match statement {
+        Statement::Let {
+            token,
+            span,
+            name,
+            value,
+            annotation,
+        } => node(
+            "Let",
+            token,
+            *span,
+            vec![
+                ("name", expression(name)),
+                ("annotation", optional_type(annotation.as_ref())),
+                ("value", expression(value)),
+            ],
+        ),
+        Statement::Return {
+            token,
+            span,
+            return_value,
+        } => node(
+            "Return",
+            token,
+            *span,
+            vec![("value", expression(return_value))],
+        ),
+        Statement::Assign {
+            token,
+            span,
+            name,
+            value,
+        } => node(
+            "Assign",
+            token,
+            *span,
+            vec![("name", expression(name)), ("value", expression(value))],
+        ),
+        Statement::Function {
+            token,
+            span,
+            name,
+            value,
+        } => node(
+            "Function",
+            token,
+            *span,
+            vec![("name", expression(name)), ("value", expression(value))],
+        ),
+        Statement::Yield {
+            token,
+            span,
+            value,
+            delegate,
+        } => node(
+            "Yield",
+            token,
+            *span,
+            vec![
+                ("delegate", Json::Bool(*delegate)),
+                ("value", expression(value)),
+            ],
+        ),
+        Statement::AExpression {
+            token,
+            span,
+            expression: inner,
+        } => node(
+            "ExpressionStatement",
+            token,
+            *span,
+            vec![("expression", expression(inner))],
+        ),
+        Statement::Block {
+            token,
+            span,
+            statements: inner,
+        } => node(
+            "Block",
+            token,
+            *span,
+            vec![("statements", statements(inner))],
+        ),
+        Statement::Operator {
+            token,
+            span,
+            fixity,
+            name,
+            value,
+        } => {
+            let associativity = match fixity.associativity {
+                Associativity::Left => "left",
+                Associativity::Right => "right",
+            };
+            node(
+                "Operator",
+                token,
+                *span,
+                vec![
+                    ("precedence", Json::Number(fixity.precedence as i64)),
+                    ("associativity", kind(associativity)),
+                    ("name", expression(name)),
+                    ("value", expression(value)),
+                ],
+            )
+        }
+    }
+}
+
+pub(super) fn expression(expression: &Expression) -> Json {
+    match expression {
+        Expression::Identifier(token) => leaf("Identifier", token),
+        Expression::IntegerLiteral(token) => leaf("IntegerLiteral", token),
+        Expression::StringLiteral(token) => leaf("StringLiteral", token),
+        Expression::SymbolLiteral(token) => leaf("SymbolLiteral", token),
+        Expression::BooleanLiteral { token, value } => Json::object(vec![
+            ("kind", kind("BooleanLiteral")),
+            ("token", self::token(token)),
+            ("value", Json::Bool(*value)),
+        ]),
+        Expression::PrefixOperator {
+            token,
+            span,
+            operator,
+            right,
+        } => node(
+            "Prefix",
+            token,
+            *span,
+            vec![
+                ("operator", Json::String(operator.to_string())),
+                ("right", self::expression(right)),
+            ],
+        ),
+        Expression::Infix {
+            token,
+            span,
+            left,
+            operator,
+            right,
+        } => node(
+            "Infix",
+            token,
+            *span,
+            vec![
+                ("operator", Json::String(operator.to_string())),
+                ("left", self::expression(left)),
+                ("right", self::expression(right)),
+            ],
+        ),
+        Expression::Call {
+            token,
+            span,
+            function,
+            arguments,
+        } => node(
+            "Call",
+            token,
+            *span,
+            vec![
+                ("function", self::expression(function)),
+                ("arguments", expressions(arguments)),
+            ],
+        ),
+        Expression::AIf {
+            token,
+            span,
+            condition,
+            consequence,
+            alternative,
+        } => node(
+            "If",
+            token,
+            *span,
+            vec![
+                ("condition", self::expression(condition)),
+                ("consequence", statement(consequence)),
+                (
+                    "alternative",
+                    alternative
+                        .as_ref()
+                        .map_or(Json::Null, |alternative| statement(alternative)),
+                ),
+            ],
+        ),
+        Expression::FunctionLiteral {
+            token,
+            span,
+            parameters,
+            body,
+            name,
+            generator,
+            signature,
+        } => node(
+            "FunctionLiteral",
+            token,
+            *span,
+            vec![
+                ("name", name.clone().map_or(Json::Null, Json::String)),
+                ("generator", Json::Bool(*generator)),
+                ("parameters", expressions(parameters)),
+                ("signature", self::signature(signature.as_ref())),
+                ("body", statement(body)),
+            ],
+        ),
+        Expression::MacroLiteral {
+            token,
+            span,
+            parameters,
+            body,
+        } => node(
+            "MacroLiteral",
+            token,
+            *span,
+            vec![
+                ("parameters", expressions(parameters)),
+                ("body", statement(body)),
+            ],
+        ),
+        Expression::ArrayLiteral {
+            token,
+            span,
+            elements,
+        } => node(
+            "ArrayLiteral",
+            token,
+            *span,
+            vec![("elements", expressions(elements))],
+        ),
+        Expression::TupleLiteral {
+            token,
+            span,
+            elements,
+        } => node(
+            "TupleLiteral",
+            token,
+            *span,
+            vec![("elements", expressions(elements))],
+        ),
+        Expression::SetLiteral {
+            token,
+            span,
+            elements,
+        } => node(
+            "SetLiteral",
+            token,
+            *span,
+            vec![("elements", expressions(elements))],
+        ),
+        Expression::Decorated {
+            token,
+            span,
+            decorators,
+            value,
+        } => node(
+            "Decorated",
+            token,
+            *span,
+            vec![
+                ("decorators", expressions(decorators)),
+                ("value", self::expression(value)),
+            ],
+        ),
+        Expression::Index {
+            token,
+            span,
+            array,
+            index,
+        } => node(
+            "Index",
+            token,
+            *span,
+            vec![
+                ("array", self::expression(array)),
+                ("index", self::expression(index)),
+            ],
+        ),
+        Expression::MapLiteral {
+            token,
+            span,
+            elements,
+        } => {
+            let entries = elements
+                .iter()
+                .map(|(key, value)| {
+                    Json::Array(vec![self::expression(key), self::expression(value)])
+                })
+                .collect();
+            node(
+                "MapLiteral",
+                token,
+                *span,
+                vec![("elements", Json::Array(entries))],
+            )
+        }
+    }
+}
+
+fn token(token: &Token) -> Json {
+    Json::object(vec![
+        (
+            "kind",
+            kind(&format!("{:?}", PureTokenKind::from(&token.kind))),
+        ),
+        ("literal", Json::String(token.kind.literal())),
+        ("span", span(token.source_span())),
+    ])
+}
+
+fn span(span: SourceSpan) -> Json {
+    let mut members = vec![];
+    if let Some(position) = span.position {
+        members.push(("from", position_of(position.start)));
+        members.push(("to", position_of(position.end)));
+    }
+    if let Some(bytes) = span.bytes {
+        let range = vec![
+            Json::Number(bytes.start as i64),
+            Json::Number(bytes.end as i64),
+        ];
+        members.push(("bytes", Json::Array(range)));
+    }
+    Json::object(members)
+}
+
+fn position_of(position: TextPosition) -> Json {
+    Json::Array(vec![
+        Json::Number(position.line_number.0 as i64),
+        Json::Number(position.column_number.0 as i64),
+    ])
+}
+
+fn signature(signature: Option<&Rc<Signature>>) -> Json {
+    match signature {
+        Some(signature) => {
+            let parameters = signature
+                .parameters
+                .iter()
+                .map(|parameter| optional_type(parameter.as_ref()))
+                .collect();
+            Json::object(vec![
+                ("kind", kind("Signature")),
+                ("parameters", Json::Array(parameters)),
+                ("result", optional_type(signature.result.as_ref())),
+            ])
+        }
+        None => Json::Null,
+    }
+}
+
+fn optional_type(annotation: Option<&TypeAnnotation>) -> Json {
+    annotation.map_or(Json::Null, type_annotation)
+}
+
+fn type_annotation(annotation: &TypeAnnotation) -> Json {
+    let types = |annotations: &[TypeAnnotation]| {
+        Json::Array(annotations.iter().map(type_annotation).collect())
+    };
+    match annotation {
+        TypeAnnotation::Array(element) => Json::object(vec![
+            ("kind", kind("Array")),
+            ("element", type_annotation(element)),
+        ]),
+        TypeAnnotation::Map(key, value) => Json::object(vec![
+            ("kind", kind("Map")),
+            ("key", type_annotation(key)),
+            ("value", type_annotation(value)),
+        ]),
+        TypeAnnotation::Set(element) => Json::object(vec![
+            ("kind", kind("Set")),
+            ("element", type_annotation(element)),
+        ]),
+        TypeAnnotation::Tuple(elements) => {
+            Json::object(vec![("kind", kind("Tuple")), ("elements", types(elements))])
+        }
+        TypeAnnotation::Function(parameters, result) => Json::object(vec![
+            ("kind", kind("Function")),
+            ("parameters", types(parameters)),
+            ("result", type_annotation(result)),
+        ]),
+        named => Json::String(named.to_string()),
+    }
+}
@@ -0,0 +1,328 @@
+use std::fmt::Display;
+
+use super::ImportError;
+
+const WIDTH: usize = 80;
+
+//members keep their order, so the same tree is always written the same way
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object(members: Vec<(&str, Json)>) -> Json {
+        Json::Object(
+            members
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(member, _)| member == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn pretty(&self) -> String {
+        let mut text = String::new();
+        self.write_pretty(&mut text, 0);
+        text
+    }
+
+    fn write_pretty(&self, text: &mut String, indent: usize) {
+        let line = self.to_string();
+        if indent * 2 + line.len() <= WIDTH || !matches!(self, Json::Array(_) | Json::Object(_)) {
+            text.push_str(&line);
+            return;
+        }
+        let inner = "  ".repeat(indent + 1);
+        let (open, close) = match self {
+            Json::Array(_) => ('[', ']'),
+            _ => ('{', '}'),
+        };
+        text.push(open);
+        let mut first = true;
+        let mut separate = |text: &mut String| {
+            if !first {
+                text.push(',');
+            }
+            first = false;
+            text.push('\n');
+            text.push_str(&inner);
+        };
+        match self {
+            Json::Array(elements) => {
+                for element in elements {
+                    separate(text);
+                    element.write_pretty(text, indent + 1);
+                }
+            }
+            Json::Object(members) => {
+                for (name, value) in members {
+                    separate(text);
+                    text.push_str(&quoted(name));
+                    text.push_str(": ");
+                    value.write_pretty(text, indent + 1);
+                }
+            }
+            _ => unreachable!("Scalars fit in a line"),
+        }
+        text.push('\n');
+        text.push_str(&"  ".repeat(indent));
+        text.push(close);
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write!(f, "{}", quoted(value)),
+            Json::Array(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (name, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", quoted(name), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+pub(super) fn quoted(value: &str) -> String {
+    let mut text = String::from('"');
+    for character in value.chars() {
+        match character {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            character if character.is_control() => {
+                text.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => text.push(character),
+        }
+    }
+    text.push('"');
+    text
+}
+
+//numbers are integers only, nothing in the tree has fractions
+pub fn parse(source: &str) -> Result<Json, ImportError> {
+    let mut reader = Reader {
+        source,
+        position: 0,
+    };
+    let value = reader.value()?;
+    reader.skip_whitespace();
+    match reader.peek() {
+        None => Ok(value),
+        Some(_) => Err(reader.error("unexpected text after the document")),
+    }
+}
+
+struct Reader<'a> {
+    source: &'a str,
+    position: usize, //in bytes
+}
+
+impl Reader<'_> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += character.len_utf8();
+        Some(character)
+    }
+
+    fn error(&self, message: &str) -> ImportError {
+        ImportError::Syntax {
+            message: message.to_string(),
+            position: self.position,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ImportError> {
+        self.skip_whitespace();
+        match self.bump() {
+            Some(character) if character == expected => Ok(()),
+            _ => Err(self.error(&format!("expected `{}`", expected))),
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json, ImportError> {
+        match self.source[self.position..].starts_with(word) {
+            true => {
+                self.position += word.len();
+                Ok(value)
+            }
+            false => Err(self.error("expected a value")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, ImportError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.keyword("null", Json::Null),
+            Some('t') => self.keyword("true", Json::Bool(true)),
+            Some('f') => self.keyword("false", Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => self.array(),
+            Some('{') => self.object(),
+            Some('-' | '0'..='9') => self.number(),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of the document")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, ImportError> {
+        let start = self.position;
+        if self.peek() == Some('-') {
+            self.position += 1;
+        }
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.position += 1;
+        }
+        if matches!(self.peek(), Some('.' | 'e' | 'E')) {
+            return Err(self.error("expected an integer"));
+        }
+        self.source[start..self.position]
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| self.error("invalid number"))
+    }
+
+    fn string(&mut self) -> Result<String, ImportError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(value),
+                Some('\\') => value.push(self.escaped()?),
+                Some(character) if character.is_control() => {
+                    return Err(self.error("control character in a string"));
+                }
+                Some(character) => value.push(character),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn escaped(&mut self) -> Result<char, ImportError> {
+        match self.bump() {
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some('/') => Ok('/'),
+            Some('b') => Ok('\u{8}'),
+            Some('f') => Ok('\u{c}'),
+            Some('n') => Ok('\n'),
+            Some('r') => Ok('\r'),
+            Some('t') => Ok('\t'),
+            Some('u') => {
+                let high = self.code_unit()?;
+                if !(0xD800..0xDC00).contains(&high) {
+                    return char::from_u32(high).ok_or_else(|| self.error("invalid escape"));
+                }
+                //characters outside of the basic plane are written as two escapes
+                if !self.source[self.position..].starts_with("\\u") {
+                    return Err(self.error("unpaired surrogate"));
+                }
+                self.position += 2;
+                let low = self.code_unit()?;
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(self.error("unpaired surrogate"));
+                }
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                char::from_u32(code).ok_or_else(|| self.error("invalid escape"))
+            }
+            _ => Err(self.error("invalid escape")),
+        }
+    }
+
+    fn code_unit(&mut self) -> Result<u32, ImportError> {
+        let digits = self
+            .source
+            .get(self.position..self.position + 4)
+            .ok_or_else(|| self.error("invalid escape"))?;
+        let code = u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid escape"))?;
+        self.position += 4;
+        Ok(code)
+    }
+
+    fn array(&mut self) -> Result<Json, ImportError> {
+        self.expect('[')?;
+        let mut elements = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Json::Array(elements));
+        }
+        loop {
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(elements)),
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, ImportError> {
+        self.expect('{')?;
+        let mut members = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.expect(':')?;
+            members.push((name, self.value()?));
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(members)),
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+}
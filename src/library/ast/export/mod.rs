@@ -0,0 +1,63 @@
+mod decode;
+mod encode;
+mod json;
+mod sexp;
+
+use std::fmt::Display;
+
+use crate::parser::{ParseError, Parser};
+
+use super::statements::Program;
+
+pub fn to_json(program: &Program) -> String {
+    encode::program(program).pretty()
+}
+
+pub fn to_sexp(program: &Program) -> String {
+    sexp::render(&encode::program(program))
+}
+
+//tokens get back their kinds and spans but not their trivia
+pub fn from_json(source: &str) -> Result<Program, ImportError> {
+    decode::program(&json::parse(source)?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    SExpression,
+}
+
+pub fn export_source(source: &str, format: ExportFormat) -> Result<String, Vec<String>> {
+    let mut parser = Parser::from_string(source);
+    let program = parser.parse_program();
+    if !parser.errors().is_empty() {
+        return Err(parser.errors().iter().map(ParseError::to_string).collect());
+    }
+    Ok(match format {
+        ExportFormat::Json => to_json(&program),
+        ExportFormat::SExpression => to_sexp(&program),
+    })
+}
+
+//`position` is in bytes, `path` leads to the node
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+    Syntax { message: String, position: usize },
+    Schema { path: String, message: String },
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::Syntax { message, position } => {
+                write!(f, "Invalid JSON at byte {}: {}", position, message)
+            }
+            ImportError::Schema { path, message } => {
+                write!(f, "Invalid tree at {}: {}", path, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
@@ -0,0 +1,111 @@
+use super::json::{Json, quoted};
+
+const WIDTH: usize = 80;
+
+//`(Infix "+" @1:1-1:5 :operator "+" ...)`, kinds of tokens, bytes of spans and empty members
+//are left out, JSON has all of them
+pub(super) fn render(json: &Json) -> String {
+    let mut text = String::new();
+    write(json, 0, &mut text);
+    text
+}
+
+fn write(json: &Json, indent: usize, text: &mut String) {
+    let line = flat(json);
+    if indent * 2 + line.len() <= WIDTH || !nested(json) {
+        text.push_str(&line);
+        return;
+    }
+    let inner = "  ".repeat(indent + 1);
+    match json {
+        Json::Object(members) => {
+            text.push_str(&head(json));
+            for (name, value) in members
+                .iter()
+                .filter(|(name, value)| is_member(name, value))
+            {
+                text.push('\n');
+                text.push_str(&inner);
+                text.push_str(&format!(":{} ", name));
+                write(value, indent + 1, text);
+            }
+            text.push(')');
+        }
+        Json::Array(elements) => {
+            text.push('(');
+            for (index, element) in elements.iter().enumerate() {
+                if index > 0 {
+                    text.push('\n');
+                    text.push_str(&inner);
+                }
+                write(element, indent + 1, text);
+            }
+            text.push(')');
+        }
+        _ => unreachable!("Scalars are not nested"),
+    }
+}
+
+fn flat(json: &Json) -> String {
+    match json {
+        Json::Null => "nil".to_string(),
+        Json::Bool(value) => value.to_string(),
+        Json::Number(value) => value.to_string(),
+        Json::String(value) => quoted(value),
+        Json::Array(elements) => {
+            let elements: Vec<String> = elements.iter().map(flat).collect();
+            format!("({})", elements.join(" "))
+        }
+        Json::Object(members) => {
+            let mut line = head(json);
+            for (name, value) in members
+                .iter()
+                .filter(|(name, value)| is_member(name, value))
+            {
+                line.push_str(&format!(" :{} {}", name, flat(value)));
+            }
+            line.push(')');
+            line
+        }
+    }
+}
+
+fn nested(json: &Json) -> bool {
+    matches!(json, Json::Array(_) | Json::Object(_))
+}
+
+fn head(json: &Json) -> String {
+    let mut head = String::from("(");
+    let token = json.get("token");
+    if let Some(Json::String(kind)) = json.get("kind") {
+        head.push_str(kind);
+    }
+    if let Some(Json::String(literal)) = token.and_then(|token| token.get("literal")) {
+        head.push(' ');
+        head.push_str(&quoted(literal));
+    }
+    //literals only have the span of their token
+    let span = json
+        .get("span")
+        .or_else(|| token.and_then(|token| token.get("span")));
+    if let Some(span) = span.and_then(self::span) {
+        head.push_str(" @");
+        head.push_str(&span);
+    }
+    head
+}
+
+fn is_member(name: &str, value: &Json) -> bool {
+    !matches!(name, "kind" | "token" | "span") && *value != Json::Null
+}
+
+fn span(span: &Json) -> Option<String> {
+    let position = |name: &str| match span.get(name) {
+        Some(Json::Array(parts)) => match parts.as_slice() {
+            [Json::Number(line), Json::Number(column)] => Some(format!("{}:{}", line, column)),
+            _ => None,
+        },
+        _ => None,
+    };
+    Some(format!("{}-{}", position("from")?, position("to")?))
+}
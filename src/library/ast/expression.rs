@@ -1,11 +1,16 @@
-use std::{any::Any, fmt::Display, rc::Rc};
+use std::{fmt::Display, rc::Rc};
 
 use crate::{
     join_collection, join_rc_collection,
+    lines::SourceSpan,
     tokens::{Token, TokenKind},
 };
 
-use super::{base::Node, statements::Statement};
+use super::{
+    base::{Node, NodeRef},
+    statements::Statement,
+    types::{Signature, display_parameters, display_result},
+};
 
 #[derive(Debug, Clone)]
 pub enum Expression {
@@ -13,17 +18,20 @@ pub enum Expression {
     IntegerLiteral(Rc<Token>),
     PrefixOperator {
         token: Rc<Token>,
+        span: SourceSpan,
         operator: PrefixOperatorType,
         right: Box<Expression>,
     },
     Infix {
         token: Rc<Token>,
+        span: SourceSpan,
         left: Box<Expression>,
         operator: InfixOperatorType,
         right: Box<Expression>,
     },
     Call {
         token: Rc<Token>,
+        span: SourceSpan,
         function: Box<Expression>, //Identifier or FunctionLiteral
         arguments: Vec<Expression>,
     },
@@ -32,42 +40,128 @@ pub enum Expression {
         value: bool,
     },
     StringLiteral(Rc<Token>),
+    SymbolLiteral(Rc<Token>),
     AIf {
         #[allow(dead_code)]
         token: Rc<Token>,
+        span: SourceSpan,
         condition: Box<Expression>,
         consequence: Box<Statement>,
         alternative: Option<Box<Statement>>,
     },
     FunctionLiteral {
         token: Rc<Token>,
+        span: SourceSpan,
         parameters: Rc<Vec<Expression>>, // Identifier
         body: Box<Statement>,
         name: Option<String>,
+        generator: bool, // fn*, calling it gives a generator instead of running the body
+        signature: Option<Rc<Signature>>,
     },
     MacroLiteral {
         token: Rc<Token>,
+        span: SourceSpan,
         parameters: Rc<Vec<Expression>>, // Identifier
         body: Box<Statement>,
     },
     ArrayLiteral {
         token: Rc<Token>,
+        span: SourceSpan,
         elements: Vec<Expression>,
     },
+    TupleLiteral {
+        token: Rc<Token>,
+        span: SourceSpan,
+        elements: Vec<Expression>,
+    },
+    SetLiteral {
+        token: Rc<Token>,
+        span: SourceSpan,
+        elements: Vec<Expression>,
+    },
+    //value of a let passed through decorators, which are evaluated in order before the value,
+    //then the last one is applied first
+    Decorated {
+        token: Rc<Token>,
+        span: SourceSpan,
+        decorators: Vec<Expression>,
+        value: Box<Expression>,
+    },
     Index {
         token: Rc<Token>,
+        span: SourceSpan,
         array: Box<Expression>,
         index: Box<Expression>,
     },
     MapLiteral {
         token: Rc<Token>,
+        span: SourceSpan,
         elements: Vec<(Expression, Expression)>,
     },
 }
 
+impl Expression {
+    //infix and index expressions give their operator, not their first token
+    pub fn token(&self) -> &Rc<Token> {
+        match self {
+            Expression::Identifier(token)
+            | Expression::IntegerLiteral(token)
+            | Expression::StringLiteral(token)
+            | Expression::SymbolLiteral(token) => token,
+            Expression::PrefixOperator { token, .. }
+            | Expression::Infix { token, .. }
+            | Expression::Call { token, .. }
+            | Expression::BooleanLiteral { token, .. }
+            | Expression::AIf { token, .. }
+            | Expression::FunctionLiteral { token, .. }
+            | Expression::MacroLiteral { token, .. }
+            | Expression::ArrayLiteral { token, .. }
+            | Expression::TupleLiteral { token, .. }
+            | Expression::SetLiteral { token, .. }
+            | Expression::Decorated { token, .. }
+            | Expression::Index { token, .. }
+            | Expression::MapLiteral { token, .. } => token,
+        }
+    }
+
+    pub fn operator_call(&self) -> Option<(&str, &Expression, &Expression)> {
+        match self {
+            Expression::Call {
+                token, arguments, ..
+            } => match (&token.kind, arguments.as_slice()) {
+                (TokenKind::Operator(operator), [left, right]) => Some((operator, left, right)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            Expression::Identifier(token)
+            | Expression::IntegerLiteral(token)
+            | Expression::StringLiteral(token)
+            | Expression::SymbolLiteral(token)
+            | Expression::BooleanLiteral { token, .. } => token.source_span(),
+            Expression::PrefixOperator { span, .. }
+            | Expression::Infix { span, .. }
+            | Expression::Call { span, .. }
+            | Expression::AIf { span, .. }
+            | Expression::FunctionLiteral { span, .. }
+            | Expression::MacroLiteral { span, .. }
+            | Expression::ArrayLiteral { span, .. }
+            | Expression::TupleLiteral { span, .. }
+            | Expression::SetLiteral { span, .. }
+            | Expression::Decorated { span, .. }
+            | Expression::Index { span, .. }
+            | Expression::MapLiteral { span, .. } => *span,
+        }
+    }
+}
+
 impl Node for Expression {
-    fn as_any(&self) -> &dyn Any {
-        self
+    fn as_node(&self) -> NodeRef<'_> {
+        NodeRef::Expression(self)
     }
 }
 impl Display for Expression {
@@ -88,20 +182,21 @@ impl Display for Expression {
                 }
             }
             Expression::PrefixOperator {
-                token: _,
-                operator,
-                right,
+                operator, right, ..
             } => write!(f, "({}{})", operator, right),
             Expression::Infix {
-                token: _,
                 left,
                 operator,
                 right,
+                ..
             } => write!(f, "({} {} {})", left, operator, right),
+            Expression::Call { .. } if let Some((operator, left, right)) = self.operator_call() => {
+                write!(f, "({} {} {})", left, operator, right)
+            }
             Expression::Call {
-                token: _,
                 function,
                 arguments,
+                ..
             } => {
                 let args = join_collection!(arguments, ", ");
                 write!(f, "{}({})", function, args)
@@ -114,11 +209,12 @@ impl Display for Expression {
                     _ => panic!("Invalid token type for StringLiteral: {:?}", real_type),
                 }
             }
+            Expression::SymbolLiteral(token) => write!(f, "{}", token.kind),
             Expression::AIf {
-                token: _,
                 condition,
                 consequence,
                 alternative,
+                ..
             } => {
                 let mut result = format!("if ({}){{", condition);
                 result.push_str(&consequence.to_string());
@@ -131,26 +227,46 @@ impl Display for Expression {
                 write!(f, "{}", result)
             }
             Expression::FunctionLiteral {
-                token: _,
                 parameters,
                 body,
                 name: _,
+                generator,
+                signature,
+                ..
             } => {
-                let params = join_rc_collection!(parameters, ", ");
-                write!(f, "fn({}){{ {} }}", params, body)
+                let params = display_parameters(parameters, signature);
+                let result = display_result(signature);
+                let star = if *generator { "*" } else { "" };
+                write!(f, "fn{}({}){}{{ {} }}", star, params, result, body)
             }
-            Expression::ArrayLiteral { token: _, elements } => {
+            Expression::ArrayLiteral { elements, .. } => {
                 let elems = join_collection!(elements, ", ");
                 write!(f, "[{}]", elems)
             }
-            Expression::Index {
-                token: _,
-                array,
-                index,
+            //comma keeps tuple of one element apart from a grouped expression
+            Expression::TupleLiteral { elements, .. } if elements.len() == 1 => {
+                write!(f, "({},)", elements[0])
+            }
+            Expression::TupleLiteral { elements, .. } => {
+                let elems = join_collection!(elements, ", ");
+                write!(f, "({})", elems)
+            }
+            Expression::SetLiteral { elements, .. } => {
+                let elems = join_collection!(elements, ", ");
+                write!(f, "#{{{}}}", elems)
+            }
+            Expression::Decorated {
+                decorators, value, ..
             } => {
+                for decorator in decorators {
+                    write!(f, "@{} ", decorator)?;
+                }
+                write!(f, "{}", value)
+            }
+            Expression::Index { array, index, .. } => {
                 write!(f, "({}[{}])", array, index)
             }
-            Expression::MapLiteral { token: _, elements } => {
+            Expression::MapLiteral { elements, .. } => {
                 let mut elems: Vec<String> = elements
                     .iter()
                     .map(|(key, value)| format!("{}: {}", key, value))
@@ -160,9 +276,7 @@ impl Display for Expression {
                 write!(f, "{{{}}}", elems_str)
             }
             Expression::MacroLiteral {
-                token: _,
-                parameters,
-                body,
+                parameters, body, ..
             } => {
                 let params = join_rc_collection!(parameters, ", ");
                 write!(f, "macro({}){{ {} }}", params, body)
@@ -173,6 +287,7 @@ impl Display for Expression {
 
 pub fn if_expression(
     token: Rc<Token>,
+    span: SourceSpan,
     condition: Expression,
     consequence: Statement,
     alternative: Option<Statement>,
@@ -192,6 +307,7 @@ pub fn if_expression(
     }
     Expression::AIf {
         token,
+        span,
         condition: Box::new(condition),
         consequence: Box::new(consequence),
         alternative: alternative.map(Box::new),
@@ -199,8 +315,10 @@ pub fn if_expression(
 }
 pub fn function_literal(
     token: Rc<Token>,
+    span: SourceSpan,
     parameters: Rc<Vec<Expression>>,
     body: Statement,
+    generator: bool,
 ) -> Expression {
     match body {
         Statement::Block { .. } => {}
@@ -215,14 +333,18 @@ pub fn function_literal(
     });
     Expression::FunctionLiteral {
         token,
+        span,
         parameters,
         body: Box::new(body),
         name: None,
+        generator,
+        signature: None,
     }
 }
 
 pub fn macro_literal(
     token: Rc<Token>,
+    span: SourceSpan,
     parameters: Rc<Vec<Expression>>,
     body: Statement,
 ) -> Expression {
@@ -239,6 +361,7 @@ pub fn macro_literal(
     });
     Expression::MacroLiteral {
         token,
+        span,
         parameters,
         body: Box::new(body),
     }
@@ -0,0 +1,304 @@
+use std::rc::Rc;
+
+use super::{
+    expression::Expression,
+    statements::{Program, Statement},
+};
+
+pub trait Fold {
+    fn fold_program(&mut self, program: Program) -> Program {
+        walk_program(self, program)
+    }
+
+    fn fold_statement(&mut self, statement: Statement) -> Statement {
+        walk_statement(self, statement)
+    }
+
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        walk_expression(self, expression)
+    }
+}
+
+pub trait Foldable {
+    fn fold_with<F: Fold + ?Sized>(self, folder: &mut F) -> Self;
+}
+
+impl Foldable for Program {
+    fn fold_with<F: Fold + ?Sized>(self, folder: &mut F) -> Self {
+        folder.fold_program(self)
+    }
+}
+
+impl Foldable for Statement {
+    fn fold_with<F: Fold + ?Sized>(self, folder: &mut F) -> Self {
+        folder.fold_statement(self)
+    }
+}
+
+impl Foldable for Expression {
+    fn fold_with<F: Fold + ?Sized>(self, folder: &mut F) -> Self {
+        folder.fold_expression(self)
+    }
+}
+
+pub fn walk_program<F: Fold + ?Sized>(folder: &mut F, program: Program) -> Program {
+    Program {
+        statements: fold_statements(folder, program.statements),
+    }
+}
+
+pub fn walk_statement<F: Fold + ?Sized>(folder: &mut F, statement: Statement) -> Statement {
+    match statement {
+        Statement::Let {
+            token,
+            span,
+            name,
+            value,
+            annotation,
+        } => Statement::Let {
+            token,
+            span,
+            name: folder.fold_expression(name),
+            value: folder.fold_expression(value),
+            annotation,
+        },
+        Statement::Return {
+            token,
+            span,
+            return_value,
+        } => Statement::Return {
+            token,
+            span,
+            return_value: folder.fold_expression(return_value),
+        },
+        Statement::Assign {
+            token,
+            span,
+            name,
+            value,
+        } => Statement::Assign {
+            token,
+            span,
+            name: folder.fold_expression(name),
+            value: folder.fold_expression(value),
+        },
+        Statement::Function {
+            token,
+            span,
+            name,
+            value,
+        } => Statement::Function {
+            token,
+            span,
+            name: folder.fold_expression(name),
+            value: folder.fold_expression(value),
+        },
+        Statement::Yield {
+            token,
+            span,
+            value,
+            delegate,
+        } => Statement::Yield {
+            token,
+            span,
+            value: folder.fold_expression(value),
+            delegate,
+        },
+        Statement::AExpression {
+            token,
+            span,
+            expression,
+        } => Statement::AExpression {
+            token,
+            span,
+            expression: folder.fold_expression(expression),
+        },
+        Statement::Block {
+            token,
+            span,
+            statements,
+        } => Statement::Block {
+            token,
+            span,
+            statements: Rc::new(fold_statements(folder, Rc::unwrap_or_clone(statements))),
+        },
+        Statement::Operator {
+            token,
+            span,
+            fixity,
+            name,
+            value,
+        } => Statement::Operator {
+            token,
+            span,
+            fixity,
+            name: folder.fold_expression(name),
+            value: folder.fold_expression(value),
+        },
+    }
+}
+
+pub fn walk_expression<F: Fold + ?Sized>(folder: &mut F, expression: Expression) -> Expression {
+    match expression {
+        Expression::Identifier(_)
+        | Expression::IntegerLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::SymbolLiteral(_)
+        | Expression::BooleanLiteral { .. } => expression,
+        Expression::PrefixOperator {
+            token,
+            span,
+            operator,
+            right,
+        } => Expression::PrefixOperator {
+            token,
+            span,
+            operator,
+            right: Box::new(folder.fold_expression(*right)),
+        },
+        Expression::Infix {
+            token,
+            span,
+            left,
+            operator,
+            right,
+        } => Expression::Infix {
+            token,
+            span,
+            left: Box::new(folder.fold_expression(*left)),
+            operator,
+            right: Box::new(folder.fold_expression(*right)),
+        },
+        Expression::Call {
+            token,
+            span,
+            function,
+            arguments,
+        } => Expression::Call {
+            token,
+            span,
+            function: Box::new(folder.fold_expression(*function)),
+            arguments: fold_expressions(folder, arguments),
+        },
+        Expression::AIf {
+            token,
+            span,
+            condition,
+            consequence,
+            alternative,
+        } => Expression::AIf {
+            token,
+            span,
+            condition: Box::new(folder.fold_expression(*condition)),
+            consequence: Box::new(folder.fold_statement(*consequence)),
+            alternative: alternative
+                .map(|alternative| Box::new(folder.fold_statement(*alternative))),
+        },
+        Expression::FunctionLiteral {
+            token,
+            span,
+            parameters,
+            body,
+            name,
+            generator,
+            signature,
+        } => Expression::FunctionLiteral {
+            token,
+            span,
+            parameters: Rc::new(fold_expressions(folder, Rc::unwrap_or_clone(parameters))),
+            body: Box::new(folder.fold_statement(*body)),
+            name,
+            generator,
+            signature,
+        },
+        Expression::MacroLiteral {
+            token,
+            span,
+            parameters,
+            body,
+        } => Expression::MacroLiteral {
+            token,
+            span,
+            parameters: Rc::new(fold_expressions(folder, Rc::unwrap_or_clone(parameters))),
+            body: Box::new(folder.fold_statement(*body)),
+        },
+        Expression::ArrayLiteral {
+            token,
+            span,
+            elements,
+        } => Expression::ArrayLiteral {
+            token,
+            span,
+            elements: fold_expressions(folder, elements),
+        },
+        Expression::TupleLiteral {
+            token,
+            span,
+            elements,
+        } => Expression::TupleLiteral {
+            token,
+            span,
+            elements: fold_expressions(folder, elements),
+        },
+        Expression::SetLiteral {
+            token,
+            span,
+            elements,
+        } => Expression::SetLiteral {
+            token,
+            span,
+            elements: fold_expressions(folder, elements),
+        },
+        Expression::Decorated {
+            token,
+            span,
+            decorators,
+            value,
+        } => Expression::Decorated {
+            token,
+            span,
+            decorators: fold_expressions(folder, decorators),
+            value: Box::new(folder.fold_expression(*value)),
+        },
+        Expression::Index {
+            token,
+            span,
+            array,
+            index,
+        } => Expression::Index {
+            token,
+            span,
+            array: Box::new(folder.fold_expression(*array)),
+            index: Box::new(folder.fold_expression(*index)),
+        },
+        Expression::MapLiteral {
+            token,
+            span,
+            elements,
+        } => Expression::MapLiteral {
+            token,
+            span,
+            elements: elements
+                .into_iter()
+                .map(|(key, value)| (folder.fold_expression(key), folder.fold_expression(value)))
+                .collect(),
+        },
+    }
+}
+
+fn fold_expressions<F: Fold + ?Sized>(
+    folder: &mut F,
+    expressions: Vec<Expression>,
+) -> Vec<Expression> {
+    expressions
+        .into_iter()
+        .map(|expression| folder.fold_expression(expression))
+        .collect()
+}
+
+fn fold_statements<F: Fold + ?Sized>(folder: &mut F, statements: Vec<Statement>) -> Vec<Statement> {
+    statements
+        .into_iter()
+        .map(|statement| folder.fold_statement(statement))
+        .collect()
+}
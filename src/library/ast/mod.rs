@@ -1,9 +1,19 @@
 pub(crate) mod base;
+pub(crate) mod concrete;
+mod equality;
+pub(crate) mod export;
 pub(crate) mod expression;
+pub mod fold;
 mod modify;
 pub(crate) mod statements;
+pub(crate) mod types;
+pub mod visit;
+pub mod visit_mut;
 
+pub use fold::{Fold, Foldable};
 pub use modify::modify;
+pub use visit::Visitor;
+pub use visit_mut::VisitorMut;
 
 #[cfg(test)]
 mod tests;
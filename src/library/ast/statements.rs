@@ -1,67 +1,191 @@
 use std::{fmt::Display, rc::Rc};
 
-use crate::{join_collection, join_rc_collection, tokens::Token};
+use crate::{
+    join_collection, join_rc_collection,
+    lines::SourceSpan,
+    tokens::{Token, TokenKind},
+};
 
-use super::{base::Node, expression::Expression};
+use super::{
+    base::{Node, NodeRef},
+    expression::Expression,
+    types::{TypeAnnotation, display_parameters, display_result},
+};
 
 #[derive(Debug, Clone)]
 pub enum Statement {
     Let {
         token: Rc<Token>,
+        span: SourceSpan,
         name: Expression,
         value: Expression,
+        annotation: Option<TypeAnnotation>, // let x: Int = ...
     },
     Return {
         token: Rc<Token>,
+        span: SourceSpan,
         return_value: Expression,
     },
+    Assign {
+        token: Rc<Token>,
+        span: SourceSpan,
+        name: Expression,
+        value: Expression,
+    },
+    Function {
+        token: Rc<Token>,
+        span: SourceSpan,
+        name: Expression,
+        value: Expression, // FunctionLiteral
+    },
+    Yield {
+        token: Rc<Token>,
+        span: SourceSpan,
+        value: Expression,
+        delegate: bool, // yield* passes on all values of another generator
+    },
     AExpression {
         #[allow(dead_code)]
         token: Rc<Token>,
+        span: SourceSpan,
         expression: Expression,
     },
     Block {
         token: Rc<Token>,
+        span: SourceSpan,
         statements: Rc<Vec<Statement>>,
     },
+    Operator {
+        token: Rc<Token>, // infix or infixr
+        span: SourceSpan,
+        fixity: Fixity,
+        name: Expression, // identifier named as the operator, `<+>`
+        value: Expression,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixity {
+    pub precedence: u8,
+    pub associativity: Associativity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+impl Display for Fixity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.associativity {
+            Associativity::Left => write!(f, "infix {}", self.precedence),
+            Associativity::Right => write!(f, "infixr {}", self.precedence),
+        }
+    }
+}
+
+impl Statement {
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            Statement::Let { span, .. }
+            | Statement::Return { span, .. }
+            | Statement::Assign { span, .. }
+            | Statement::Function { span, .. }
+            | Statement::Yield { span, .. }
+            | Statement::AExpression { span, .. }
+            | Statement::Block { span, .. }
+            | Statement::Operator { span, .. } => *span,
+        }
+    }
 }
 
 impl Node for Statement {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    fn as_node(&self) -> NodeRef<'_> {
+        NodeRef::Statement(self)
     }
 }
 
 impl Display for Statement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Statement::Let {
+                name,
+                value,
+                annotation: Some(annotation),
+                ..
+            } => write!(f, "let {}: {}={}", name, annotation, value),
             Statement::Let { name, value, .. } => {
                 write!(f, "let {}={}", name, value)
             }
+            Statement::Assign { name, value, .. } => {
+                write!(f, "{}={}", name, value)
+            }
+            Statement::Function { name, value, .. } => match value {
+                Expression::FunctionLiteral {
+                    parameters,
+                    body,
+                    generator,
+                    signature,
+                    ..
+                } => {
+                    let params = display_parameters(parameters, signature);
+                    let result = display_result(signature);
+                    let star = if *generator { "*" } else { "" };
+                    write!(f, "fn{} {}({}){}{{ {} }}", star, name, params, result, body)
+                }
+                _ => write!(f, "fn {}={}", name, value),
+            },
             Statement::Return {
                 token,
                 return_value,
+                ..
             } => write!(f, "{} {}", token.short(), return_value),
-            Statement::AExpression {
-                token: _,
-                expression,
-            } => write!(f, "{}", expression),
-            Statement::Block {
-                token: _,
-                statements,
-            } => write!(f, "{}", join_rc_collection!(statements, "\n")),
+            Statement::Yield {
+                value, delegate, ..
+            } => {
+                let star = if *delegate { "*" } else { "" };
+                write!(f, "yield{} {}", star, value)
+            }
+            Statement::AExpression { expression, .. } => write!(f, "{}", expression),
+            Statement::Block { statements, .. } => {
+                write!(f, "{}", join_rc_collection!(statements, "\n"))
+            }
+            Statement::Operator {
+                fixity,
+                name,
+                value,
+                ..
+            } => write!(f, "{} {} = {}", fixity, name, value),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Program {
     pub statements: Vec<Statement>,
 }
 
+pub const STRICT_DIRECTIVE: &str = "use strict";
+
+impl Program {
+    //`"use strict"` as the first statement
+    pub fn is_strict(&self) -> bool {
+        match self.statements.first() {
+            Some(Statement::AExpression {
+                expression: Expression::StringLiteral(token),
+                ..
+            }) => {
+                matches!(&token.kind, TokenKind::StringLiteral(value) if value == STRICT_DIRECTIVE)
+            }
+            _ => false,
+        }
+    }
+}
+
 impl Node for Program {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    fn as_node(&self) -> NodeRef<'_> {
+        NodeRef::Program(self)
     }
 }
 impl Display for Program {
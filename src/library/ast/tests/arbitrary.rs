@@ -0,0 +1,459 @@
+use std::rc::Rc;
+
+use crate::{
+    ast::{
+        expression::{Expression, InfixOperatorType, PrefixOperatorType},
+        statements::{Associativity, Fixity, Program, Statement},
+        types::{Signature, TypeAnnotation},
+    },
+    lines::SourceSpan,
+    tokens::{Token, TokenKind},
+};
+
+const NAMES: [&str; 10] = [
+    "a", "b", "x", "y", "f", "value", "items", "total", "ok", "_tmp",
+];
+const OPERATOR_CHARACTERS: [char; 15] = [
+    '+', '-', '*', '/', '<', '>', '=', '!', '%', '&', '|', '^', '~', '?', '.',
+];
+const BUILTIN_OPERATORS: [&str; 11] = ["=", "==", "!=", "!", "+", "-", "*", "/", "<", ">", "->"];
+
+//same seed gives the same tree, so a failing one can be made again
+pub(super) struct Arbitrary {
+    state: u64,
+    operators: Vec<String>,
+}
+
+impl Arbitrary {
+    pub(super) fn new(seed: u64) -> Self {
+        Arbitrary {
+            state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+            operators: vec![],
+        }
+    }
+
+    //xorshift64*
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub(super) fn program(&mut self) -> Program {
+        let count = self.below(6);
+        Program {
+            statements: (0..count).map(|_| self.statement(3, false)).collect(),
+        }
+    }
+
+    fn block(&mut self, depth: usize, yields: bool) -> Statement {
+        let count = if depth == 0 { 0 } else { self.below(4) };
+        Statement::Block {
+            token: token(TokenKind::LeftBrace),
+            span: SourceSpan::default(),
+            statements: Rc::new((0..count).map(|_| self.statement(depth, yields)).collect()),
+        }
+    }
+
+    //`yield` is only valid in generators and in `if`s directly in them
+    fn statement(&mut self, depth: usize, yields: bool) -> Statement {
+        let depth = depth.saturating_sub(1);
+        match self.below(if yields { 9 } else { 8 }) {
+            0 => self.let_statement(depth),
+            1 => self.decorated_let(depth),
+            2 => Statement::Return {
+                token: token(TokenKind::Return),
+                span: SourceSpan::default(),
+                return_value: self.expression(depth),
+            },
+            3 => Statement::Assign {
+                token: token(TokenKind::Assign),
+                span: SourceSpan::default(),
+                name: self.identifier(),
+                value: self.expression(depth),
+            },
+            4 => {
+                let name = self.identifier();
+                let mut value = self.function(depth);
+                if let Expression::FunctionLiteral { name: literal, .. } = &mut value {
+                    *literal = Some(name.to_string());
+                }
+                Statement::Function {
+                    token: token(TokenKind::Function),
+                    span: SourceSpan::default(),
+                    name,
+                    value,
+                }
+            }
+            5 => self.operator_declaration(depth),
+            6 if yields => {
+                let expression = self.if_expression(depth, true);
+                expression_statement(expression)
+            }
+            8 => Statement::Yield {
+                token: token(TokenKind::Yield),
+                span: SourceSpan::default(),
+                delegate: self.chance(30),
+                value: self.expression(depth),
+            },
+            _ => {
+                let expression = self.expression(depth);
+                expression_statement(expression)
+            }
+        }
+    }
+
+    fn let_statement(&mut self, depth: usize) -> Statement {
+        let destructuring = self.chance(25);
+        let name = match destructuring {
+            true => self.pattern(2),
+            false => self.identifier(),
+        };
+        let annotation = self.chance(25).then(|| self.type_annotation(2));
+        let mut value = self.expression(depth);
+        //only a function bound to a single name takes the name
+        if let (false, Expression::FunctionLiteral { name: literal, .. }) =
+            (destructuring, &mut value)
+        {
+            *literal = Some(name.to_string());
+        }
+        Statement::Let {
+            token: token(TokenKind::Let),
+            span: SourceSpan::default(),
+            name,
+            value,
+            annotation,
+        }
+    }
+
+    fn decorated_let(&mut self, depth: usize) -> Statement {
+        let count = 1 + self.below(3);
+        let decorators = (0..count).map(|_| self.expression(depth)).collect();
+        let name = self.identifier();
+        let annotation = self.chance(25).then(|| self.type_annotation(2));
+        Statement::Let {
+            token: token(TokenKind::Let),
+            span: SourceSpan::default(),
+            name,
+            value: Expression::Decorated {
+                token: token(TokenKind::At),
+                span: SourceSpan::default(),
+                decorators,
+                value: Box::new(self.expression(depth)),
+            },
+            annotation,
+        }
+    }
+
+    fn operator_declaration(&mut self, depth: usize) -> Statement {
+        let name = loop {
+            let length = 1 + self.below(3);
+            let name: String = (0..length)
+                .map(|_| *self.pick(&OPERATOR_CHARACTERS))
+                .collect();
+            if !name.contains("//")
+                && !BUILTIN_OPERATORS.contains(&name.as_str())
+                && !self.operators.contains(&name)
+            {
+                break name;
+            }
+        };
+        //the parser knows the operator already in its own value
+        self.operators.push(name.clone());
+        let precedence = match self.below(3) {
+            0 => *self.pick(&[40, 50, 60, 70]),
+            _ => 1 + self.below(79) as u8,
+        };
+        let associativity = match self.chance(50) {
+            true => Associativity::Left,
+            false => Associativity::Right,
+        };
+        Statement::Operator {
+            token: token(TokenKind::Identifier(match associativity {
+                Associativity::Left => "infix".to_string(),
+                Associativity::Right => "infixr".to_string(),
+            })),
+            span: SourceSpan::default(),
+            fixity: Fixity {
+                precedence,
+                associativity,
+            },
+            name: Expression::Identifier(token(TokenKind::Identifier(name))),
+            value: self.expression(depth),
+        }
+    }
+
+    fn expression(&mut self, depth: usize) -> Expression {
+        if depth == 0 || self.chance(20) {
+            return self.literal();
+        }
+        let depth = depth - 1;
+        match self.below(14) {
+            0 => {
+                let (kind, operator) = match self.chance(50) {
+                    true => (TokenKind::Negation, PrefixOperatorType::Bang),
+                    false => (TokenKind::Minus, PrefixOperatorType::Minus),
+                };
+                Expression::PrefixOperator {
+                    token: token(kind),
+                    span: SourceSpan::default(),
+                    operator,
+                    right: Box::new(self.expression(depth)),
+                }
+            }
+            1 | 2 => {
+                let left = self.expression(depth);
+                let (kind, operator) = self.pick(&INFIX).clone();
+                Expression::Infix {
+                    token: token(kind),
+                    span: SourceSpan::default(),
+                    left: Box::new(left),
+                    operator,
+                    right: Box::new(self.expression(depth)),
+                }
+            }
+            3 if !self.operators.is_empty() => {
+                let operator = self.pick(&self.operators.clone()).clone();
+                let left = self.expression(depth);
+                let right = self.expression(depth);
+                Expression::Call {
+                    token: token(TokenKind::Operator(operator.clone())),
+                    span: SourceSpan::default(),
+                    function: Box::new(Expression::Identifier(token(TokenKind::Identifier(
+                        operator,
+                    )))),
+                    arguments: vec![left, right],
+                }
+            }
+            4 => {
+                let function = self.expression(depth);
+                Expression::Call {
+                    token: token(TokenKind::LeftParen),
+                    span: SourceSpan::default(),
+                    function: Box::new(function),
+                    arguments: self.expressions(depth),
+                }
+            }
+            5 => {
+                let array = self.expression(depth);
+                Expression::Index {
+                    token: token(TokenKind::RightBracket),
+                    span: SourceSpan::default(),
+                    array: Box::new(array),
+                    index: Box::new(self.expression(depth)),
+                }
+            }
+            6 => self.if_expression(depth, false),
+            7 => self.function(depth),
+            8 => {
+                let parameters = self.parameters();
+                Expression::MacroLiteral {
+                    token: token(TokenKind::Macro),
+                    span: SourceSpan::default(),
+                    parameters: Rc::new(parameters),
+                    body: Box::new(self.block(depth, false)),
+                }
+            }
+            9 => Expression::ArrayLiteral {
+                token: token(TokenKind::RightBracket),
+                span: SourceSpan::default(),
+                elements: self.expressions(depth),
+            },
+            10 => Expression::TupleLiteral {
+                token: token(TokenKind::LeftParen),
+                span: SourceSpan::default(),
+                elements: self.expressions(depth),
+            },
+            11 => Expression::SetLiteral {
+                token: token(TokenKind::SetStart),
+                span: SourceSpan::default(),
+                elements: self.expressions(depth),
+            },
+            12 => {
+                let count = self.below(3);
+                let elements = (0..count)
+                    .map(|_| (self.expression(depth), self.expression(depth)))
+                    .collect();
+                Expression::MapLiteral {
+                    token: token(TokenKind::RightBrace),
+                    span: SourceSpan::default(),
+                    elements,
+                }
+            }
+            _ => self.literal(),
+        }
+    }
+
+    fn expressions(&mut self, depth: usize) -> Vec<Expression> {
+        let count = self.below(4);
+        (0..count).map(|_| self.expression(depth)).collect()
+    }
+
+    fn literal(&mut self) -> Expression {
+        match self.below(5) {
+            0 => self.identifier(),
+            1 => {
+                let value = match self.chance(80) {
+                    true => self.below(100) as u32,
+                    false => self.next() as u32,
+                };
+                Expression::IntegerLiteral(token(TokenKind::Integer(i64::from(value))))
+            }
+            2 => {
+                let length = self.below(6);
+                let value = (0..length)
+                    .map(|_| *self.pick(&['a', 'Z', ' ', ':', '/', '{', '-', '#', '\\', '\n']))
+                    .collect();
+                Expression::StringLiteral(token(TokenKind::StringLiteral(value)))
+            }
+            3 => {
+                let name = self.pick(&NAMES).to_string();
+                Expression::SymbolLiteral(token(TokenKind::Symbol(name)))
+            }
+            _ => {
+                let value = self.chance(50);
+                Expression::BooleanLiteral {
+                    token: token(if value {
+                        TokenKind::True
+                    } else {
+                        TokenKind::False
+                    }),
+                    value,
+                }
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Expression {
+        let name = self.pick(&NAMES).to_string();
+        Expression::Identifier(token(TokenKind::Identifier(name)))
+    }
+
+    fn pattern(&mut self, depth: usize) -> Expression {
+        let count = self.below(4);
+        let elements = (0..count)
+            .map(|_| match depth > 0 && self.chance(25) {
+                true => self.pattern(depth - 1),
+                false => self.identifier(),
+            })
+            .collect();
+        Expression::TupleLiteral {
+            token: token(TokenKind::LeftParen),
+            span: SourceSpan::default(),
+            elements,
+        }
+    }
+
+    fn if_expression(&mut self, depth: usize, yields: bool) -> Expression {
+        let condition = self.expression(depth);
+        let consequence = self.block(depth, yields);
+        let alternative = self.chance(50).then(|| Box::new(self.block(depth, yields)));
+        Expression::AIf {
+            token: token(TokenKind::If),
+            span: SourceSpan::default(),
+            condition: Box::new(condition),
+            consequence: Box::new(consequence),
+            alternative,
+        }
+    }
+
+    fn function(&mut self, depth: usize) -> Expression {
+        let generator = self.chance(30);
+        let parameters = self.parameters();
+        let annotations: Vec<_> = parameters
+            .iter()
+            .map(|_| self.chance(30).then(|| self.type_annotation(2)))
+            .collect();
+        let result = self.chance(30).then(|| self.type_annotation(2));
+        //functions without any annotation have no signature
+        let signature = (result.is_some() || annotations.iter().any(Option::is_some)).then(|| {
+            Rc::new(Signature {
+                parameters: annotations,
+                result,
+            })
+        });
+        Expression::FunctionLiteral {
+            token: token(TokenKind::Function),
+            span: SourceSpan::default(),
+            parameters: Rc::new(parameters),
+            body: Box::new(self.block(depth, generator)),
+            name: None,
+            generator,
+            signature,
+        }
+    }
+
+    fn parameters(&mut self) -> Vec<Expression> {
+        let count = self.below(4);
+        (0..count).map(|_| self.identifier()).collect()
+    }
+
+    fn type_annotation(&mut self, depth: usize) -> TypeAnnotation {
+        let named = [
+            TypeAnnotation::Any,
+            TypeAnnotation::Int,
+            TypeAnnotation::String,
+            TypeAnnotation::Bool,
+            TypeAnnotation::Char,
+            TypeAnnotation::Symbol,
+            TypeAnnotation::Null,
+        ];
+        if depth == 0 || self.chance(50) {
+            return self.pick(&named).clone();
+        }
+        let depth = depth - 1;
+        match self.below(5) {
+            0 => TypeAnnotation::Array(Box::new(self.type_annotation(depth))),
+            1 => TypeAnnotation::Map(
+                Box::new(self.type_annotation(depth)),
+                Box::new(self.type_annotation(depth)),
+            ),
+            2 => TypeAnnotation::Set(Box::new(self.type_annotation(depth))),
+            3 => {
+                let count = self.below(3);
+                TypeAnnotation::Tuple((0..count).map(|_| self.type_annotation(depth)).collect())
+            }
+            _ => {
+                let count = self.below(3);
+                let parameters = (0..count).map(|_| self.type_annotation(depth)).collect();
+                TypeAnnotation::Function(parameters, Box::new(self.type_annotation(depth)))
+            }
+        }
+    }
+}
+
+const INFIX: [(TokenKind, InfixOperatorType); 8] = [
+    (TokenKind::Plus, InfixOperatorType::Plus),
+    (TokenKind::Minus, InfixOperatorType::Minus),
+    (TokenKind::Asterisk, InfixOperatorType::Multiply),
+    (TokenKind::Slash, InfixOperatorType::Divide),
+    (TokenKind::LessThen, InfixOperatorType::LessThan),
+    (TokenKind::GreaterThen, InfixOperatorType::GreaterThan),
+    (TokenKind::Equal, InfixOperatorType::Equal),
+    (TokenKind::Inequal, InfixOperatorType::NotEqual),
+];
+
+fn token(kind: TokenKind) -> Rc<Token> {
+    Rc::new(Token::at(SourceSpan::default(), kind))
+}
+
+fn expression_statement(expression: Expression) -> Statement {
+    Statement::AExpression {
+        token: expression.token().clone(),
+        span: SourceSpan::default(),
+        expression,
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    ast::concrete::{SyntaxElement, SyntaxKind, SyntaxNode},
+    ast::statements::Statement,
+    parser::{Parser, parse_lossless},
+};
+
+#[test]
+fn prints_source_back() {
+    let sources = [
+        "let a = 1;",
+        "  let   add = fn(a, b) {\n\ta + b // sum\n};\n\n// call it\nadd(0x1F, 2) ",
+        "let s = \"two\nlines\";\r\nlet m = {:ok: #{1, 2}, \"k\": [a[1]]};\r\n",
+        "let $x = 1 . 2;",
+        "",
+        "   \n// only a comment",
+        "let a = 1; * 2 ) ] let b = [1, 2]\n",
+    ];
+    for source in sources {
+        assert_eq!(parse_lossless(source).0.text(), source);
+    }
+}
+
+#[test]
+fn converts_to_program() {
+    let source = "let a = 1; // one\nfn add(a, b) { a + b }\n\nadd(a, 2) * 3";
+    let (tree, errors) = parse_lossless(source);
+    assert!(errors.is_empty(), "{errors:?}");
+    let mut parser = Parser::from_string(source);
+    assert_eq!(
+        tree.to_program().to_string(),
+        parser.parse_program().to_string()
+    );
+    let statements: Vec<&SyntaxNode> = tree.nodes().collect();
+    assert_eq!(statements.len(), 3);
+    assert_eq!(statements[0].text(), "let a = 1;");
+    assert_eq!(statements[1].text(), " // one\nfn add(a, b) { a + b }");
+    assert!(matches!(
+        statements[1].to_statement(),
+        Some(Statement::Function { .. })
+    ));
+    assert_eq!(
+        statements[2].to_expression().unwrap().to_string(),
+        "(add(a, 2) * 3)"
+    );
+}
+
+#[test]
+fn groups_brackets() {
+    let (tree, _) = parse_lossless("f([1, (2)], {})");
+    let statement = tree.nodes().next().unwrap();
+    let call = statement.nodes().next().unwrap();
+    assert!(matches!(call.kind, SyntaxKind::Group));
+    assert_eq!(call.text(), "([1, (2)], {})");
+    let inner: Vec<String> = call.nodes().map(SyntaxNode::text).collect();
+    assert_eq!(inner, vec!["[1, (2)]", " {}"]);
+    assert_eq!(call.nodes().next().unwrap().nodes().count(), 1);
+}
+
+#[test]
+fn keeps_invalid_statements() {
+    let (tree, errors) = parse_lossless("let a = 1; * 2; let b = 3;");
+    assert!(!errors.is_empty());
+    let kinds: Vec<&SyntaxKind> = tree.nodes().map(|node| &node.kind).collect();
+    assert!(matches!(kinds[1], SyntaxKind::Invalid));
+    assert_eq!(tree.nodes().nth(1).unwrap().text(), " * 2;");
+    assert_eq!(tree.to_program().statements.len(), 2);
+    assert!(matches!(
+        tree.children.last(),
+        Some(SyntaxElement::Token(token)) if token.source_text().is_empty()
+    ));
+}
+
+#[test]
+fn same_errors_as_parser() {
+    let sources = [
+        "(   <+>$ /",
+        "let a = 1; * 2; let b = 3;",
+        "let = ; fn f( { 1 } ) ; return",
+        "if (a { b } else } let c = [1, 2; c",
+        "f(1, , 2); let 1 = 2; {:a: }",
+    ];
+    for source in sources {
+        let (tree, errors) = parse_lossless(source);
+        let mut parser = Parser::from_string(source);
+        let program = parser.parse_program();
+        let lossless: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        let expected: Vec<String> = parser.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(lossless, expected, "for {}", source);
+        assert_eq!(tree.text(), source);
+        assert!(tree.to_program() == program, "for {}", source);
+    }
+}
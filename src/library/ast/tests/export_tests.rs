@@ -0,0 +1,230 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    ast::export::{ExportFormat, ImportError, export_source, from_json, to_json, to_sexp},
+    ast::statements::Program,
+    code::compile,
+    evaluator::evaluate,
+    object::Environment,
+    parser::Parser,
+    vm::VM,
+};
+
+fn parse(source: &str) -> Program {
+    let mut parser = Parser::from_string(source);
+    let program = parser.parse_program();
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+    program
+}
+
+fn evaluated(program: &Program) -> String {
+    let environment = Rc::new(RefCell::new(Environment::new()));
+    evaluate(program, environment).to_string()
+}
+
+fn compiled(program: Program) -> String {
+    let mut vm = VM::new(compile(program).expect("Program compiles"));
+    vm.run();
+    vm.last_poped_stack_element()
+        .expect("Program leaves a value")
+        .to_string()
+}
+
+#[test]
+fn imports_what_was_exported() {
+    let sources = [
+        "let a: Int = 1; a = a + 2; return -a;",
+        "let (x, y) = (1, \"two\\n\"); let s = #{:ok, (x,)}; {\"k\": [s, !true]}[\"k\"][0]",
+        "fn* gen(n: Int) -> Int { yield n; yield* other(); } @memo @trace(1) let f = fn() { 1 };",
+        "let m = macro(a, b) { quote(unquote(a) < unquote(b)) }; if (x > 1) { x } else { y == z }",
+        "infixr 20 <+> = fn(a, b) { a * b / 2 != b }; 1 <+> 2 <+> 3",
+        "",
+    ];
+    for source in sources {
+        let program = parse(source);
+        let json = to_json(&program);
+        let imported = from_json(&json).unwrap_or_else(|error| panic!("{}\n{}", error, json));
+        assert_eq!(to_json(&imported), json);
+        assert_eq!(imported.to_string(), program.to_string());
+        assert_eq!(to_sexp(&imported), to_sexp(&program));
+    }
+}
+
+#[test]
+fn imported_programs_run() {
+    let source = r#"
+        fn fib(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } }
+        infix 60 <+> = fn(a, b) { a * 10 + b };
+        let (a, b) = (fib(10), len("four"));
+        a <+> b
+    "#;
+    let imported = from_json(&to_json(&parse(source))).unwrap();
+    assert_eq!(evaluated(&imported), "554");
+    assert_eq!(compiled(imported), "554");
+}
+
+#[test]
+fn imports_written_by_hand() {
+    let json = r#"{
+        "kind": "Program",
+        "statements": [{
+            "kind": "ExpressionStatement",
+            "token": {"kind": "Integer", "literal": "2", "span": {}},
+            "span": {},
+            "expression": {
+                "kind": "Infix",
+                "token": {"kind": "Asterisk", "literal": "*", "span": {}},
+                "span": {},
+                "operator": "*",
+                "left": {"kind": "IntegerLiteral", "token": {"kind": "Integer", "literal": "2", "span": {}}},
+                "right": {"kind": "IntegerLiteral", "token": {"kind": "Integer", "literal": "21", "span": {}}}
+            }
+        }]
+    }"#;
+    let program = from_json(json).unwrap();
+    assert_eq!(program.to_string(), "(2 * 21)");
+    assert_eq!(evaluated(&program), "42");
+    assert_eq!(compiled(program), "42");
+}
+
+#[test]
+fn exports_json() {
+    let expected = r#"{
+  "kind": "Program",
+  "statements": [
+    {
+      "kind": "ExpressionStatement",
+      "token": {
+        "kind": "Negation",
+        "literal": "!",
+        "span": {"from": [1, 1], "to": [1, 1], "bytes": [0, 1]}
+      },
+      "span": {"from": [1, 1], "to": [1, 2], "bytes": [0, 2]},
+      "expression": {
+        "kind": "Prefix",
+        "token": {
+          "kind": "Negation",
+          "literal": "!",
+          "span": {"from": [1, 1], "to": [1, 1], "bytes": [0, 1]}
+        },
+        "span": {"from": [1, 1], "to": [1, 2], "bytes": [0, 2]},
+        "operator": "!",
+        "right": {
+          "kind": "Identifier",
+          "token": {
+            "kind": "Identifier",
+            "literal": "a",
+            "span": {"from": [1, 2], "to": [1, 2], "bytes": [1, 2]}
+          }
+        }
+      }
+    }
+  ]
+}"#;
+    assert_eq!(to_json(&parse("!a")), expected);
+}
+
+#[test]
+fn exports_s_expressions() {
+    let source = "let total = sum([1, 2], :all);\nfn sum(xs, mode) { xs[0] + xs[1] }";
+    let expected = indoc::indoc! {r#"
+        (Program
+          :statements ((Let "let" @1:1-1:30
+              :name (Identifier "total" @1:5-1:9)
+              :value (Call "(" @1:13-1:29
+                :function (Identifier "sum" @1:13-1:15)
+                :arguments ((ArrayLiteral "]" @1:17-1:22
+                    :elements ((IntegerLiteral "1" @1:18-1:18) (IntegerLiteral "2" @1:21-1:21)))
+                  (SymbolLiteral ":all" @1:25-1:28))))
+            (Function "function" @2:1-2:34
+              :name (Identifier "sum" @2:4-2:6)
+              :value (FunctionLiteral "function" @2:1-2:34
+                :name "sum"
+                :generator false
+                :parameters ((Identifier "xs" @2:8-2:9) (Identifier "mode" @2:12-2:15))
+                :body (Block "{" @2:18-2:34
+                  :statements ((ExpressionStatement "xs" @2:20-2:32
+                      :expression (Infix "+" @2:20-2:32
+                        :operator "+"
+                        :left (Index "]" @2:20-2:24
+                          :array (Identifier "xs" @2:20-2:21)
+                          :index (IntegerLiteral "0" @2:23-2:23))
+                        :right (Index "]" @2:28-2:32
+                          :array (Identifier "xs" @2:28-2:29)
+                          :index (IntegerLiteral "1" @2:31-2:31))))))))))"#};
+    assert_eq!(
+        export_source(source, ExportFormat::SExpression),
+        Ok(expected.to_string())
+    );
+}
+
+#[test]
+fn reports_invalid_documents() {
+    let syntax = |message: &str, position| ImportError::Syntax {
+        message: message.to_string(),
+        position,
+    };
+    let schema = |path: &str, message: &str| ImportError::Schema {
+        path: path.to_string(),
+        message: message.to_string(),
+    };
+    let statement = |expression: &str| {
+        format!(
+            r#"{{"kind": "Program", "statements": [{{"kind": "ExpressionStatement", "token": {{"kind": "True", "literal": "true", "span": {{}}}}, "span": {{}}, "expression": {}}}]}}"#,
+            expression
+        )
+    };
+    let cases = [
+        ("[1, 2", syntax("expected `,` or `]`", 5)),
+        (r#"{"kind": 1.5}"#, syntax("expected an integer", 10)),
+        (
+            r#"{"kind": "Program"} x"#,
+            syntax("unexpected text after the document", 20),
+        ),
+        (
+            r#"{"kind": "Statement"}"#,
+            schema("program", "expected a `Program`"),
+        ),
+        (
+            r#"{"kind": "Program"}"#,
+            schema("program", "missing member `statements`"),
+        ),
+        (
+            &statement(
+                r#"{"kind": "Identifier", "token": {"kind": "Integer", "literal": "1", "span": {}}}"#,
+            ),
+            schema(
+                "program.statements[0].expression.token",
+                "expected a token of kind `Identifier`",
+            ),
+        ),
+        (
+            &statement(
+                r#"{"kind": "Identifier", "token": {"kind": "Let", "literal": "var", "span": {}}}"#,
+            ),
+            schema(
+                "program.statements[0].expression.token.literal",
+                "expected `let`",
+            ),
+        ),
+        (
+            &statement(r#"{"kind": "Lambda"}"#),
+            schema(
+                "program.statements[0].expression",
+                "unknown expression `Lambda`",
+            ),
+        ),
+        (
+            &statement(
+                r#"{"kind": "Prefix", "token": {"kind": "Minus", "literal": "-", "span": {"from": [1, 1]}}, "span": {}, "operator": "~", "right": null}"#,
+            ),
+            schema(
+                "program.statements[0].expression.token.span",
+                "expected both `from` and `to`",
+            ),
+        ),
+    ];
+    for (json, expected) in cases {
+        assert_eq!(from_json(json).map(|_| ()), Err(expected), "{}", json);
+    }
+}
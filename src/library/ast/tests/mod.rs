@@ -1 +1,6 @@
+mod arbitrary;
+mod concrete_tests;
+mod export_tests;
 mod modify_testing;
+mod roundtrip_tests;
+mod visit_tests;
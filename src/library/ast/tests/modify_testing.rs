@@ -2,17 +2,20 @@ use std::{rc::Rc, vec};
 
 use crate::{
     ast::{
-        base::Node,
         expression::{self, Expression, PrefixOperatorType},
         modify,
         statements::{Program, Statement},
     },
+    lines::SourceSpan,
+    parser::Parser,
     tokens::{Token, TokenKind},
 };
 
 fn one() -> Expression {
     let token = Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(1),
     };
     return Expression::IntegerLiteral(Rc::new(token));
@@ -21,6 +24,8 @@ fn one() -> Expression {
 fn two() -> Expression {
     let token = Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(2),
     };
     return Expression::IntegerLiteral(Rc::new(token));
@@ -29,69 +34,64 @@ fn two() -> Expression {
 fn four() -> Expression {
     let token = Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(4),
     };
     return Expression::IntegerLiteral(Rc::new(token));
 }
 
-fn turn_one_into_two<'a>(node: Rc<dyn Node + 'a>) -> Rc<dyn Node + 'a> {
-    let expression = node.as_any().downcast_ref::<Expression>();
-    if let Some(expression) = expression {
-        return match expression {
-            Expression::IntegerLiteral(token) => match token.kind {
-                crate::tokens::TokenKind::Integer(value) => match value == 1 {
-                    true => Rc::new(two()),
-                    false => node,
-                },
-                _ => node,
+fn turn_one_into_two(expression: Expression) -> Expression {
+    match &expression {
+        Expression::IntegerLiteral(token) => match token.kind {
+            crate::tokens::TokenKind::Integer(value) => match value == 1 {
+                true => two(),
+                false => expression,
             },
-            _ => node,
-        };
+            _ => expression,
+        },
+        _ => expression,
     }
-    return node;
 }
-fn double_integer<'a>(node: Rc<dyn Node + 'a>) -> Rc<dyn Node + 'a> {
-    let expression = node.as_any().downcast_ref::<Expression>();
-    if let Some(expression) = expression {
-        return match expression {
-            Expression::IntegerLiteral(token) => match token.kind {
-                crate::tokens::TokenKind::Integer(value) => {
-                    let token = Token {
-                        context: Option::None,
-                        kind: crate::tokens::TokenKind::Integer(value * 2),
-                    };
-                    Rc::new(Expression::IntegerLiteral(Rc::new(token)))
-                }
-                _ => node,
-            },
-            _ => node,
-        };
+
+fn double_integer(expression: Expression) -> Expression {
+    match &expression {
+        Expression::IntegerLiteral(token) => match token.kind {
+            crate::tokens::TokenKind::Integer(value) => {
+                let token = Token {
+                    context: Option::None,
+                    span: Option::None,
+                    layout: Option::None,
+                    kind: crate::tokens::TokenKind::Integer(value * 2),
+                };
+                Expression::IntegerLiteral(Rc::new(token))
+            }
+            _ => expression,
+        },
+        _ => expression,
     }
-    return node;
 }
 
 #[test]
 fn should_be_able_modify() {
     let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(1),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: one(),
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => {
+        Statement::AExpression { expression, .. } => {
             check_if_integer_literal_equals(&expression, 2);
         }
         _ => panic!("Expected Integer expression got {:?}", first_statement),
@@ -102,24 +102,22 @@ fn should_be_able_modify() {
 fn should_not_modify() {
     let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(2),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: two(),
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => {
+        Statement::AExpression { expression, .. } => {
             check_if_integer_literal_equals(&expression, 2);
         }
         _ => panic!("Expected Integer expression got {:?}", first_statement),
@@ -133,12 +131,16 @@ macro_rules! should_traverse_infix_expresion {
             fn $name() {
      let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(0),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: Expression::Infix {
+                span: SourceSpan::default(),
                 token: token,
                 left: Box::new($input_left),
                 operator: expression::InfixOperatorType::Plus,
@@ -146,21 +148,21 @@ macro_rules! should_traverse_infix_expresion {
             },
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
         Statement::AExpression {
             token: _,
             expression,
+            ..
         } => match expression {
             Expression::Infix {
                 token: _,
                 left,
                 operator: _,
                 right,
+                ..
             } => {
                 check_if_integer_literal_equals(&left, $output_left);
                 check_if_integer_literal_equals(&right, $output_right);
@@ -188,32 +190,36 @@ macro_rules! should_traverse_prefix_expression {
             fn $name() {
      let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(0),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: Expression::PrefixOperator {
+                span: SourceSpan::default(),
                 token: token,
                 operator: PrefixOperatorType::Minus,
                 right: Box::new($input),
             },
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
         Statement::AExpression {
             token: _,
             expression,
+            ..
         } => match expression {
             Expression::PrefixOperator {
                 token: _,
                 operator: _,
                 right,
+                ..
             } => {
                 check_if_integer_literal_equals(&right, $output);
             }
@@ -237,32 +243,36 @@ macro_rules! should_traverse_index_expression {
             fn $name() {
      let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(0),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: Expression::Index {
+                span: SourceSpan::default(),
                 token: token,
                 array: Box::new($left),
                 index: Box::new($index),
             },
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
         Statement::AExpression {
             token: _,
             expression,
+            ..
         } => match expression {
             Expression::Index {
                 token: _,
                 array,
                 index,
+                ..
             } => {
                 check_if_integer_literal_equals(&array, $output_left);
                 check_if_integer_literal_equals(&index, $output_index);
@@ -286,19 +296,26 @@ should_traverse_index_expression! {
 fn should_traverse_if_expresion() {
     let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(1),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: Expression::AIf {
+                span: SourceSpan::default(),
                 token: token.clone(),
                 condition: Box::new(one()),
                 consequence: Box::new(Statement::Block {
+                    span: SourceSpan::default(),
                     token: token.clone(),
                     statements: Rc::new(vec![Statement::AExpression {
+                        span: SourceSpan::default(),
                         token: token.clone(),
                         expression: (Expression::PrefixOperator {
+                            span: SourceSpan::default(),
                             token: token.clone(),
                             operator: PrefixOperatorType::Bang,
                             right: Box::new(two()),
@@ -306,10 +323,13 @@ fn should_traverse_if_expresion() {
                     }]),
                 }),
                 alternative: Some(Box::new(Statement::Block {
+                    span: SourceSpan::default(),
                     token: token.clone(),
                     statements: Rc::new(vec![Statement::AExpression {
+                        span: SourceSpan::default(),
                         token: token.clone(),
                         expression: (Expression::PrefixOperator {
+                            span: SourceSpan::default(),
                             token: token.clone(),
                             operator: PrefixOperatorType::Bang,
                             right: Box::new(one()),
@@ -319,38 +339,25 @@ fn should_traverse_if_expresion() {
             },
         }],
     };
-    let result = modify(Rc::new(program), double_integer);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, double_integer);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        Statement::AExpression { expression, .. } => match expression {
             Expression::AIf {
-                token: _,
                 condition,
                 consequence,
                 alternative,
+                ..
             } => {
                 check_if_integer_literal_equals(&condition, 2);
                 match *consequence {
-                    Statement::Block {
-                        token: _,
-                        statements,
-                    } => {
+                    Statement::Block { statements, .. } => {
                         assert_eq!(statements.len(), 1);
                         match statements.get(0).unwrap() {
-                            Statement::AExpression {
-                                token: _,
-                                expression,
-                            } => match expression {
+                            Statement::AExpression { expression, .. } => match expression {
                                 Expression::PrefixOperator {
-                                    token: _,
-                                    operator: _,
-                                    right,
+                                    operator: _, right, ..
                                 } => check_if_integer_literal_equals(&right, 4),
                                 _ => panic!("Expected prefix got {:?}", expression),
                             },
@@ -360,20 +367,12 @@ fn should_traverse_if_expresion() {
                     _ => panic!("Expected block got {:?}", consequence),
                 }
                 match *alternative.unwrap() {
-                    Statement::Block {
-                        token: _,
-                        statements,
-                    } => {
+                    Statement::Block { statements, .. } => {
                         assert_eq!(statements.len(), 1);
                         match statements.get(0).unwrap() {
-                            Statement::AExpression {
-                                token: _,
-                                expression,
-                            } => match expression {
+                            Statement::AExpression { expression, .. } => match expression {
                                 Expression::PrefixOperator {
-                                    token: _,
-                                    operator: _,
-                                    right,
+                                    operator: _, right, ..
                                 } => check_if_integer_literal_equals(&right, 2),
                                 _ => panic!("Expected prefix operator got {:?}", expression),
                             },
@@ -397,23 +396,25 @@ macro_rules! should_modify_return_statements {
             fn $name() {
      let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(0),
     });
     let program = Program {
         statements: vec![Statement::Return {
+            span: SourceSpan::default(),
             token: token.clone(),
             return_value: $return,
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
         Statement::Return {
             token: _,
             return_value,
+            ..
         } => check_if_integer_literal_equals(&return_value, $output),
         _ => panic!("Expected expression statement got {:?}", first_statement),
     }
@@ -434,22 +435,26 @@ macro_rules! should_modify_let_statements {
             fn $name() {
      let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(0),
     });
         let name = Rc::new(Token{
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Identifier(String::from("aaa")),
         });
     let program = Program {
         statements: vec![Statement::Let {
+            span: SourceSpan::default(),
             token: token.clone(),
             name: Expression::Identifier(name.clone()),
             value: $let_value,
+            annotation: None,
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
@@ -457,6 +462,8 @@ macro_rules! should_modify_let_statements {
             token: _,
             name:_,
             value,
+            annotation: _,
+            ..
         } => check_if_integer_literal_equals(&value, $output),
         _ => panic!("Expected expression statement got {:?}", first_statement),
     }
@@ -474,19 +481,26 @@ should_modify_let_statements! {
 fn should_traverse_functional() {
     let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(1),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: Expression::FunctionLiteral {
+                span: SourceSpan::default(),
                 token: token.clone(),
                 parameters: Rc::new(vec![]),
                 body: Box::new(Statement::Block {
+                    span: SourceSpan::default(),
                     token: token.clone(),
                     statements: Rc::new(vec![Statement::AExpression {
+                        span: SourceSpan::default(),
                         token: token.clone(),
                         expression: (Expression::PrefixOperator {
+                            span: SourceSpan::default(),
                             token: token.clone(),
                             operator: PrefixOperatorType::Bang,
                             right: Box::new(two()),
@@ -494,39 +508,30 @@ fn should_traverse_functional() {
                     }]),
                 }),
                 name: None,
+                generator: false,
+                signature: None,
             },
         }],
     };
-    let result = modify(Rc::new(program), double_integer);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, double_integer);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        Statement::AExpression { expression, .. } => match expression {
             Expression::FunctionLiteral {
-                token: _,
                 parameters: _,
                 body,
                 name: _,
+                generator: _,
+                signature: _,
+                ..
             } => match *body {
-                Statement::Block {
-                    token: _,
-                    statements,
-                } => {
+                Statement::Block { statements, .. } => {
                     assert_eq!(statements.len(), 1);
                     match statements.get(0).unwrap() {
-                        Statement::AExpression {
-                            token: _,
-                            expression,
-                        } => match expression {
+                        Statement::AExpression { expression, .. } => match expression {
                             Expression::PrefixOperator {
-                                token: _,
-                                operator: _,
-                                right,
+                                operator: _, right, ..
                             } => check_if_integer_literal_equals(&right, 4),
                             _ => panic!("Expected prefix got {:?}", expression),
                         },
@@ -545,28 +550,27 @@ fn should_traverse_functional() {
 fn should_traverse_array() {
     let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(1),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: Expression::ArrayLiteral {
+                span: SourceSpan::default(),
                 token: token.clone(),
                 elements: vec![one(), four(), one()],
             },
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
-            Expression::ArrayLiteral { token: _, elements } => {
+        Statement::AExpression { expression, .. } => match expression {
+            Expression::ArrayLiteral { elements, .. } => {
                 check_if_integer_literal_equals(&elements[0], 2);
                 check_if_integer_literal_equals(&elements[1], 4);
                 check_if_integer_literal_equals(&elements[2], 2);
@@ -581,28 +585,27 @@ fn should_traverse_array() {
 fn should_traverse_map_literal() {
     let token = Rc::new(Token {
         context: Option::None,
+        span: Option::None,
+        layout: Option::None,
         kind: crate::tokens::TokenKind::Integer(1),
     });
     let program = Program {
         statements: vec![Statement::AExpression {
+            span: SourceSpan::default(),
             token: token.clone(),
             expression: Expression::MapLiteral {
+                span: SourceSpan::default(),
                 token: token.clone(),
                 elements: vec![(one(), one()), (four(), four())],
             },
         }],
     };
-    let result = modify(Rc::new(program), turn_one_into_two);
-    let output_program = result.as_any().downcast_ref::<Program>();
-    let output = output_program.unwrap();
+    let output = modify(program, turn_one_into_two);
     assert_eq!(output.statements.len(), 1);
     let first_statement = output.statements[0].clone();
     match first_statement {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
-            Expression::MapLiteral { token: _, elements } => {
+        Statement::AExpression { expression, .. } => match expression {
+            Expression::MapLiteral { elements, .. } => {
                 let (a1, a2) = &elements[0];
                 check_if_integer_literal_equals(a1, 2);
                 check_if_integer_literal_equals(a2, 2);
@@ -616,6 +619,46 @@ fn should_traverse_map_literal() {
     }
 }
 
+#[test]
+fn should_keep_spans() {
+    let source = "let a = [1 + 3, if (1) { 1 }];\nfn f(x) { x * 1 }";
+    let program = Parser::from_string(source).parse_program();
+    let output = modify(
+        Parser::from_string(source).parse_program(),
+        turn_one_into_two,
+    );
+    assert_eq!(
+        output.to_string(),
+        "let a=[(2 + 3), if (2){2}]\nfn f(x){ (x * 2) }"
+    );
+    let spans = |program: &Program| -> Vec<Option<&str>> {
+        let mut spans = vec![];
+        for statement in &program.statements {
+            spans.push(statement.span().text(source));
+            if let Statement::Let {
+                value: Expression::ArrayLiteral { elements, span, .. },
+                ..
+            } = statement
+            {
+                spans.push(span.text(source));
+                spans.extend(elements.iter().map(|element| element.span().text(source)));
+            }
+        }
+        spans
+    };
+    assert_eq!(spans(&output), spans(&program));
+    assert_eq!(
+        spans(&output),
+        vec![
+            Some("let a = [1 + 3, if (1) { 1 }];"),
+            Some("[1 + 3, if (1) { 1 }]"),
+            Some("1 + 3"),
+            Some("if (1) { 1 }"),
+            Some("fn f(x) { x * 1 }"),
+        ]
+    );
+}
+
 macro_rules! check_expression_value {
     ($expression:expr, $variant:ident, $token_kind:ident, $expected:expr) => {
         match $expression {
@@ -645,6 +688,6 @@ macro_rules! check_expression_value {
     };
 }
 
-fn check_if_integer_literal_equals(expression: &Expression, expected_value: u32) {
+fn check_if_integer_literal_equals(expression: &Expression, expected_value: i64) {
     check_expression_value!(expression, IntegerLiteral, Integer, expected_value);
 }
@@ -0,0 +1,92 @@
+use crate::{
+    ast::export::{from_json, to_json},
+    ast::statements::Program,
+    formatter::print_program,
+    parser::Parser,
+};
+
+use super::arbitrary::Arbitrary;
+
+const SEEDS: u64 = 1000;
+
+fn parse(source: &str) -> Program {
+    let mut parser = Parser::from_string(source);
+    let program = parser.parse_program();
+    assert!(
+        parser.errors().is_empty(),
+        "{:?}\n{}",
+        parser.errors(),
+        source
+    );
+    program
+}
+
+#[test]
+fn parses_what_was_printed() {
+    for seed in 0..SEEDS {
+        let program = Arbitrary::new(seed).program();
+        let source = print_program(&program);
+        let mut parser = Parser::from_string(&source);
+        let parsed = parser.parse_program();
+        assert!(
+            parser.errors().is_empty(),
+            "seed {}: {:?}\n{}",
+            seed,
+            parser.errors(),
+            source
+        );
+        assert!(parsed == program, "seed {}: printed as\n{}", seed, source);
+        assert_eq!(print_program(&parsed), source, "seed {}", seed);
+    }
+}
+
+#[test]
+fn imports_what_was_exported() {
+    for seed in 0..SEEDS {
+        let program = Arbitrary::new(seed).program();
+        let imported = from_json(&to_json(&program))
+            .unwrap_or_else(|error| panic!("seed {}: {}", seed, error));
+        assert!(imported == program, "seed {}", seed);
+    }
+}
+
+#[test]
+fn prints_literals_as_written() {
+    let source = "let s = {\"b\": \"two words\", :a: 1};\nreturn s[\"b\"];\n";
+    assert_eq!(print_program(&parse(source)), source);
+}
+
+#[test]
+fn separates_glued_prefix_operators() {
+    let program = parse("infix 10 -! = fn(a, b) { a }; -(!a); !-a");
+    assert_eq!(
+        print_program(&program),
+        "infix 10 -! = fn(a, b) { a };\n-(!a);\n!-a;\n"
+    );
+}
+
+#[test]
+fn compares_structure() {
+    let equal = [
+        ("a + b * c", "a+(b*c)"),
+        ("let f = fn(x) {\n x\n};", "let f = fn(x) { x }"),
+        ("{:a: 0x10}", "{ :a: 16 }"),
+        ("infix 10 <+> = f; a <+> b", "infix 10 <+> = f;\n(a <+> b)"),
+    ];
+    for (left, right) in equal {
+        assert!(parse(left) == parse(right), "{} and {}", left, right);
+    }
+    let different = [
+        ("a + b * c", "(a + b) * c"),
+        ("let f = fn(x) { x };", "let g = fn(x) { x };"),
+        ("let x: Int = 1", "let x = 1"),
+        ("fn f(x) { x }", "fn* f(x) { x }"),
+        ("\"a\"", "a"),
+        ("f(a, b)", "f(a)"),
+        ("infix 10 <+> = f;", "infixr 10 <+> = f;"),
+        ("[1, 2]", "[1, 2, 3]"),
+    ];
+    for (left, right) in different {
+        assert!(parse(left) != parse(right), "{} and {}", left, right);
+    }
+}
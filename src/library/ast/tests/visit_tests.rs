@@ -0,0 +1,118 @@
+use std::rc::Rc;
+
+use crate::{
+    ast::{
+        Fold, Foldable, Visitor, VisitorMut,
+        expression::{Expression, InfixOperatorType},
+        fold, visit, visit_mut,
+    },
+    parser::Parser,
+    tokens::{Token, TokenKind},
+};
+
+struct Names(Vec<String>);
+
+impl Visitor for Names {
+    fn visit_expression(&mut self, expression: &Expression) {
+        if let Expression::Identifier(token) = expression {
+            self.0.push(token.kind.to_string());
+        }
+        visit::walk_expression(self, expression);
+    }
+}
+
+struct Rename;
+
+impl VisitorMut for Rename {
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        if let Expression::Identifier(token) = expression {
+            let kind = TokenKind::Identifier(format!("{}_", token.kind));
+            *token = Rc::new(Token::at(token.source_span(), kind));
+        }
+        visit_mut::walk_expression_mut(self, expression);
+    }
+}
+
+struct Constants;
+
+impl Fold for Constants {
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        let expression = fold::walk_expression(self, expression);
+        let Expression::Infix {
+            left,
+            operator,
+            right,
+            span,
+            ..
+        } = &expression
+        else {
+            return expression;
+        };
+        let (Expression::IntegerLiteral(left), Expression::IntegerLiteral(right)) =
+            (left.as_ref(), right.as_ref())
+        else {
+            return expression;
+        };
+        let value = match (&left.kind, operator, &right.kind) {
+            (TokenKind::Integer(a), InfixOperatorType::Plus, TokenKind::Integer(b)) => a + b,
+            (TokenKind::Integer(a), InfixOperatorType::Multiply, TokenKind::Integer(b)) => a * b,
+            _ => return expression,
+        };
+        Expression::IntegerLiteral(Rc::new(Token::at(*span, TokenKind::Integer(value))))
+    }
+}
+
+#[test]
+fn visits_in_source_order() {
+    let program = Parser::from_string("let a = fn(x) { x + b }; a(1)").parse_program();
+    let mut names = Names(vec![]);
+    names.visit_program(&program);
+    assert_eq!(names.0, vec!["a", "x", "x", "b", "a"]);
+}
+
+#[test]
+fn changes_nodes_in_place() {
+    let program = Parser::from_string("let f = fn(x) { if (x) { y } }; f(z)").parse_program();
+    let mut renamed = program.clone();
+    Rename.visit_program_mut(&mut renamed);
+    assert_eq!(
+        renamed.to_string(),
+        Parser::from_string("let f_ = fn(x_) { if (x_) { y_ } }; f_(z_)")
+            .parse_program()
+            .to_string()
+    );
+    // The shared parameters and block statements of the clone are left alone.
+    assert_eq!(
+        program.to_string(),
+        Parser::from_string("let f = fn(x) { if (x) { y } }; f(z)")
+            .parse_program()
+            .to_string()
+    );
+}
+
+#[test]
+fn folds_constants() {
+    let tests = [
+        ("1 + 2 * 3", "7"),
+        ("let a = [1 * 2, b + 1 + 1];", "let a = [2, b + 1 + 1];"),
+        ("f(2 * (3 + 4))", "f(14)"),
+        ("if (x) { 1 + 1 } else { 0 }", "if (x) { 2 } else { 0 }"),
+    ];
+    for (input, expected) in tests {
+        let program = Parser::from_string(input).parse_program();
+        assert_eq!(
+            program.fold_with(&mut Constants).to_string(),
+            Parser::from_string(expected).parse_program().to_string()
+        );
+    }
+}
+
+#[test]
+fn folding_keeps_spans() {
+    let source = "a + 1 * 2";
+    let expression = Parser::from_string(source).parse_program().statements[0].clone();
+    let span = expression.span();
+    let folded = expression.fold_with(&mut Constants);
+    assert_eq!(folded.span(), span);
+    assert_eq!(folded.to_string(), "(a + 2)");
+}
@@ -0,0 +1,109 @@
+use std::{fmt::Display, rc::Rc};
+
+use crate::join_collection;
+
+use super::expression::Expression;
+
+//`Any` accepts every value, it is also the type of everything not annotated
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeAnnotation {
+    Any,
+    Int,
+    String,
+    Bool,
+    Char,
+    Symbol,
+    Null,
+    Array(Box<TypeAnnotation>),
+    Map(Box<TypeAnnotation>, Box<TypeAnnotation>),
+    Set(Box<TypeAnnotation>),
+    Tuple(Vec<TypeAnnotation>),
+    Function(Vec<TypeAnnotation>, Box<TypeAnnotation>),
+}
+
+impl TypeAnnotation {
+    pub fn named(name: &str) -> Option<TypeAnnotation> {
+        match name {
+            "Any" => Some(TypeAnnotation::Any),
+            "Int" => Some(TypeAnnotation::Int),
+            "String" => Some(TypeAnnotation::String),
+            "Bool" => Some(TypeAnnotation::Bool),
+            "Char" => Some(TypeAnnotation::Char),
+            "Symbol" => Some(TypeAnnotation::Symbol),
+            "Null" => Some(TypeAnnotation::Null),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TypeAnnotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeAnnotation::Any => write!(f, "Any"),
+            TypeAnnotation::Int => write!(f, "Int"),
+            TypeAnnotation::String => write!(f, "String"),
+            TypeAnnotation::Bool => write!(f, "Bool"),
+            TypeAnnotation::Char => write!(f, "Char"),
+            TypeAnnotation::Symbol => write!(f, "Symbol"),
+            TypeAnnotation::Null => write!(f, "Null"),
+            TypeAnnotation::Array(element) => write!(f, "[{}]", element),
+            TypeAnnotation::Map(key, value) => write!(f, "{{{}: {}}}", key, value),
+            TypeAnnotation::Set(element) => write!(f, "#{{{}}}", element),
+            TypeAnnotation::Tuple(elements) if elements.len() == 1 => {
+                write!(f, "({},)", elements[0])
+            }
+            TypeAnnotation::Tuple(elements) => write!(f, "({})", join_collection!(elements, ", ")),
+            TypeAnnotation::Function(parameters, result) => {
+                write!(
+                    f,
+                    "fn({}) -> {}",
+                    join_collection!(parameters, ", "),
+                    result
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub parameters: Vec<Option<TypeAnnotation>>,
+    pub result: Option<TypeAnnotation>,
+}
+
+impl Signature {
+    pub fn function_type(&self) -> TypeAnnotation {
+        TypeAnnotation::Function(
+            self.parameters
+                .iter()
+                .map(|parameter| parameter.clone().unwrap_or(TypeAnnotation::Any))
+                .collect(),
+            Box::new(self.result.clone().unwrap_or(TypeAnnotation::Any)),
+        )
+    }
+}
+
+pub fn display_parameters(parameters: &[Expression], signature: &Option<Rc<Signature>>) -> String {
+    match signature {
+        Some(signature) => parameters
+            .iter()
+            .zip(signature.parameters.iter())
+            .map(|(parameter, annotation)| match annotation {
+                Some(annotation) => format!("{}: {}", parameter, annotation),
+                None => parameter.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => join_collection!(parameters, ", "),
+    }
+}
+
+pub fn display_result(signature: &Option<Rc<Signature>>) -> String {
+    match signature
+        .as_ref()
+        .and_then(|signature| signature.result.as_ref())
+    {
+        Some(result) => format!(" -> {}", result),
+        None => String::new(),
+    }
+}
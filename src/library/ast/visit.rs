@@ -0,0 +1,118 @@
+use super::{
+    expression::Expression,
+    statements::{Program, Statement},
+};
+
+//an override decides whether the walk goes on by calling the `walk_` function
+pub trait Visitor {
+    fn visit_program(&mut self, program: &Program) {
+        walk_program(self, program);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) {
+    for statement in &program.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Let { name, value, .. }
+        | Statement::Assign { name, value, .. }
+        | Statement::Function { name, value, .. }
+        | Statement::Operator { name, value, .. } => {
+            visitor.visit_expression(name);
+            visitor.visit_expression(value);
+        }
+        Statement::Return { return_value, .. } => visitor.visit_expression(return_value),
+        Statement::Yield { value, .. } => visitor.visit_expression(value),
+        Statement::AExpression { expression, .. } => visitor.visit_expression(expression),
+        Statement::Block { statements, .. } => {
+            for statement in statements.iter() {
+                visitor.visit_statement(statement);
+            }
+        }
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Identifier(_)
+        | Expression::IntegerLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::SymbolLiteral(_)
+        | Expression::BooleanLiteral { .. } => {}
+        Expression::PrefixOperator { right, .. } => visitor.visit_expression(right),
+        Expression::Infix { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            visitor.visit_expression(function);
+            for argument in arguments {
+                visitor.visit_expression(argument);
+            }
+        }
+        Expression::AIf {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_statement(consequence);
+            if let Some(alternative) = alternative {
+                visitor.visit_statement(alternative);
+            }
+        }
+        Expression::FunctionLiteral {
+            parameters, body, ..
+        }
+        | Expression::MacroLiteral {
+            parameters, body, ..
+        } => {
+            for parameter in parameters.iter() {
+                visitor.visit_expression(parameter);
+            }
+            visitor.visit_statement(body);
+        }
+        Expression::ArrayLiteral { elements, .. }
+        | Expression::TupleLiteral { elements, .. }
+        | Expression::SetLiteral { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::Decorated {
+            decorators, value, ..
+        } => {
+            for decorator in decorators {
+                visitor.visit_expression(decorator);
+            }
+            visitor.visit_expression(value);
+        }
+        Expression::Index { array, index, .. } => {
+            visitor.visit_expression(array);
+            visitor.visit_expression(index);
+        }
+        Expression::MapLiteral { elements, .. } => {
+            for (key, value) in elements {
+                visitor.visit_expression(key);
+                visitor.visit_expression(value);
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::rc::Rc;
+
+use super::{
+    expression::Expression,
+    statements::{Program, Statement},
+};
+
+//shared blocks and parameters are copied before the first change, other owners keep the old nodes
+pub trait VisitorMut {
+    fn visit_program_mut(&mut self, program: &mut Program) {
+        walk_program_mut(self, program);
+    }
+
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement);
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression);
+    }
+}
+
+pub fn walk_program_mut<V: VisitorMut + ?Sized>(visitor: &mut V, program: &mut Program) {
+    for statement in &mut program.statements {
+        visitor.visit_statement_mut(statement);
+    }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Let { name, value, .. }
+        | Statement::Assign { name, value, .. }
+        | Statement::Function { name, value, .. }
+        | Statement::Operator { name, value, .. } => {
+            visitor.visit_expression_mut(name);
+            visitor.visit_expression_mut(value);
+        }
+        Statement::Return { return_value, .. } => visitor.visit_expression_mut(return_value),
+        Statement::Yield { value, .. } => visitor.visit_expression_mut(value),
+        Statement::AExpression { expression, .. } => visitor.visit_expression_mut(expression),
+        Statement::Block { statements, .. } => {
+            for statement in Rc::make_mut(statements) {
+                visitor.visit_statement_mut(statement);
+            }
+        }
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        Expression::Identifier(_)
+        | Expression::IntegerLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::SymbolLiteral(_)
+        | Expression::BooleanLiteral { .. } => {}
+        Expression::PrefixOperator { right, .. } => visitor.visit_expression_mut(right),
+        Expression::Infix { left, right, .. } => {
+            visitor.visit_expression_mut(left);
+            visitor.visit_expression_mut(right);
+        }
+        Expression::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            visitor.visit_expression_mut(function);
+            for argument in arguments {
+                visitor.visit_expression_mut(argument);
+            }
+        }
+        Expression::AIf {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            visitor.visit_expression_mut(condition);
+            visitor.visit_statement_mut(consequence);
+            if let Some(alternative) = alternative {
+                visitor.visit_statement_mut(alternative);
+            }
+        }
+        Expression::FunctionLiteral {
+            parameters, body, ..
+        }
+        | Expression::MacroLiteral {
+            parameters, body, ..
+        } => {
+            for parameter in Rc::make_mut(parameters) {
+                visitor.visit_expression_mut(parameter);
+            }
+            visitor.visit_statement_mut(body);
+        }
+        Expression::ArrayLiteral { elements, .. }
+        | Expression::TupleLiteral { elements, .. }
+        | Expression::SetLiteral { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression_mut(element);
+            }
+        }
+        Expression::Decorated {
+            decorators, value, ..
+        } => {
+            for decorator in decorators {
+                visitor.visit_expression_mut(decorator);
+            }
+            visitor.visit_expression_mut(value);
+        }
+        Expression::Index { array, index, .. } => {
+            visitor.visit_expression_mut(array);
+            visitor.visit_expression_mut(index);
+        }
+        Expression::MapLiteral { elements, .. } => {
+            for (key, value) in elements {
+                visitor.visit_expression_mut(key);
+                visitor.visit_expression_mut(value);
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{checker::check, join_collection, parser::Parser, print_bash_error};
+
+fn check_parser_errors(parser: &Parser) {
+    if !parser.errors.is_empty() {
+        panic!(
+            "Parser errors: \n{}",
+            print_bash_error!(join_collection!(&parser.errors, "\n"))
+        );
+    }
+}
+
+fn diagnostics(input: &str) -> Vec<String> {
+    let mut parser = Parser::from_string(input);
+    let program = parser.parse_program();
+    check_parser_errors(&parser);
+    check(&program).iter().map(|d| d.to_string()).collect()
+}
+
+macro_rules! checks_without_diagnostics {
+    ($($name:ident: $input:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                assert_eq!(diagnostics($input), Vec::<String>::new());
+            }
+        )*
+    };
+}
+
+macro_rules! reports_diagnostics {
+    ($($name:ident: ($input:expr, $expected:expr),)*) => {
+        $(
+            #[test]
+            fn $name() {
+                assert_eq!(diagnostics($input), $expected);
+            }
+        )*
+    };
+}
+
+checks_without_diagnostics! {
+    unannotated_program: "let add = fn(a, b) { a + b }; add(1, 2); add(\"a\", [1]);",
+    annotated_let: "let x: Int = 1 + 2; let s: String = \"a\" + 1;",
+    annotated_function: "let f = fn(a: String, b: [Int]) -> Bool { len(b) == 2 }; let r: Bool = f(\"a\", [1, 2]);",
+    any_accepts_everything: "let x: Any = 1; x = \"a\"; let y: Int = x;",
+    nested_collections: "let m: {String: [Int]} = {\"a\": [1], \"b\": [2, 3]}; let v: [Int] = m[\"a\"];",
+    tuple_annotation: "let (a, b): (Int, String) = (1, \"a\"); let c: String = a + b;",
+    set_annotation: "let s: #{Symbol} = #{:a, :b};",
+    function_annotation: "let apply: fn(fn(Int) -> Int, Int) -> Int = fn(f, x) { f(x) };",
+    recursive_function: "let fact = fn(n: Int) -> Int { if (n < 2) { return 1 } n * fact(n - 1) }; fact(5);",
+    declared_function_used_before: "let r: Int = twice(2); fn twice(n: Int) -> Int { n * 2 }",
+    map_with_overloads: "let v = {\"__add__\": fn(a, b) { 1 }}; v + v;",
+    reassigned_variable_is_any: "let x = 1; x = \"a\"; x + \"b\";",
+}
+
+reports_diagnostics! {
+    let_mismatch: ("let x: Int = \"a\";", vec!["1,14: x expected Int, got String"]),
+    argument_mismatch: (
+        "let f = fn(a: String) { a }; f(1);",
+        vec!["1,32: Argument 1 of f expected String, got Int"]
+    ),
+    arity_mismatch: ("let f = fn(a: Int) { a }; f(1, 2);", vec!["1,28: f expects 1 arguments, got 2"]),
+    result_mismatch: (
+        "let f = fn() -> Int { \"a\" };",
+        vec!["1,9: Result expected Int, got String"]
+    ),
+    return_mismatch: (
+        "let f = fn(a: Int) -> Bool { if (a > 1) { return a } true };",
+        vec!["1,50: Return expected Bool, got Int"]
+    ),
+    assign_mismatch: ("let x: Int = 1; x = [1];", vec!["1,23: x expected Int, got [Int]"]),
+    array_elements: ("let xs: [Int] = [\"a\", \"b\"];", vec!["1,26: xs expected [Int], got [String]"]),
+    map_arithmetic: (
+        "let m: {String: Int} = {\"a\": 1}; m + 1;",
+        vec!["1,36: Cannot use + on {String: Int} and Int"]
+    ),
+    unannotated_operator: ("1 + true;", vec!["1,3: Cannot use + on Int and Bool"]),
+    call_of_integer: ("let x: Int = 1; x(2);", vec!["1,18: Cannot call Int"]),
+    index_of_bool: ("true[0];", vec!["1,7: Cannot index Bool"]),
+    destructuring_mismatch: (
+        "let (a, b): (Int, Int, Int) = (1, 2, 3);",
+        vec!["1,5: Cannot destructure (Int, Int, Int) into tuple of 2 elements"]
+    ),
+    result_of_call_is_used: (
+        "let f = fn(a: Int) -> String { \"v\" + a }; let x: Int = f(1);",
+        vec!["1,57: x expected Int, got String"]
+    ),
+    minus_on_string: ("-\"a\";", vec!["1,1: Cannot use - on String"]),
+}
@@ -0,0 +1,1017 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    ast::{
+        expression::{Expression, InfixOperatorType, PrefixOperatorType},
+        statements::{Program, Statement},
+        types::TypeAnnotation,
+    },
+    object::parse_built_in_function,
+    tokens::{Token, TokenKind},
+};
+
+use super::{Diagnostic, infix_type};
+
+//`Any` is for values of different types, like elements of `[1, "a"]`, it never causes a diagnostic
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Variable(usize),
+    Any,
+    Int,
+    String,
+    Bool,
+    Char,
+    Symbol,
+    Null,
+    Array(Box<Type>),
+    Map(Box<Type>, Box<Type>),
+    Set(Box<Type>),
+    Tuple(Vec<Type>),
+    Function(Vec<Type>, Box<Type>),
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Variable(index) => {
+                let letter = (b'a' + (index % 26) as u8) as char;
+                match index / 26 {
+                    0 => write!(f, "'{}", letter),
+                    round => write!(f, "'{}{}", letter, round),
+                }
+            }
+            Type::Tuple(elements) if elements.len() == 1 => write!(f, "({},)", elements[0]),
+            Type::Tuple(elements) => {
+                let elements = elements.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+                write!(f, "({})", elements.join(", "))
+            }
+            Type::Function(parameters, result) => {
+                let parameters = parameters.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+                write!(f, "fn({}) -> {}", parameters.join(", "), result)
+            }
+            Type::Array(element) => write!(f, "[{}]", element),
+            Type::Map(key, value) => write!(f, "{{{}: {}}}", key, value),
+            Type::Set(element) => write!(f, "#{{{}}}", element),
+            other => write!(f, "{}", other.to_annotation()),
+        }
+    }
+}
+
+impl Type {
+    fn from_annotation(annotation: &TypeAnnotation) -> Type {
+        match annotation {
+            TypeAnnotation::Any => Type::Any,
+            TypeAnnotation::Int => Type::Int,
+            TypeAnnotation::String => Type::String,
+            TypeAnnotation::Bool => Type::Bool,
+            TypeAnnotation::Char => Type::Char,
+            TypeAnnotation::Symbol => Type::Symbol,
+            TypeAnnotation::Null => Type::Null,
+            TypeAnnotation::Array(element) => Type::Array(Box::new(Type::from_annotation(element))),
+            TypeAnnotation::Set(element) => Type::Set(Box::new(Type::from_annotation(element))),
+            TypeAnnotation::Map(key, value) => Type::Map(
+                Box::new(Type::from_annotation(key)),
+                Box::new(Type::from_annotation(value)),
+            ),
+            TypeAnnotation::Tuple(elements) => {
+                Type::Tuple(elements.iter().map(Type::from_annotation).collect())
+            }
+            TypeAnnotation::Function(parameters, result) => Type::Function(
+                parameters.iter().map(Type::from_annotation).collect(),
+                Box::new(Type::from_annotation(result)),
+            ),
+        }
+    }
+
+    fn to_annotation(&self) -> TypeAnnotation {
+        match self {
+            Type::Variable(_) | Type::Any => TypeAnnotation::Any,
+            Type::Int => TypeAnnotation::Int,
+            Type::String => TypeAnnotation::String,
+            Type::Bool => TypeAnnotation::Bool,
+            Type::Char => TypeAnnotation::Char,
+            Type::Symbol => TypeAnnotation::Symbol,
+            Type::Null => TypeAnnotation::Null,
+            Type::Array(element) => TypeAnnotation::Array(Box::new(element.to_annotation())),
+            Type::Set(element) => TypeAnnotation::Set(Box::new(element.to_annotation())),
+            Type::Map(key, value) => TypeAnnotation::Map(
+                Box::new(key.to_annotation()),
+                Box::new(value.to_annotation()),
+            ),
+            Type::Tuple(elements) => {
+                TypeAnnotation::Tuple(elements.iter().map(Type::to_annotation).collect())
+            }
+            Type::Function(parameters, result) => TypeAnnotation::Function(
+                parameters.iter().map(Type::to_annotation).collect(),
+                Box::new(result.to_annotation()),
+            ),
+        }
+    }
+
+    fn variables(&self, found: &mut Vec<usize>) {
+        match self {
+            Type::Variable(index) if !found.contains(index) => found.push(*index),
+            Type::Array(element) | Type::Set(element) => element.variables(found),
+            Type::Map(key, value) => {
+                key.variables(found);
+                value.variables(found);
+            }
+            Type::Tuple(elements) => elements.iter().for_each(|e| e.variables(found)),
+            Type::Function(parameters, result) => {
+                parameters.iter().for_each(|p| p.variables(found));
+                result.variables(found);
+            }
+            _ => {}
+        }
+    }
+
+    fn rename(&self, names: &HashMap<usize, Type>) -> Type {
+        match self {
+            Type::Variable(index) => names.get(index).cloned().unwrap_or(Type::Variable(*index)),
+            Type::Array(element) => Type::Array(Box::new(element.rename(names))),
+            Type::Set(element) => Type::Set(Box::new(element.rename(names))),
+            Type::Map(key, value) => {
+                Type::Map(Box::new(key.rename(names)), Box::new(value.rename(names)))
+            }
+            Type::Tuple(elements) => {
+                Type::Tuple(elements.iter().map(|e| e.rename(names)).collect())
+            }
+            Type::Function(parameters, result) => Type::Function(
+                parameters.iter().map(|p| p.rename(names)).collect(),
+                Box::new(result.rename(names)),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    //variables numbered in order of appearance, so the type reads `fn('a) -> 'a`
+    fn normalized(&self) -> Type {
+        let mut found = vec![];
+        self.variables(&mut found);
+        let names = found
+            .into_iter()
+            .enumerate()
+            .map(|(position, index)| (index, Type::Variable(position)))
+            .collect();
+        self.rename(&names)
+    }
+
+    fn is_known(&self) -> bool {
+        !matches!(self, Type::Variable(_) | Type::Any)
+    }
+}
+
+#[derive(Debug)]
+pub struct Analysis {
+    pub bindings: Vec<(String, Type)>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Analysis {
+    pub fn type_of(&self, name: &str) -> Option<&Type> {
+        self.bindings
+            .iter()
+            .find(|(binding, _)| binding == name)
+            .map(|(_, found)| found)
+    }
+}
+
+//only errors which happen whatever the values are get reported, like calling an `Int`
+pub fn infer(program: &Program) -> Analysis {
+    let mut inference = Inference::default();
+    inference.scopes.push(HashMap::new());
+    inference.infer_statements(&program.statements);
+    let top = inference.scopes.pop().unwrap_or_default();
+    let bindings = inference
+        .order
+        .iter()
+        .filter_map(|name| top.get(name).map(|entry| (name, entry)))
+        .map(|(name, entry)| {
+            (
+                name.clone(),
+                inference.resolve(&entry.scheme.body).normalized(),
+            )
+        })
+        .collect();
+    Analysis {
+        bindings,
+        diagnostics: inference.diagnostics,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Scheme {
+    variables: Vec<usize>,
+    body: Type,
+}
+
+impl Scheme {
+    fn mono(body: Type) -> Scheme {
+        Scheme {
+            variables: vec![],
+            body,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    scheme: Scheme,
+    annotated: bool,
+}
+
+struct Returns {
+    result: Type,
+    annotated: bool,
+    any: bool,
+}
+
+#[derive(Default)]
+struct Inference {
+    substitution: Vec<Option<Type>>,
+    scopes: Vec<HashMap<String, Entry>>,
+    order: Vec<String>, // top level names in order of definition
+    returns: Vec<Returns>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Inference {
+    fn report(&mut self, message: String, token: &Token) {
+        self.diagnostics.push(Diagnostic {
+            message,
+            position: token.context,
+        });
+    }
+
+    fn fresh(&mut self) -> Type {
+        self.substitution.push(None);
+        Type::Variable(self.substitution.len() - 1)
+    }
+
+    fn resolve(&self, found: &Type) -> Type {
+        match found {
+            Type::Variable(index) => match &self.substitution[*index] {
+                Some(bound) => self.resolve(bound),
+                None => found.clone(),
+            },
+            Type::Array(element) => Type::Array(Box::new(self.resolve(element))),
+            Type::Set(element) => Type::Set(Box::new(self.resolve(element))),
+            Type::Map(key, value) => {
+                Type::Map(Box::new(self.resolve(key)), Box::new(self.resolve(value)))
+            }
+            Type::Tuple(elements) => {
+                Type::Tuple(elements.iter().map(|e| self.resolve(e)).collect())
+            }
+            Type::Function(parameters, result) => Type::Function(
+                parameters.iter().map(|p| self.resolve(p)).collect(),
+                Box::new(self.resolve(result)),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    fn unify(&mut self, left: &Type, right: &Type) -> bool {
+        let (left, right) = (self.resolve(left), self.resolve(right));
+        match (&left, &right) {
+            (Type::Variable(a), Type::Variable(b)) if a == b => true,
+            (Type::Variable(index), other) | (other, Type::Variable(index)) => {
+                let mut found = vec![];
+                other.variables(&mut found);
+                //a value containing itself, like `x` in `x(x)`, is left to run time
+                let bound = if found.contains(index) {
+                    Type::Any
+                } else {
+                    other.clone()
+                };
+                self.substitution[*index] = Some(bound);
+                true
+            }
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Array(a), Type::Array(b)) | (Type::Set(a), Type::Set(b)) => self.unify(a, b),
+            (Type::Map(left_key, left_value), Type::Map(right_key, right_value)) => {
+                self.unify(left_key, right_key) && self.unify(left_value, right_value)
+            }
+            (Type::Tuple(a), Type::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| self.unify(a, b))
+            }
+            (
+                Type::Function(left_parameters, left_result),
+                Type::Function(right_parameters, right_result),
+            ) => {
+                left_parameters.len() == right_parameters.len()
+                    && left_parameters
+                        .iter()
+                        .zip(right_parameters)
+                        .all(|(a, b)| self.unify(a, b))
+                    && self.unify(left_result, right_result)
+            }
+            _ => left == right,
+        }
+    }
+
+    //leaves nothing bound when it fails
+    fn attempt(&mut self, left: &Type, right: &Type) -> bool {
+        let saved = self.substitution.clone();
+        let unified = self.unify(left, right);
+        if !unified {
+            self.substitution = saved;
+        }
+        unified
+    }
+
+    fn expect(&mut self, expected: &Type, actual: &Type, subject: &str, token: &Token) {
+        if !self.attempt(expected, actual) {
+            let expected = self.resolve(expected).normalized();
+            let actual = self.resolve(actual).normalized();
+            self.report(
+                format!("{} expected {}, got {}", subject, expected, actual),
+                token,
+            );
+        }
+    }
+
+    fn define(&mut self, name: &str, scheme: Scheme, annotated: bool) {
+        if self.scopes.len() == 1 && !self.order.iter().any(|known| known == name) {
+            self.order.push(name.to_string());
+        }
+        self.scopes
+            .last_mut()
+            .expect("Definition outside of any scope")
+            .insert(name.to_string(), Entry { scheme, annotated });
+    }
+
+    fn lookup(&self, name: &str) -> Option<Entry> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+    }
+
+    fn instantiate(&mut self, scheme: &Scheme) -> Type {
+        let names = scheme
+            .variables
+            .iter()
+            .map(|index| (*index, self.fresh()))
+            .collect();
+        self.resolve(&scheme.body).rename(&names)
+    }
+
+    fn generalize(&self, found: &Type) -> Scheme {
+        let body = self.resolve(found);
+        let mut in_scope = vec![];
+        for entry in self.scopes.iter().flat_map(|scope| scope.values()) {
+            let mut variables = vec![];
+            self.resolve(&entry.scheme.body).variables(&mut variables);
+            in_scope.extend(
+                variables
+                    .into_iter()
+                    .filter(|index| !entry.scheme.variables.contains(index)),
+            );
+        }
+        let mut variables = vec![];
+        body.variables(&mut variables);
+        variables.retain(|index| !in_scope.contains(index));
+        Scheme { variables, body }
+    }
+
+    //declared functions are inferred first, as the evaluator hoists them
+    fn infer_statements(&mut self, statements: &[Statement]) -> Type {
+        let declared = statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Function { name, value, .. } => Some((name.to_string(), value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let placeholders = declared
+            .iter()
+            .map(|(name, _)| {
+                let placeholder = self.fresh();
+                self.define(name, Scheme::mono(placeholder.clone()), false);
+                placeholder
+            })
+            .collect::<Vec<_>>();
+        for ((_, value), placeholder) in declared.iter().zip(&placeholders) {
+            let function = self.infer_expression(value);
+            self.unify(placeholder, &function);
+        }
+        for ((name, _), placeholder) in declared.iter().zip(&placeholders) {
+            let scheme = self.generalize(placeholder);
+            self.define(name, scheme, false);
+        }
+        let mut result = Type::Null;
+        for statement in statements {
+            result = match statement {
+                Statement::Function { .. } => Type::Any,
+                _ => self.infer_statement(statement),
+            };
+        }
+        result
+    }
+
+    fn infer_block(&mut self, statements: &[Statement]) -> Type {
+        self.scopes.push(HashMap::new());
+        let result = self.infer_statements(statements);
+        self.scopes.pop();
+        result
+    }
+
+    fn infer_statement(&mut self, statement: &Statement) -> Type {
+        match statement {
+            Statement::Let {
+                name: name @ Expression::Identifier(_),
+                value,
+                annotation,
+                ..
+            } => {
+                let name = &name.to_string();
+                let value_type = self.infer_expression(value);
+                let scheme = match annotation {
+                    Some(annotation) => {
+                        let annotation = Type::from_annotation(annotation);
+                        self.expect(&annotation, &value_type, name, value.token());
+                        Scheme::mono(annotation)
+                    }
+                    None => self.generalize(&value_type),
+                };
+                self.define(name, scheme, annotation.is_some());
+                Type::Any
+            }
+            Statement::Let {
+                name: pattern,
+                value,
+                annotation,
+                ..
+            } => {
+                let value_type = self.infer_expression(value);
+                if let Some(annotation) = annotation {
+                    let annotation = Type::from_annotation(annotation);
+                    self.expect(
+                        &annotation,
+                        &value_type,
+                        &pattern.to_string(),
+                        value.token(),
+                    );
+                }
+                self.bind_pattern(pattern, &value_type, annotation.is_some());
+                Type::Any
+            }
+            Statement::Assign { name, value, .. } => {
+                let value_type = self.infer_expression(value);
+                let name = name.to_string();
+                if let Some(entry) = self.lookup(&name) {
+                    let current = self.instantiate(&entry.scheme);
+                    if entry.annotated {
+                        self.expect(&current, &value_type, &name, value.token());
+                    } else if !self.attempt(&current, &value_type) {
+                        //variable holds values of different types from now on
+                        self.replace(&name, Scheme::mono(Type::Any));
+                    }
+                }
+                Type::Any
+            }
+            Statement::Return { return_value, .. } => {
+                let value_type = self.infer_expression(return_value);
+                self.returned(&value_type, "Return", return_value.token());
+                Type::Any
+            }
+            Statement::Function { value, .. } => {
+                self.infer_expression(value);
+                Type::Any
+            }
+            Statement::Yield { value, .. } => {
+                self.infer_expression(value);
+                Type::Any
+            }
+            Statement::AExpression { expression, .. } => self.infer_expression(expression),
+            Statement::Block { statements, .. } => self.infer_block(statements),
+            Statement::Operator { name, value, .. } => {
+                let value_type = self.infer_expression(value);
+                let scheme = self.generalize(&value_type);
+                self.define(&name.to_string(), scheme, false);
+                Type::Any
+            }
+        }
+    }
+
+    fn replace(&mut self, name: &str, scheme: Scheme) {
+        if let Some(entry) = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            entry.scheme = scheme;
+        }
+    }
+
+    fn returned(&mut self, value: &Type, subject: &str, token: &Token) {
+        let Some(Returns {
+            result, annotated, ..
+        }) = self.returns.last()
+        else {
+            return;
+        };
+        let (result, annotated) = (result.clone(), *annotated);
+        if annotated {
+            self.expect(&result, value, subject, token);
+        } else if !self.attempt(&result, value)
+            && let Some(returns) = self.returns.last_mut()
+        {
+            returns.any = true;
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &Expression, value: &Type, annotated: bool) {
+        let elements = match pattern {
+            Expression::Identifier(_) => {
+                let scheme = Scheme::mono(value.clone());
+                self.define(&pattern.to_string(), scheme, annotated);
+                return;
+            }
+            Expression::TupleLiteral { elements, .. } => elements,
+            _ => return,
+        };
+        if let Type::Variable(_) = self.resolve(value) {
+            let tuple = Type::Tuple(elements.iter().map(|_| self.fresh()).collect());
+            self.unify(value, &tuple);
+        }
+        match self.resolve(value) {
+            Type::Tuple(types) if types.len() == elements.len() => {
+                for (element, element_type) in elements.iter().zip(&types) {
+                    self.bind_pattern(element, element_type, annotated);
+                }
+                return;
+            }
+            Type::Any => {}
+            other => self.report(
+                format!(
+                    "Cannot destructure {} into tuple of {} elements",
+                    other,
+                    elements.len()
+                ),
+                pattern.token(),
+            ),
+        }
+        for element in elements {
+            self.bind_pattern(element, &Type::Any, false);
+        }
+    }
+
+    fn infer_expression(&mut self, expression: &Expression) -> Type {
+        match expression {
+            Expression::IntegerLiteral(_) => Type::Int,
+            Expression::StringLiteral(_) => Type::String,
+            Expression::SymbolLiteral(_) => Type::Symbol,
+            Expression::BooleanLiteral { .. } => Type::Bool,
+            Expression::Identifier(_) => match self.lookup(&expression.to_string()) {
+                Some(entry) => self.instantiate(&entry.scheme),
+                None => Type::Any,
+            },
+            Expression::PrefixOperator {
+                token,
+                operator,
+                right,
+                ..
+            } => {
+                let right = self.infer_expression(right);
+                let accepted = match operator {
+                    PrefixOperatorType::Minus => self.attempt(&right, &Type::Int),
+                    PrefixOperatorType::Bang => matches!(
+                        self.resolve(&right),
+                        Type::Bool
+                            | Type::String
+                            | Type::Int
+                            | Type::Null
+                            | Type::Variable(_)
+                            | Type::Any
+                    ),
+                };
+                if !accepted {
+                    let right = self.resolve(&right).normalized();
+                    self.report(format!("Cannot use {} on {}", operator, right), token);
+                }
+                match operator {
+                    PrefixOperatorType::Minus => Type::Int,
+                    PrefixOperatorType::Bang => Type::Bool,
+                }
+            }
+            Expression::Infix {
+                token,
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                let left = self.infer_expression(left);
+                let right = self.infer_expression(right);
+                self.infer_infix(token, operator, &left, &right)
+            }
+            Expression::AIf {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => {
+                self.infer_expression(condition);
+                let consequence = self.infer_statement(consequence);
+                let alternative = match alternative {
+                    Some(alternative) => self.infer_statement(alternative),
+                    None => Type::Null,
+                };
+                if self.attempt(&consequence, &alternative) {
+                    consequence
+                } else {
+                    Type::Any
+                }
+            }
+            Expression::Call {
+                token,
+                function,
+                arguments,
+                ..
+            } => self.infer_call(token, function, arguments),
+            Expression::FunctionLiteral { .. } => self.infer_function(expression),
+            Expression::MacroLiteral { .. } => Type::Any,
+            Expression::ArrayLiteral { elements, .. } => {
+                Type::Array(Box::new(self.infer_elements(elements)))
+            }
+            Expression::SetLiteral { elements, .. } => {
+                Type::Set(Box::new(self.infer_elements(elements)))
+            }
+            Expression::TupleLiteral { elements, .. } => Type::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.infer_expression(element))
+                    .collect(),
+            ),
+            Expression::MapLiteral { elements, .. } => {
+                let (mut keys, mut values) = (vec![], vec![]);
+                for (key, value) in elements {
+                    keys.push(self.infer_expression(key));
+                    values.push(self.infer_expression(value));
+                }
+                let key = self.common(keys);
+                let value = self.common(values);
+                Type::Map(Box::new(key), Box::new(value))
+            }
+            Expression::Index {
+                token,
+                array,
+                index,
+                ..
+            } => self.infer_index(token, array, index),
+            Expression::Decorated {
+                token,
+                decorators,
+                value,
+                ..
+            } => {
+                let mut decorated = self.infer_expression(value);
+                for decorator in decorators.iter().rev() {
+                    let function = self.infer_expression(decorator);
+                    let name = decorator.to_string();
+                    decorated = self.apply(token, &name, &function, &[(decorated, token)]);
+                }
+                decorated
+            }
+        }
+    }
+
+    fn infer_elements(&mut self, elements: &[Expression]) -> Type {
+        let types = elements
+            .iter()
+            .map(|element| self.infer_expression(element))
+            .collect();
+        self.common(types)
+    }
+
+    fn common(&mut self, types: Vec<Type>) -> Type {
+        let common = self.fresh();
+        for found in types {
+            if !self.attempt(&common, &found) {
+                return Type::Any;
+            }
+        }
+        common
+    }
+
+    fn infer_infix(
+        &mut self,
+        token: &Token,
+        operator: &InfixOperatorType,
+        left: &Type,
+        right: &Type,
+    ) -> Type {
+        let (left, right) = (
+            self.resolve(left).normalized(),
+            self.resolve(right).normalized(),
+        );
+        let comparison = matches!(
+            operator,
+            InfixOperatorType::Equal
+                | InfixOperatorType::NotEqual
+                | InfixOperatorType::LessThan
+                | InfixOperatorType::GreaterThan
+        );
+        //the other operand may be a map overriding the operator
+        if !left.is_known() || !right.is_known() {
+            return if comparison { Type::Bool } else { Type::Any };
+        }
+        match infix_type(operator, &left.to_annotation(), &right.to_annotation()) {
+            Some(result) => Type::from_annotation(&result),
+            None => {
+                self.report(
+                    format!("Cannot use {} on {} and {}", operator, left, right),
+                    token,
+                );
+                Type::Any
+            }
+        }
+    }
+
+    fn infer_index(&mut self, token: &Token, collection: &Expression, index: &Expression) -> Type {
+        let collection_type = self.infer_expression(collection);
+        let index_type = self.infer_expression(index);
+        match self.resolve(&collection_type) {
+            Type::Array(element) => {
+                self.expect(&Type::Int, &index_type, "Index", index.token());
+                *element
+            }
+            Type::String => {
+                self.expect(&Type::Int, &index_type, "Index", index.token());
+                Type::Char
+            }
+            Type::Map(key, value) => {
+                self.expect(&key, &index_type, "Key", index.token());
+                *value
+            }
+            Type::Tuple(elements) => {
+                self.expect(&Type::Int, &index_type, "Index", index.token());
+                //elements are counted from 1
+                match &index.token().kind {
+                    TokenKind::Integer(position)
+                        if (1..=elements.len()).contains(&(*position as usize)) =>
+                    {
+                        elements[*position as usize - 1].clone()
+                    }
+                    _ => Type::Any,
+                }
+            }
+            Type::Variable(_) | Type::Any => Type::Any,
+            other => {
+                let other = other.normalized();
+                self.report(format!("Cannot index {}", other), token);
+                Type::Any
+            }
+        }
+    }
+
+    fn infer_call(
+        &mut self,
+        token: &Token,
+        function: &Expression,
+        arguments: &[Expression],
+    ) -> Type {
+        let name = function.to_string();
+        let builtin = match function {
+            Expression::Identifier(_) if self.lookup(&name).is_none() => {
+                parse_built_in_function(&name).map(|_| name.clone())
+            }
+            _ => None,
+        };
+        //arguments of quote are code, not values
+        if matches!(builtin.as_deref(), Some("quote" | "unquote")) {
+            return Type::Any;
+        }
+        let callee = match builtin {
+            Some(_) => Type::Any,
+            None => self.infer_expression(function),
+        };
+        let arguments = arguments
+            .iter()
+            .map(|argument| (self.infer_expression(argument), argument.token()))
+            .collect::<Vec<_>>();
+        match builtin {
+            Some(builtin) => self.apply_builtin(token, &builtin, &arguments),
+            None => self.apply(token, &name, &callee, &arguments),
+        }
+    }
+
+    fn apply(
+        &mut self,
+        token: &Token,
+        name: &str,
+        callee: &Type,
+        arguments: &[(Type, &std::rc::Rc<Token>)],
+    ) -> Type {
+        match self.resolve(callee) {
+            Type::Function(parameters, result) => {
+                if parameters.len() != arguments.len() {
+                    self.report(
+                        format!(
+                            "{} expects {} arguments, got {}",
+                            name,
+                            parameters.len(),
+                            arguments.len()
+                        ),
+                        token,
+                    );
+                }
+                for (position, (parameter, (argument, argument_token))) in
+                    parameters.iter().zip(arguments).enumerate()
+                {
+                    let subject = format!("Argument {} of {}", position + 1, name);
+                    self.expect(parameter, argument, &subject, argument_token);
+                }
+                *result
+            }
+            Type::Variable(_) => {
+                let result = self.fresh();
+                let parameters = arguments.iter().map(|(found, _)| found.clone()).collect();
+                let function = Type::Function(parameters, Box::new(result.clone()));
+                if self.attempt(callee, &function) {
+                    result
+                } else {
+                    Type::Any
+                }
+            }
+            Type::Any => Type::Any,
+            other => {
+                let other = other.normalized();
+                self.report(format!("Cannot call {}", other), token);
+                Type::Any
+            }
+        }
+    }
+
+    //first matching signature is used, nothing is assumed about arguments not known yet
+    fn apply_builtin(
+        &mut self,
+        token: &Token,
+        name: &str,
+        arguments: &[(Type, &std::rc::Rc<Token>)],
+    ) -> Type {
+        let signatures = self.builtin_signatures(name);
+        let Some((first_parameters, _)) = signatures.first() else {
+            return match name {
+                "puts" => Type::Null,
+                _ => Type::Any,
+            };
+        };
+        if first_parameters.len() != arguments.len() {
+            self.report(
+                format!(
+                    "{} expects {} arguments, got {}",
+                    name,
+                    first_parameters.len(),
+                    arguments.len()
+                ),
+                token,
+            );
+            return Type::Any;
+        }
+        //`first` of an argument not known yet may give an element or a character
+        let unknown = arguments
+            .iter()
+            .any(|(argument, _)| !self.resolve(argument).is_known());
+        let shapes = signatures
+            .iter()
+            .map(|(parameters, _)| std::mem::discriminant(&parameters[0]))
+            .collect::<Vec<_>>();
+        if unknown && shapes.iter().any(|shape| *shape != shapes[0]) {
+            return match name {
+                "len" => Type::Int,
+                _ => self.fresh(),
+            };
+        }
+        if name == "len" && matches!(self.resolve(&arguments[0].0), Type::Tuple(_)) {
+            return Type::Int;
+        }
+        for (parameters, result) in &signatures {
+            let saved = self.substitution.clone();
+            if parameters
+                .iter()
+                .zip(arguments)
+                .all(|(parameter, (argument, _))| self.unify(parameter, argument))
+            {
+                return result.clone();
+            }
+            self.substitution = saved;
+        }
+        let types = arguments
+            .iter()
+            .map(|(argument, _)| self.resolve(argument).normalized().to_string())
+            .collect::<Vec<_>>();
+        self.report(
+            format!("Invalid arguments for {}: {}", name, types.join(", ")),
+            token,
+        );
+        Type::Any
+    }
+
+    fn builtin_signatures(&mut self, name: &str) -> Vec<(Vec<Type>, Type)> {
+        let a = self.fresh();
+        let (b, c) = (self.fresh(), self.fresh());
+        let array = |element: &Type| Type::Array(Box::new(element.clone()));
+        let set = |element: &Type| Type::Set(Box::new(element.clone()));
+        match name {
+            "len" => vec![
+                (vec![array(&a)], Type::Int),
+                (vec![Type::String], Type::Int),
+                (vec![set(&a)], Type::Int),
+            ],
+            "first" | "last" => vec![
+                (vec![array(&a)], a.clone()),
+                (vec![Type::String], Type::Char),
+            ],
+            "rest" => vec![
+                (vec![array(&a)], array(&a)),
+                (vec![Type::String], Type::String),
+            ],
+            "push" => vec![
+                (vec![array(&a), a.clone()], array(&a)),
+                (vec![array(&b), Type::Any], array(&Type::Any)),
+            ],
+            "chars" => vec![(vec![Type::String], array(&Type::Char))],
+            "union" | "intersection" | "difference" => vec![
+                (vec![set(&a), set(&a)], set(&a)),
+                (vec![set(&b), set(&c)], set(&Type::Any)),
+            ],
+            "contains" => vec![(vec![set(&a), Type::Any], Type::Bool)],
+            "str" => vec![(vec![Type::Any], Type::String)],
+            "next" => vec![(vec![Type::Any], Type::Any)],
+            "done" => vec![(vec![Type::Any], Type::Bool)],
+            "take" => vec![(vec![Type::Any, Type::Int], array(&Type::Any))],
+            _ => vec![],
+        }
+    }
+
+    fn infer_function(&mut self, expression: &Expression) -> Type {
+        let Expression::FunctionLiteral {
+            token,
+            parameters,
+            body,
+            name,
+            generator,
+            signature,
+            ..
+        } = expression
+        else {
+            return Type::Any;
+        };
+        let annotated = match signature {
+            Some(signature) => signature.parameters.clone(),
+            None => vec![None; parameters.len()],
+        };
+        let parameter_types = annotated
+            .iter()
+            .map(|annotation| match annotation {
+                Some(annotation) => Type::from_annotation(annotation),
+                None => self.fresh(),
+            })
+            .collect::<Vec<_>>();
+        let declared_result = signature
+            .as_ref()
+            .and_then(|signature| signature.result.as_ref())
+            .filter(|_| !generator);
+        let result = match declared_result {
+            Some(result) => Type::from_annotation(result),
+            None => self.fresh(),
+        };
+        //calling a generator gives the generator, its body runs when values are taken
+        let called = if *generator {
+            Type::Any
+        } else {
+            result.clone()
+        };
+        let function = Type::Function(parameter_types.clone(), Box::new(called));
+        self.scopes.push(HashMap::new());
+        if let Some(name) = name {
+            self.define(name, Scheme::mono(function.clone()), false);
+        }
+        for ((parameter, parameter_type), annotation) in
+            parameters.iter().zip(&parameter_types).zip(&annotated)
+        {
+            let scheme = Scheme::mono(parameter_type.clone());
+            self.define(&parameter.to_string(), scheme, annotation.is_some());
+        }
+        self.returns.push(Returns {
+            result: result.clone(),
+            annotated: declared_result.is_some(),
+            any: false,
+        });
+        let value = self.infer_statement(body);
+        if !generator {
+            self.returned(&value, "Result", token);
+        }
+        let returns = self.returns.pop().expect("Function without its result");
+        self.scopes.pop();
+        if returns.any {
+            Type::Function(parameter_types, Box::new(Type::Any))
+        } else {
+            function
+        }
+    }
+}
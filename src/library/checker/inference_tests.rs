@@ -0,0 +1,150 @@
+use crate::{checker::infer, join_collection, parser::Parser, print_bash_error};
+
+fn analyse(input: &str) -> crate::checker::Analysis {
+    let mut parser = Parser::from_string(input);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        panic!(
+            "Parser errors: \n{}",
+            print_bash_error!(join_collection!(&parser.errors, "\n"))
+        );
+    }
+    infer(&program)
+}
+
+macro_rules! infers_types {
+    ($($name:ident: ($input:expr, $binding:expr, $expected:expr),)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let analysis = analyse($input);
+                assert_eq!(analysis.diagnostics, vec![]);
+                let found = analysis.type_of($binding).map(|t| t.to_string());
+                assert_eq!(found.as_deref(), Some($expected));
+            }
+        )*
+    };
+}
+
+macro_rules! infers_errors {
+    ($($name:ident: ($input:expr, $expected:expr),)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let analysis = analyse($input);
+                let found = analysis.diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+                assert_eq!(found, $expected);
+            }
+        )*
+    };
+}
+
+infers_types! {
+    integer_binding: ("let x = 1 + 2;", "x", "Int"),
+    identity_is_generic: ("let id = fn(x) { x };", "id", "fn('a) -> 'a"),
+    identity_used_twice: ("let id = fn(x) { x }; let a = id(1); let b = id(\"s\");", "b", "String"),
+    parameter_called: ("let apply = fn(f, x) { f(x) };", "apply", "fn(fn('a) -> 'b, 'a) -> 'b"),
+    closure_captures: ("let adder = fn(a) { fn(b) { a - b } };", "adder", "fn('a) -> fn('b) -> Any"),
+    negated_parameter: ("let neg = fn(a) { -a };", "neg", "fn(Int) -> Int"),
+    homogeneous_array: ("let xs = [1, 2, 3];", "xs", "[Int]"),
+    mixed_array: ("let xs = [1, \"a\"];", "xs", "[Any]"),
+    empty_array: ("let xs = [];", "xs", "['a]"),
+    homogeneous_map: ("let m = {\"a\": [1], \"b\": []};", "m", "{String: [Int]}"),
+    map_lookup: ("let m = {\"a\": 1}; let v = m[\"a\"];", "v", "Int"),
+    first_of_array: ("let head = first([true]);", "head", "Bool"),
+    first_of_string: ("let head = first(\"abc\");", "head", "Char"),
+    push_keeps_element: ("let xs = push([1], 2);", "xs", "[Int]"),
+    push_of_other_type: ("let xs = push([1], \"a\");", "xs", "[Any]"),
+    length_of_parameter: ("let size = fn(x) { len(x) };", "size", "fn('a) -> Int"),
+    chars_of_string: ("let cs = chars(\"ab\");", "cs", "[Char]"),
+    union_of_sets: ("let s = union(#{1}, #{2});", "s", "#{Int}"),
+    recursive_function: (
+        "let fact = fn(n) { if (n < 2) { 1 } else { n * fact(n - 1) } };",
+        "fact",
+        "fn(Any) -> Int"
+    ),
+    declared_before_use: ("let r = twice(2); fn twice(n) { -n }", "r", "Int"),
+    declared_is_generic: ("fn pair(a) { (a, a) } let p = pair(:x);", "p", "(Symbol, Symbol)"),
+    tuple_destructuring: ("let (a, b) = (1, \"s\"); let c = b;", "c", "String"),
+    tuple_index: ("let t = (1, \"s\"); let s = t[2];", "s", "String"),
+    annotation_is_used: ("let f = fn(a: [Int]) { a };", "f", "fn([Int]) -> [Int]"),
+    different_returns: ("let f = fn(a) { if (a) { return 1 } \"s\" };", "f", "fn('a) -> Any"),
+    reassigned_variable: ("let x = 1; x = \"s\";", "x", "Any"),
+    string_concatenation: ("let s = \"a\" + 1;", "s", "String"),
+    parameters_added: ("let add = fn(a, b) { a + b };", "add", "fn('a, 'b) -> Any"),
+    overloaded_operator: ("let v = {\"__add__\": fn(a, b) { a }}; let r = v + v;", "r", "Any"),
+}
+
+infers_errors! {
+    call_of_integer: ("let x = 1; x(2);", vec!["1,13: Cannot call Int"]),
+    adding_function: (
+        "let f = fn(x) { x }; f + 1;",
+        vec!["1,24: Cannot use + on fn('a) -> 'a and Int"]
+    ),
+    argument_of_inferred_function: (
+        "let call = fn(f) { f(1) }; call(2);",
+        vec!["1,33: Argument 1 of call expected fn(Int) -> 'a, got Int"]
+    ),
+    wrong_number_of_arguments: (
+        "let add = fn(a, b) { a + b }; add(1);",
+        vec!["1,34: add expects 2 arguments, got 1"]
+    ),
+    map_values_added: (
+        "let m = {\"a\": 1}; m + m;",
+        vec!["1,21: Cannot use + on {String: Int} and {String: Int}"]
+    ),
+    indexing_boolean: ("let b = true; b[1];", vec!["1,18: Cannot index Bool"]),
+    array_index_of_string: ("[1][\"a\"];", vec!["1,5: Index expected Int, got String"]),
+    builtin_of_wrong_type: ("len(1);", vec!["1,4: Invalid arguments for len: Int"]),
+    builtin_arity: ("chars(\"a\", \"b\");", vec!["1,6: chars expects 1 arguments, got 2"]),
+    minus_of_string: ("let f = fn(s) { -s }; f(\"a\");", vec!["1,25: Argument 1 of f expected Int, got String"]),
+    annotation_mismatch: ("let x: String = 1 + 2;", vec!["1,19: x expected String, got Int"]),
+    result_of_inferred_call: (
+        "let size = fn(x) { len(x) }; let s: String = size(\"a\");",
+        vec!["1,50: s expected String, got Int"]
+    ),
+}
+
+#[test]
+fn unannotated_programs_are_accepted() {
+    let analysis = analyse(
+        "let map = fn(xs, f) { if (len(xs) == 0) { [] } else { push(map(rest(xs), f), f(first(xs))) } };
+         let doubled = map([1, 2], fn(x) { x * 2 });
+         let names = map([\"a\"], fn(x) { x + \"!\" });
+         puts(doubled, names);",
+    );
+    assert_eq!(analysis.diagnostics, vec![]);
+    assert_eq!(
+        analysis.type_of("map").unwrap().to_string(),
+        "fn('a, fn('b) -> 'c) -> ['c]"
+    );
+}
+
+#[test]
+fn operands_of_overloadable_operators_are_not_constrained() {
+    //strings repeat and join, maps override any operator
+    let programs = [
+        "let f = fn(a) { a * 2 }; f(\"ab\");",
+        "let f = fn(x) { \"a\" + x }; f(1);",
+        "let f = fn(x) { x + 1 }; f(\"a\");",
+        "let f = fn(x) { x - 1 }; f({\"__sub__\": fn(a, b) { a }});",
+    ];
+    for program in programs {
+        assert_eq!(analyse(program).diagnostics, vec![], "for {}", program);
+    }
+}
+
+#[test]
+fn check_source_lists_bindings() {
+    let result = crate::checker::check_source("let a = 1; let f = fn(x) { [x] };");
+    assert_eq!(
+        result,
+        Ok(vec!["a: Int".to_string(), "f: fn('a) -> ['a]".to_string()])
+    );
+}
+
+#[test]
+fn check_source_reports_errors() {
+    let result = crate::checker::check_source("let a = 1; a(1);");
+    assert_eq!(result, Err(vec!["1,13: Cannot call Int".to_string()]));
+}
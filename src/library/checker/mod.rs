@@ -0,0 +1,648 @@
+#[cfg(test)]
+mod checker_tests;
+mod inference;
+#[cfg(test)]
+mod inference_tests;
+
+pub use inference::{Analysis, Type, infer};
+
+use std::{collections::HashMap, fmt::Display, io::Read, rc::Rc};
+
+use crate::{
+    ast::{
+        expression::{Expression, InfixOperatorType, PrefixOperatorType},
+        statements::{Program, Statement},
+        types::TypeAnnotation,
+    },
+    lines::TokenPosition,
+    parser::{ParseError, Parser},
+    tokens::{Token, TokenKind},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub position: Option<TokenPosition>,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.position {
+            Some(position) => write!(f, "{}: {}", position.start, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+//everything not annotated is `Any`, so it fails only on operations failing for any value
+pub fn check(program: &Program) -> Vec<Diagnostic> {
+    let mut checker = Checker::default();
+    checker.check_block(&program.statements);
+    checker.diagnostics
+}
+
+pub fn check_source(source: &str) -> Result<Vec<String>, Vec<String>> {
+    check_parsed(Parser::from_string(source))
+}
+
+pub fn check_reader(reader: impl Read + 'static) -> Result<Vec<String>, Vec<String>> {
+    check_parsed(Parser::from_reader(reader))
+}
+
+fn check_parsed(mut parser: Parser) -> Result<Vec<String>, Vec<String>> {
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(parser.errors.iter().map(ParseError::to_string).collect());
+    }
+    let analysis = infer(&program);
+    if !analysis.diagnostics.is_empty() {
+        return Err(analysis.diagnostics.iter().map(|d| d.to_string()).collect());
+    }
+    Ok(analysis
+        .bindings
+        .iter()
+        .map(|(name, found)| format!("{}: {}", name, found))
+        .collect())
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    annotation: TypeAnnotation,
+    declared: bool, // annotated in the source, assignments have to keep to it
+}
+
+#[derive(Default)]
+struct Checker {
+    scopes: Vec<HashMap<String, Binding>>,
+    results: Vec<Option<TypeAnnotation>>, // annotated results of enclosing functions
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    fn report(&mut self, message: String, token: &Token) {
+        self.diagnostics.push(Diagnostic {
+            message,
+            position: token.context,
+        });
+    }
+
+    fn expect(
+        &mut self,
+        expected: &TypeAnnotation,
+        actual: &TypeAnnotation,
+        subject: &str,
+        token: &Token,
+    ) {
+        if !consistent(expected, actual) {
+            self.report(
+                format!("{} expected {}, got {}", subject, expected, actual),
+                token,
+            );
+        }
+    }
+
+    fn define(&mut self, name: &str, annotation: TypeAnnotation, declared: bool) {
+        self.scopes
+            .last_mut()
+            .expect("Definition outside of any scope")
+            .insert(
+                name.to_string(),
+                Binding {
+                    annotation,
+                    declared,
+                },
+            );
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn check_block(&mut self, statements: &[Statement]) -> TypeAnnotation {
+        self.scopes.push(HashMap::new());
+        //declared functions can be called before their declaration, as in the evaluator
+        for statement in statements {
+            if let Statement::Function { name, value, .. } = statement {
+                self.define(&name.to_string(), function_type(value), false);
+            }
+        }
+        let mut result = TypeAnnotation::Null;
+        for statement in statements {
+            result = self.check_statement(statement);
+        }
+        self.scopes.pop();
+        result
+    }
+
+    fn check_statement(&mut self, statement: &Statement) -> TypeAnnotation {
+        match statement {
+            Statement::Let {
+                name: Expression::Identifier(_),
+                value,
+                annotation,
+                ..
+            } => {
+                let name = statement_name(statement);
+                let value_type = self.check_expression(value);
+                match annotation {
+                    Some(annotation) => {
+                        self.expect(annotation, &value_type, &name, value.token());
+                        self.define(&name, annotation.clone(), true);
+                    }
+                    None => self.define(&name, value_type, false),
+                }
+                TypeAnnotation::Any
+            }
+            Statement::Let {
+                name: pattern,
+                value,
+                annotation,
+                ..
+            } => {
+                let value_type = self.check_expression(value);
+                let value_type = match annotation {
+                    Some(annotation) => {
+                        self.expect(annotation, &value_type, &pattern.to_string(), value.token());
+                        annotation.clone()
+                    }
+                    None => value_type,
+                };
+                self.bind_pattern(pattern, &value_type, annotation.is_some());
+                TypeAnnotation::Any
+            }
+            Statement::Assign { name, value, .. } => {
+                let value_type = self.check_expression(value);
+                let name = name.to_string();
+                match self.lookup(&name).cloned() {
+                    Some(binding) if binding.declared => {
+                        self.expect(&binding.annotation, &value_type, &name, value.token());
+                    }
+                    //unannotated variable holding other values is not known anymore
+                    Some(binding) if binding.annotation != value_type => self.widen(&name),
+                    _ => {}
+                }
+                TypeAnnotation::Any
+            }
+            Statement::Return { return_value, .. } => {
+                let value_type = self.check_expression(return_value);
+                if let Some(Some(result)) = self.results.last().cloned() {
+                    self.expect(&result, &value_type, "Return", return_value.token());
+                }
+                TypeAnnotation::Any
+            }
+            Statement::Function { value, .. } => {
+                self.check_expression(value);
+                TypeAnnotation::Any
+            }
+            Statement::Yield { value, .. } => {
+                self.check_expression(value);
+                TypeAnnotation::Any
+            }
+            Statement::AExpression { expression, .. } => self.check_expression(expression),
+            Statement::Block { statements, .. } => self.check_block(statements),
+            Statement::Operator { name, value, .. } => {
+                let value_type = self.check_expression(value);
+                self.define(&name.to_string(), value_type, false);
+                TypeAnnotation::Any
+            }
+        }
+    }
+
+    fn widen(&mut self, name: &str) {
+        if let Some(binding) = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            binding.annotation = TypeAnnotation::Any;
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &Expression, value_type: &TypeAnnotation, declared: bool) {
+        let elements = match pattern {
+            Expression::Identifier(_) => {
+                self.define(&pattern.to_string(), value_type.clone(), declared);
+                return;
+            }
+            Expression::TupleLiteral { elements, .. } => elements,
+            _ => return,
+        };
+        match value_type {
+            TypeAnnotation::Tuple(types) if types.len() == elements.len() => {
+                for (element, element_type) in elements.iter().zip(types) {
+                    self.bind_pattern(element, element_type, declared);
+                }
+                return;
+            }
+            TypeAnnotation::Any => {}
+            _ => self.report(
+                format!(
+                    "Cannot destructure {} into tuple of {} elements",
+                    value_type,
+                    elements.len()
+                ),
+                pattern.token(),
+            ),
+        }
+        for element in elements {
+            self.bind_pattern(element, &TypeAnnotation::Any, false);
+        }
+    }
+
+    fn check_expression(&mut self, expression: &Expression) -> TypeAnnotation {
+        match expression {
+            Expression::IntegerLiteral(_) => TypeAnnotation::Int,
+            Expression::StringLiteral(_) => TypeAnnotation::String,
+            Expression::SymbolLiteral(_) => TypeAnnotation::Symbol,
+            Expression::BooleanLiteral { .. } => TypeAnnotation::Bool,
+            Expression::Identifier(_) => {
+                let name = expression.to_string();
+                match self.lookup(&name) {
+                    Some(binding) => binding.annotation.clone(),
+                    None => builtin_type(&name),
+                }
+            }
+            Expression::PrefixOperator {
+                token,
+                operator,
+                right,
+                ..
+            } => {
+                let right = self.check_expression(right);
+                match (operator, &right) {
+                    (PrefixOperatorType::Minus, TypeAnnotation::Int | TypeAnnotation::Any) => {
+                        TypeAnnotation::Int
+                    }
+                    (
+                        PrefixOperatorType::Bang,
+                        TypeAnnotation::Bool
+                        | TypeAnnotation::String
+                        | TypeAnnotation::Int
+                        | TypeAnnotation::Null
+                        | TypeAnnotation::Any,
+                    ) => TypeAnnotation::Bool,
+                    _ => {
+                        self.report(format!("Cannot use {} on {}", operator, right), token);
+                        TypeAnnotation::Any
+                    }
+                }
+            }
+            Expression::Infix {
+                token,
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                let left = self.check_expression(left);
+                let right = self.check_expression(right);
+                infix_type(operator, &left, &right).unwrap_or_else(|| {
+                    self.report(
+                        format!("Cannot use {} on {} and {}", operator, left, right),
+                        token,
+                    );
+                    TypeAnnotation::Any
+                })
+            }
+            Expression::AIf {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => {
+                self.check_expression(condition);
+                let consequence = self.check_statement(consequence);
+                let alternative = match alternative {
+                    Some(alternative) => self.check_statement(alternative),
+                    None => TypeAnnotation::Null,
+                };
+                if consequence == alternative {
+                    consequence
+                } else {
+                    TypeAnnotation::Any
+                }
+            }
+            Expression::Call {
+                token,
+                function,
+                arguments,
+                ..
+            } => self.check_call(token, function, arguments),
+            Expression::FunctionLiteral { .. } => self.check_function(expression),
+            Expression::MacroLiteral { .. } => TypeAnnotation::Any,
+            Expression::ArrayLiteral { elements, .. } => {
+                TypeAnnotation::Array(Box::new(self.common_type(elements)))
+            }
+            Expression::SetLiteral { elements, .. } => {
+                TypeAnnotation::Set(Box::new(self.common_type(elements)))
+            }
+            Expression::TupleLiteral { elements, .. } => TypeAnnotation::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.check_expression(element))
+                    .collect(),
+            ),
+            Expression::MapLiteral { elements, .. } => {
+                let (mut keys, mut values) = (vec![], vec![]);
+                for (key, value) in elements {
+                    keys.push(self.check_expression(key));
+                    values.push(self.check_expression(value));
+                }
+                TypeAnnotation::Map(Box::new(common(keys)), Box::new(common(values)))
+            }
+            Expression::Index {
+                token,
+                array,
+                index,
+                ..
+            } => self.check_index(token, array, index),
+            Expression::Decorated {
+                decorators, value, ..
+            } => {
+                let mut decorated = self.check_expression(value);
+                for decorator in decorators.iter().rev() {
+                    decorated = match self.check_expression(decorator) {
+                        TypeAnnotation::Function(_, result) => *result,
+                        _ => TypeAnnotation::Any,
+                    };
+                }
+                decorated
+            }
+        }
+    }
+
+    fn common_type(&mut self, elements: &[Expression]) -> TypeAnnotation {
+        let types = elements
+            .iter()
+            .map(|element| self.check_expression(element))
+            .collect();
+        common(types)
+    }
+
+    fn check_index(
+        &mut self,
+        token: &Token,
+        collection: &Expression,
+        index: &Expression,
+    ) -> TypeAnnotation {
+        let collection_type = self.check_expression(collection);
+        let index_type = self.check_expression(index);
+        match collection_type {
+            TypeAnnotation::Array(element) => {
+                self.expect(&TypeAnnotation::Int, &index_type, "Index", index.token());
+                *element
+            }
+            TypeAnnotation::String => {
+                self.expect(&TypeAnnotation::Int, &index_type, "Index", index.token());
+                TypeAnnotation::Char
+            }
+            TypeAnnotation::Map(key, value) => {
+                self.expect(&key, &index_type, "Key", index.token());
+                *value
+            }
+            TypeAnnotation::Tuple(elements) => {
+                self.expect(&TypeAnnotation::Int, &index_type, "Index", index.token());
+                //elements are counted from 1
+                match &index.token().kind {
+                    TokenKind::Integer(position)
+                        if (1..=elements.len()).contains(&(*position as usize)) =>
+                    {
+                        elements[*position as usize - 1].clone()
+                    }
+                    _ => TypeAnnotation::Any,
+                }
+            }
+            TypeAnnotation::Any => TypeAnnotation::Any,
+            other => {
+                self.report(format!("Cannot index {}", other), token);
+                TypeAnnotation::Any
+            }
+        }
+    }
+
+    fn check_call(
+        &mut self,
+        token: &Rc<Token>,
+        function: &Expression,
+        arguments: &[Expression],
+    ) -> TypeAnnotation {
+        let callee = self.check_expression(function);
+        let argument_types = arguments
+            .iter()
+            .map(|argument| self.check_expression(argument))
+            .collect::<Vec<_>>();
+        match callee {
+            TypeAnnotation::Function(parameters, result) => {
+                if parameters.len() != arguments.len() {
+                    self.report(
+                        format!(
+                            "{} expects {} arguments, got {}",
+                            function,
+                            parameters.len(),
+                            arguments.len()
+                        ),
+                        token,
+                    );
+                }
+                for (position, (parameter, (argument, argument_type))) in parameters
+                    .iter()
+                    .zip(arguments.iter().zip(argument_types.iter()))
+                    .enumerate()
+                {
+                    self.expect(
+                        parameter,
+                        argument_type,
+                        &format!("Argument {} of {}", position + 1, function),
+                        argument.token(),
+                    );
+                }
+                *result
+            }
+            TypeAnnotation::Any => TypeAnnotation::Any,
+            other => {
+                self.report(format!("Cannot call {}", other), token);
+                TypeAnnotation::Any
+            }
+        }
+    }
+
+    fn check_function(&mut self, expression: &Expression) -> TypeAnnotation {
+        let Expression::FunctionLiteral {
+            token,
+            parameters,
+            body,
+            name,
+            generator,
+            signature,
+            ..
+        } = expression
+        else {
+            return TypeAnnotation::Any;
+        };
+        let annotated = match signature {
+            Some(signature) => signature.parameters.clone(),
+            None => vec![None; parameters.len()],
+        };
+        self.scopes.push(HashMap::new());
+        if let Some(name) = name {
+            self.define(name, function_type(expression), false);
+        }
+        for (parameter, annotation) in parameters.iter().zip(annotated) {
+            let declared = annotation.is_some();
+            let annotation = annotation.unwrap_or(TypeAnnotation::Any);
+            self.define(&parameter.to_string(), annotation, declared);
+        }
+        //body of a generator runs when values are taken, the call gives a generator
+        let result = signature
+            .as_ref()
+            .and_then(|signature| signature.result.clone())
+            .filter(|_| !generator);
+        self.results.push(result.clone());
+        let value = self.check_statement(body);
+        self.results.pop();
+        self.scopes.pop();
+        if let Some(result) = result {
+            self.expect(&result, &value, "Result", token);
+        }
+        function_type(expression)
+    }
+}
+
+fn statement_name(statement: &Statement) -> String {
+    match statement {
+        Statement::Let { name, .. } => name.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn function_type(function: &Expression) -> TypeAnnotation {
+    match function {
+        Expression::FunctionLiteral {
+            generator: true,
+            parameters,
+            ..
+        } => TypeAnnotation::Function(
+            vec![TypeAnnotation::Any; parameters.len()],
+            Box::new(TypeAnnotation::Any),
+        ),
+        Expression::FunctionLiteral {
+            signature: Some(signature),
+            ..
+        } => signature.function_type(),
+        Expression::FunctionLiteral { parameters, .. } => TypeAnnotation::Function(
+            vec![TypeAnnotation::Any; parameters.len()],
+            Box::new(TypeAnnotation::Any),
+        ),
+        _ => TypeAnnotation::Any,
+    }
+}
+
+fn builtin_type(name: &str) -> TypeAnnotation {
+    let function = |parameters: usize, result| {
+        TypeAnnotation::Function(vec![TypeAnnotation::Any; parameters], Box::new(result))
+    };
+    match name {
+        "len" => function(1, TypeAnnotation::Int),
+        "str" => function(1, TypeAnnotation::String),
+        "contains" => function(2, TypeAnnotation::Bool),
+        _ => TypeAnnotation::Any,
+    }
+}
+
+fn common(types: Vec<TypeAnnotation>) -> TypeAnnotation {
+    match types.split_first() {
+        Some((first, rest)) if rest.iter().all(|other| other == first) => first.clone(),
+        _ => TypeAnnotation::Any,
+    }
+}
+
+fn consistent(expected: &TypeAnnotation, actual: &TypeAnnotation) -> bool {
+    match (expected, actual) {
+        (TypeAnnotation::Any, _) | (_, TypeAnnotation::Any) => true,
+        (TypeAnnotation::Array(expected), TypeAnnotation::Array(actual))
+        | (TypeAnnotation::Set(expected), TypeAnnotation::Set(actual)) => {
+            consistent(expected, actual)
+        }
+        (
+            TypeAnnotation::Map(expected_key, expected_value),
+            TypeAnnotation::Map(actual_key, actual_value),
+        ) => consistent(expected_key, actual_key) && consistent(expected_value, actual_value),
+        (TypeAnnotation::Tuple(expected), TypeAnnotation::Tuple(actual)) => {
+            all_consistent(expected, actual)
+        }
+        (
+            TypeAnnotation::Function(expected_parameters, expected_result),
+            TypeAnnotation::Function(actual_parameters, actual_result),
+        ) => {
+            all_consistent(expected_parameters, actual_parameters)
+                && consistent(expected_result, actual_result)
+        }
+        _ => expected == actual,
+    }
+}
+
+fn all_consistent(expected: &[TypeAnnotation], actual: &[TypeAnnotation]) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .iter()
+            .zip(actual)
+            .all(|(expected, actual)| consistent(expected, actual))
+}
+
+//a map may override operators, so only maps which cannot hold functions are rejected
+fn infix_type(
+    operator: &InfixOperatorType,
+    left: &TypeAnnotation,
+    right: &TypeAnnotation,
+) -> Option<TypeAnnotation> {
+    use TypeAnnotation as T;
+    let text = match operator {
+        InfixOperatorType::Plus => Some(T::String),
+        InfixOperatorType::Equal | InfixOperatorType::NotEqual => Some(T::Bool),
+        _ => None,
+    };
+    let equality = matches!(
+        operator,
+        InfixOperatorType::Equal | InfixOperatorType::NotEqual
+    );
+    match (left, right) {
+        (T::Map(_, value), _) | (_, T::Map(_, value)) if may_override(value) => Some(T::Any),
+        (T::Map(..), _) | (_, T::Map(..)) => None,
+        (T::Any, _) | (_, T::Any) => Some(T::Any),
+        (T::Int, T::Int) => match operator {
+            InfixOperatorType::Plus
+            | InfixOperatorType::Minus
+            | InfixOperatorType::Multiply
+            | InfixOperatorType::Divide => Some(T::Int),
+            _ => Some(T::Bool),
+        },
+        (T::Int, T::String) | (T::Bool, T::Int) => {
+            matches!(operator, InfixOperatorType::Plus).then_some(T::String)
+        }
+        (T::String, T::Int) => matches!(
+            operator,
+            InfixOperatorType::Plus | InfixOperatorType::Multiply
+        )
+        .then_some(T::String),
+        (T::String, T::String | T::Bool | T::Null | T::Char) | (T::Bool | T::Char, T::String) => {
+            text
+        }
+        (T::Char, T::Char) => match operator {
+            InfixOperatorType::Plus => Some(T::String),
+            InfixOperatorType::Minus | InfixOperatorType::Multiply | InfixOperatorType::Divide => {
+                None
+            }
+            _ => Some(T::Bool),
+        },
+        (T::Bool, T::Bool)
+        | (T::Tuple(_), T::Tuple(_))
+        | (T::Symbol, T::Symbol)
+        | (T::Set(_), T::Set(_)) => equality.then_some(T::Bool),
+        _ => None,
+    }
+}
+
+fn may_override(value: &TypeAnnotation) -> bool {
+    matches!(value, TypeAnnotation::Any | TypeAnnotation::Function(..))
+}
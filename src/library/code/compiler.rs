@@ -2,17 +2,19 @@ use std::{cell::RefCell, rc::Rc, vec};
 
 use crate::{
     ast::{
-        base::Node,
+        base::{Node, NodeRef},
         expression::{Expression, InfixOperatorType},
         statements::{Program, Statement},
     },
     code::{
         OpCode,
         definitions::{Byte, Instructions, OpCodes},
+        lookup,
         make::make,
+        read_u_16,
         symbol_table::SymbolTable,
     },
-    object::{CompiledFunctionEntry, Object},
+    object::{CompiledFunctionEntry, Object, intern},
     tokens::{self, Token, TokenKind},
 };
 
@@ -52,6 +54,7 @@ pub(crate) struct CompilationScope {
     pub(crate) instructions: Vec<Byte>,
     pub(crate) last_instruction: Option<EmitedInstruction>,
     pub(crate) previous_instruction: Option<EmitedInstruction>, //can temporary show not correct values, it is
+    pub(crate) positions: Vec<(usize, (usize, usize))>,
 }
 
 impl CompilationScope {
@@ -60,6 +63,7 @@ impl CompilationScope {
             instructions: vec![],
             last_instruction: None,
             previous_instruction: None,
+            positions: vec![],
         }
     }
 }
@@ -70,7 +74,8 @@ pub(crate) struct Worker {
     pub(crate) scopes: Vec<CompilationScope>,
     pub(crate) scope_index: usize,
     pub(crate) symbol_table: Rc<RefCell<SymbolTable>>,
-    //TODO: provide symbol map: bytes -> position, stil naive no stack trace
+    pub(crate) strict: bool,
+    //TODO: positions are kept only for operators and patterns, stil naive no stack trace
 }
 
 macro_rules! scope {
@@ -101,6 +106,7 @@ impl Worker {
             symbol_table: SymbolTable::new_table(),
             scopes: vec![main_scope],
             scope_index: 0,
+            strict: false,
         }
     }
 
@@ -123,6 +129,11 @@ impl Worker {
         return possition;
     }
 
+    fn mark_position(&mut self, token: &Token) {
+        let offset = self.current_instructions_lenght();
+        scope_mut!(self).positions.push((offset, token.position()));
+    }
+
     fn current_instructions_lenght(&self) -> usize {
         scope!(self).instructions.len()
     }
@@ -160,34 +171,60 @@ impl Worker {
     }
 
     fn compile<T: Node>(&mut self, node: &T) {
-        let statement = node.as_any().downcast_ref::<Statement>();
-        if let Some(statement) = statement {
-            self.compile_statement(statement);
-            return;
-        }
-        let program = node.as_any().downcast_ref::<Program>();
-        if let Some(program) = program {
-            self.compile_program(program);
-            return;
-        }
-
-        let expression = node.as_any().downcast_ref::<Expression>();
-        if let Some(expression) = expression {
-            self.compile_expression(expression);
-            return;
+        match node.as_node() {
+            NodeRef::Program(program) => self.compile_program(program),
+            NodeRef::Statement(statement) => self.compile_statement(statement),
+            NodeRef::Expression(expression) => self.compile_expression(expression),
         }
-        panic!("Should never reach here, node: {:?}", node);
     }
 
     fn compile_program(&mut self, program: &Program) {
-        for statement in program.statements.clone() {
-            self.compile_statement(&statement);
+        self.strict = program.is_strict();
+        self.compile_statements(&program.statements);
+    }
+
+    //declarations are compiled first, so they can call each other and be called before they appear
+    fn compile_statements(&mut self, statements: &[Statement]) {
+        let declarations = statements
+            .iter()
+            .filter_map(|s| match s {
+                Statement::Function {
+                    name: Expression::Identifier(token),
+                    value,
+                    ..
+                } => match &token.kind {
+                    TokenKind::Identifier(name) => Some((name.clone(), value)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for (name, _) in &declarations {
+            SymbolTable::define(&self.symbol_table, name);
+        }
+        for (name, value) in declarations {
+            self.compile_let(name, value);
         }
+        statements.iter().for_each(|s| self.compile_statement(s));
     }
 
     fn compile_statement(&mut self, statement: &Statement) {
         match statement {
-            Statement::Let { token, name, value } => {
+            Statement::Let {
+                token,
+                name: pattern @ Expression::TupleLiteral { .. },
+                value,
+                ..
+            } => {
+                self.compile_expression(value);
+                self.bind_pattern(pattern, token.clone());
+            }
+            Statement::Let {
+                token, name, value, ..
+            }
+            | Statement::Operator {
+                token, name, value, ..
+            } => {
                 let name = match name {
                     Expression::Identifier(token) => match &token.kind {
                         crate::tokens::TokenKind::Identifier(v) => v,
@@ -203,27 +240,49 @@ impl Worker {
                 };
                 self.compile_let(name.to_string(), value)
             }
-            Statement::Return {
-                token: _,
-                return_value,
+            Statement::Assign {
+                token, name, value, ..
             } => {
+                let name = match name {
+                    Expression::Identifier(token) => match &token.kind {
+                        crate::tokens::TokenKind::Identifier(v) => v,
+                        _ => {
+                            self.add_errors(CompilationError::UnexpectedSymbol(token.clone()));
+                            return;
+                        }
+                    },
+                    _ => {
+                        self.add_errors(CompilationError::UnexpectedSymbol(token.clone()));
+                        return;
+                    }
+                };
+                self.compile_assign(name.to_string(), value, token.clone())
+            }
+            // already compiled when its scope was entered
+            Statement::Function { .. } => {}
+            Statement::Return { return_value, .. } => {
                 self.compile_expression(return_value);
                 self.emit_op_code(OpCodes::ReturnValue);
             }
-            Statement::AExpression {
-                token: _,
-                expression,
+            Statement::Yield {
+                value, delegate, ..
             } => {
+                self.compile_expression(value);
+                match delegate {
+                    true => self.emit_op_code(OpCodes::Delegate),
+                    false => self.emit_op_code(OpCodes::Yield),
+                };
+            }
+            Statement::AExpression { expression, .. } => {
                 self.compile_expression(expression);
                 self.emit_op_code(OpCodes::Pop);
             }
-            Statement::Block {
-                token: _,
-                statements,
-            } => {
-                statements
-                    .iter()
-                    .for_each(|s| self.compile_statement(&s.clone()));
+            Statement::Block { statements, .. } => {
+                SymbolTable::enter_block(&self.symbol_table);
+                self.compile_statements(statements);
+                if let Some(first_slot) = SymbolTable::leave_block(&self.symbol_table) {
+                    self.close_block_slots(first_slot);
+                }
             }
         }
     }
@@ -238,7 +297,18 @@ impl Worker {
                         return;
                     }
                 };
-                let value = Object::Int(value.into());
+                let value = Object::Int(value);
+                let constant_possition = self.add_constant(value);
+                self.emit(OpCodes::Constant, &[constant_possition]);
+            }
+            Expression::SymbolLiteral(token) => {
+                let value = match &token.kind {
+                    crate::tokens::TokenKind::Symbol(name) => Object::Symbol(intern(name)),
+                    _ => {
+                        self.add_errors(CompilationError::UnexpectedSymbol(token.clone()));
+                        return;
+                    }
+                };
                 let constant_possition = self.add_constant(value);
                 self.emit(OpCodes::Constant, &[constant_possition]);
             }
@@ -255,10 +325,11 @@ impl Worker {
                 self.emit(OpCodes::Constant, &[constant_possition]);
             }
             Expression::Infix {
-                token: _,
+                token,
                 left,
                 operator,
                 right,
+                ..
             } => {
                 match operator {
                     InfixOperatorType::LessThan => {
@@ -270,6 +341,7 @@ impl Worker {
                         self.compile_expression(&right);
                     }
                 }
+                self.mark_position(token);
                 match operator {
                     InfixOperatorType::Plus => self.emit_op_code(OpCodes::Add),
                     InfixOperatorType::Divide => self.emit_op_code(OpCodes::Divide),
@@ -288,9 +360,7 @@ impl Worker {
                 };
             }
             Expression::PrefixOperator {
-                token: _,
-                operator,
-                right,
+                operator, right, ..
             } => {
                 self.compile_expression(&right);
                 match operator {
@@ -303,17 +373,15 @@ impl Worker {
                 }
             }
             Expression::AIf {
-                token: _,
                 condition,
                 consequence,
                 alternative,
+                ..
             } => {
                 self.compile_expression(&condition);
                 let jump_after_consequences = self.emit(OpCodes::JumpNotTruthy, &[9999]);
                 self.compile_statement(consequence.as_ref());
-                if self.last_instruction_is(OpCodes::Pop) {
-                    self.remove_last_pop();
-                }
+                self.keep_branch_value();
                 let ajump_to_end_of_conditional = self.emit(OpCodes::Jump, &[9999]);
                 self.change_operand(
                     jump_after_consequences,
@@ -322,9 +390,7 @@ impl Worker {
                 match alternative {
                     Some(body) => {
                         self.compile_statement(&body);
-                        if self.last_instruction_is(OpCodes::Pop) {
-                            self.remove_last_pop();
-                        }
+                        self.keep_branch_value();
                     }
                     None => {
                         self.emit_op_code(OpCodes::Null);
@@ -341,14 +407,37 @@ impl Worker {
                     _ => self.add_errors(CompilationError::UnexpectedSymbol(token.clone())),
                 };
             }
-            Expression::ArrayLiteral { token: _, elements } => {
+            Expression::ArrayLiteral { elements, .. } => {
                 for element in elements {
                     self.compile(element);
                 }
                 //TODO exception when usize larger than u16
                 self.emit(OpCodes::Array, &[elements.len() as u16]);
             }
-            Expression::MapLiteral { token: _, elements } => {
+            Expression::TupleLiteral { elements, .. } => {
+                for element in elements {
+                    self.compile(element);
+                }
+                self.emit(OpCodes::Tuple, &[elements.len() as u16]);
+            }
+            Expression::Decorated {
+                decorators, value, ..
+            } => {
+                for decorator in decorators {
+                    self.compile(decorator);
+                }
+                self.compile(value.as_ref());
+                for _ in decorators {
+                    self.emit(OpCodes::Call, &[1]);
+                }
+            }
+            Expression::SetLiteral { elements, .. } => {
+                for element in elements {
+                    self.compile(element);
+                }
+                self.emit(OpCodes::Set, &[elements.len() as u16]);
+            }
+            Expression::MapLiteral { elements, .. } => {
                 for (key, value) in elements {
                     self.compile(key);
                     self.compile(value);
@@ -356,11 +445,7 @@ impl Worker {
                 let size = elements.len() * 2;
                 self.emit(OpCodes::Hash, &[size as u16]);
             }
-            Expression::Index {
-                token: _,
-                array,
-                index,
-            } => {
+            Expression::Index { array, index, .. } => {
                 self.compile(array.as_ref());
                 self.compile(index.as_ref());
                 self.emit_op_code(OpCodes::Index);
@@ -370,6 +455,8 @@ impl Worker {
                 parameters,
                 body,
                 name,
+                generator,
+                ..
             } => {
                 self.enter_scope();
                 if let Some(found_name) = name {
@@ -393,25 +480,36 @@ impl Worker {
                     }
                 }
 
-                self.compile(body.as_ref());
+                //body shares scope with parameters, leaving the frame closes captured slots
+                match body.as_ref() {
+                    Statement::Block { statements, .. } => self.compile_statements(statements),
+                    _ => self.compile(body.as_ref()),
+                }
                 if self.last_instruction_is(OpCodes::Pop) {
                     self.replace_last_pop_with_return()
                 }
                 if !self.last_instruction_is(OpCodes::ReturnValue) {
                     self.emit_op_code(OpCodes::ReturnNone);
                 }
+                //frame of a generator is suspended on yield, it can not be replaced by callee
+                if !generator {
+                    self.mark_tail_calls();
+                }
                 let number_of_locals = SymbolTable::number_of_locals(&self.symbol_table);
                 let free_symbols = &self.symbol_table.borrow().free_symbols.clone(); //Has to
                 //happen before leave scope, otherwise free variables will be lost after leaving
                 //scope
+                let positions = std::mem::take(&mut scope_mut!(self).positions);
                 let instructions = self.leave_scope();
                 for free in free_symbols {
-                    self.load_symbol(&free.name, token.clone());
+                    self.capture_symbol(&free.name, token.clone());
                 }
                 let compiled_function = Object::CompiledFunction(CompiledFunctionEntry {
                     instructions,
                     number_of_locals,
                     number_of_parameters: parameters.len(),
+                    generator: *generator,
+                    positions,
                 });
                 let constant_position = self.add_constant(compiled_function);
                 self.emit(
@@ -423,6 +521,7 @@ impl Worker {
                 token,
                 function,
                 arguments,
+                ..
             } => {
                 self.check_call(&function, token.clone(), &arguments);
                 self.compile_expression(&function);
@@ -461,6 +560,48 @@ impl Worker {
         }
     }
 
+    //closure shares a cell with the scope which defined it, later changes are seen on both sides
+    fn capture_symbol(&mut self, name: &String, token: Rc<Token>) {
+        match SymbolTable::resolve(&self.symbol_table, name).map(|s| (s.what_type(), s.index)) {
+            Some((SymbolType::LOCAL, index)) => {
+                self.emit(OpCodes::CaptureLocal, &[index]);
+            }
+            Some((SymbolType::FREE, index)) => {
+                self.emit(OpCodes::CaptureFree, &[index]);
+            }
+            Some((SymbolType::FUNCTION, _)) => {
+                self.emit_op_code(OpCodes::CaptureClosure);
+            }
+            _ => {
+                self.add_errors(CompilationError::UndefinedVariable(
+                    token.clone(),
+                    name.to_string(),
+                ));
+            }
+        }
+    }
+
+    //block ending with `let` or `yield` leaves nothing on the stack, null is its value
+    fn keep_branch_value(&mut self) {
+        if self.last_instruction_is(OpCodes::Pop) {
+            self.remove_last_pop();
+        } else if !self.last_instruction_is(OpCodes::ReturnValue) {
+            self.emit_op_code(OpCodes::Null);
+        }
+    }
+
+    //value of the block has to stay the last instruction, conditionals and functions use it
+    fn close_block_slots(&mut self, first_slot: u16) {
+        let ends_with_pop = self.last_instruction_is(OpCodes::Pop);
+        if ends_with_pop {
+            self.remove_last_pop();
+        }
+        self.emit(OpCodes::CloseFree, &[first_slot]);
+        if ends_with_pop {
+            self.emit_op_code(OpCodes::Pop);
+        }
+    }
+
     fn replace_instructions(&mut self, possition: usize, new_instruction: Instructions) {
         let mut i = 0;
         let bytes = new_instruction.bytes();
@@ -509,8 +650,36 @@ impl Worker {
     }
 
     fn compile_let(&mut self, name: String, value: &Expression) {
-        let symbol = SymbolTable::define(&self.symbol_table, &name);
+        //value is compiled before the name is defined, so `let x = x + 1` in a block reads the
+        //outer x, same as in evaluator. Decorated function refers to itself through the name.
+        if let Expression::Decorated { .. } = value {
+            SymbolTable::define(&self.symbol_table, &name);
+        }
         self.compile_expression(value);
+        self.define_value(name);
+    }
+
+    //elements are on the stack with the last one on top, so names are bound from the last
+    fn bind_pattern(&mut self, pattern: &Expression, token: Rc<Token>) {
+        match pattern {
+            Expression::Identifier(name_token) => match &name_token.kind {
+                TokenKind::Identifier(name) => self.define_value(name.clone()),
+                _ => self.add_errors(CompilationError::UnexpectedSymbol(name_token.clone())),
+            },
+            Expression::TupleLiteral { elements, .. } => {
+                self.mark_position(&token);
+                self.emit(OpCodes::Destructure, &[elements.len() as u16]);
+                for element in elements.iter().rev() {
+                    self.bind_pattern(element, token.clone());
+                }
+            }
+            _ => self.add_errors(CompilationError::UnexpectedSymbol(token)),
+        }
+    }
+
+    //value on top of the stack is stored under a new name
+    fn define_value(&mut self, name: String) {
+        let symbol = SymbolTable::define(&self.symbol_table, &name);
         let op_code = match symbol.what_type() {
             SymbolType::GLOBAL => OpCodes::SetGlobal,
             SymbolType::LOCAL => OpCodes::SetLocal,
@@ -528,6 +697,45 @@ impl Worker {
         self.emit(op_code, &[symbol.index]);
     }
 
+    fn compile_assign(&mut self, name: String, value: &Expression, token: Rc<Token>) {
+        self.compile_expression(value);
+        let symbol = match SymbolTable::resolve(&self.symbol_table, &name) {
+            Some(v) => v,
+            None => {
+                self.add_errors(CompilationError::UndefinedVariable(token, name));
+                return;
+            }
+        };
+        let op_code = match symbol.what_type() {
+            SymbolType::GLOBAL => OpCodes::SetGlobal,
+            SymbolType::LOCAL => OpCodes::SetLocal,
+            SymbolType::FREE => OpCodes::SetFree,
+            SymbolType::BUILTIN => {
+                self.add_errors(CompilationError::BuiltinCannotBeSet(name));
+                return;
+            }
+            SymbolType::FUNCTION => {
+                self.add_errors(CompilationError::FunctionCannotBeSet(name));
+                return;
+            }
+        };
+        self.emit(op_code, &[symbol.index]);
+    }
+
+    //calls followed only by a return reuse the frame, so tail recursion runs in constant stack
+    fn mark_tail_calls(&mut self) {
+        let instructions = scope!(self).instructions.clone();
+        let call: u8 = OpCodes::Call.into();
+        let mut index = 0;
+        while index < instructions.len() {
+            let width = instruction_width(&instructions, index);
+            if instructions[index].0 == call && returns_from(&instructions, index + width) {
+                self.change_bytecode(index, Byte(OpCodes::TailCall as u8));
+            }
+            index += width;
+        }
+    }
+
     fn replace_last_pop_with_return(&mut self) {
         let scope = scope!(self);
         let pop_position = match scope.last_instruction {
@@ -547,10 +755,12 @@ impl Worker {
     fn check_call(&mut self, function: &Expression, token: Rc<Token>, arguments: &[Expression]) {
         match function {
             Expression::FunctionLiteral {
-                token: _,
                 parameters,
                 body: _,
                 name: _,
+                generator: _,
+                signature: _,
+                ..
             } => {
                 if parameters.len() != arguments.len() {
                     self.add_errors(CompilationError::WrongNumberOfArguments {
@@ -575,6 +785,8 @@ impl From<Worker> for Result<Bytecode, Vec<CompilationError>> {
         Result::Ok(Bytecode {
             instructions: Instructions(current_scope.instructions.clone()),
             constants: value.constants,
+            strict: value.strict,
+            positions: current_scope.positions.clone(),
         })
     }
 }
@@ -582,4 +794,34 @@ impl From<Worker> for Result<Bytecode, Vec<CompilationError>> {
 pub struct Bytecode {
     pub instructions: Instructions,
     pub constants: Vec<Object>,
+    pub strict: bool, //program started with "use strict"
+    pub positions: Vec<(usize, (usize, usize))>,
+}
+
+fn instruction_width(instructions: &[Byte], index: usize) -> usize {
+    let definition = lookup(&OpCode(instructions[index].clone()))
+        .unwrap_or_else(|e| panic!("Unknown instruction at {index}: {e:?}"));
+    1 + definition.operands_widths.iter().sum::<usize>()
+}
+
+//closing captured slots does not count, calling in place closes them anyway
+fn returns_from(instructions: &[Byte], mut index: usize) -> bool {
+    let jump: u8 = OpCodes::Jump.into();
+    let close_free: u8 = OpCodes::CloseFree.into();
+    let return_value: u8 = OpCodes::ReturnValue.into();
+    while let Some(byte) = instructions.get(index) {
+        match byte.0 {
+            op if op == return_value => return true,
+            op if op == close_free => index += instruction_width(instructions, index),
+            op if op == jump => {
+                let target = read_u_16(&instructions[index + 1..]) as usize;
+                if target <= index {
+                    return false;
+                }
+                index = target;
+            }
+            _ => return false,
+        }
+    }
+    false
 }
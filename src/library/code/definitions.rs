@@ -151,6 +151,17 @@ pub enum OpCodes {
     Closure,
     GetFree,
     CurrentClosure,
+    SetFree,
+    CaptureLocal,
+    CaptureFree,
+    CloseFree,
+    CaptureClosure,
+    TailCall,
+    Yield,
+    Delegate,
+    Tuple,
+    Destructure,
+    Set,
 }
 impl OpCodes {
     fn string(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -185,6 +196,17 @@ impl OpCodes {
             OpCodes::Closure => f.write_str("Closure"),
             OpCodes::GetFree => f.write_str("GetFree"),
             OpCodes::CurrentClosure => f.write_str("CurrentClosure"),
+            OpCodes::SetFree => f.write_str("SetFree"),
+            OpCodes::CaptureLocal => f.write_str("CaptureLocal"),
+            OpCodes::CaptureFree => f.write_str("CaptureFree"),
+            OpCodes::CloseFree => f.write_str("CloseFree"),
+            OpCodes::CaptureClosure => f.write_str("CaptureClosure"),
+            OpCodes::TailCall => f.write_str("tail call"),
+            OpCodes::Yield => f.write_str("yield"),
+            OpCodes::Delegate => f.write_str("yield*"),
+            OpCodes::Tuple => f.write_str("()"),
+            OpCodes::Destructure => f.write_str("Destructure"),
+            OpCodes::Set => f.write_str("#{}"),
         }
     }
 }
@@ -49,6 +49,17 @@ static DEFINITIONS: LazyLock<HashMap<OpCode, Definition>> = LazyLock::new(|| {
         //variables in function]
         pair(OpCodes::GetFree, vec![1]),
         pair(OpCodes::CurrentClosure, vec![]),
+        pair(OpCodes::SetFree, vec![1]),
+        pair(OpCodes::CaptureLocal, vec![1]), //local slot shared with next closure
+        pair(OpCodes::CaptureFree, vec![1]),  //free variable shared with next closure
+        pair(OpCodes::CloseFree, vec![1]),    //first local slot of block which is left
+        pair(OpCodes::CaptureClosure, vec![]), //running closure shared with next closure
+        pair(OpCodes::TailCall, vec![1]),     //number of arguments passed, frame is reused
+        pair(OpCodes::Yield, vec![]),         //frame of generator is suspended with the value
+        pair(OpCodes::Delegate, vec![]),      //values of generator on the stack are yielded
+        pair(OpCodes::Tuple, vec![2]),        //operand number of elements in tuple
+        pair(OpCodes::Destructure, vec![2]),  //tuple is replaced by its elements, last on top
+        pair(OpCodes::Set, vec![2]),          //operand number of elements in set
     ]);
 });
 
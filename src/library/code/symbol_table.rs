@@ -1,4 +1,4 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, cmp, collections::HashMap, rc::Rc};
 
 use crate::{code::symbol_table, object::BuiltInFunction};
 
@@ -27,7 +27,9 @@ impl Symbol {
 
 pub(crate) struct SymbolTable {
     store: HashMap<String, Rc<Symbol>>,
+    blocks: Vec<BlockScope>,
     counter: u16,
+    max_counter: u16,
     level: usize,
     outer: Option<Rc<RefCell<SymbolTable>>>,
     builtin_scope: Rc<RefCell<BuiltinScope>>,
@@ -38,11 +40,20 @@ struct BuiltinScope {
     counter: u16,
 }
 
+//slots of a block are given back when it ends, so sibling blocks share them
+struct BlockScope {
+    store: HashMap<String, Rc<Symbol>>,
+    counter_at_entry: u16,
+    captured: bool,
+}
+
 impl SymbolTable {
     fn new(builtin: BuiltinScope) -> Self {
         SymbolTable {
             store: HashMap::new(),
+            blocks: vec![],
             counter: 0,
+            max_counter: 0,
             level: 0,
             outer: None,
             builtin_scope: Rc::new(RefCell::new(builtin)),
@@ -51,6 +62,11 @@ impl SymbolTable {
     }
 
     pub fn define(symbol_table: &Rc<RefCell<SymbolTable>>, name: &str) -> Rc<Symbol> {
+        //redefinition in the same scope overrides the variable, same as evaluator does, so
+        //closures which captured it see the new value
+        if let Some(existing) = symbol_table.borrow().find_in_current_scope(name) {
+            return existing;
+        }
         let symbol = Rc::new(Symbol {
             name: name.to_string(),
             index: symbol_table.borrow().counter,
@@ -60,14 +76,69 @@ impl SymbolTable {
                 _ => SymbolType::LOCAL,
             },
         });
-        symbol_table
-            .borrow_mut()
-            .store
-            .insert(name.to_string(), symbol.clone());
-        symbol_table.borrow_mut().counter += 1;
+        let mut table = symbol_table.borrow_mut();
+        match table.blocks.last_mut() {
+            Some(block) => block.store.insert(name.to_string(), symbol.clone()),
+            None => table.store.insert(name.to_string(), symbol.clone()),
+        };
+        table.counter += 1;
+        table.max_counter = cmp::max(table.max_counter, table.counter);
         symbol.clone()
     }
 
+    pub fn enter_block(symbol_table: &Rc<RefCell<SymbolTable>>) {
+        let mut table = symbol_table.borrow_mut();
+        let counter_at_entry = table.counter;
+        table.blocks.push(BlockScope {
+            store: HashMap::new(),
+            counter_at_entry,
+            captured: false,
+        });
+    }
+
+    //only local slots are reused, closures read globals by their index
+    //gives first slot of the block when a closure captured its variables, they have to be closed
+    pub fn leave_block(symbol_table: &Rc<RefCell<SymbolTable>>) -> Option<u16> {
+        let mut table = symbol_table.borrow_mut();
+        let block = table.blocks.pop().expect("Block scope was not entered");
+        if table.level == 0 {
+            return None;
+        }
+        table.counter = block.counter_at_entry;
+        block.captured.then_some(block.counter_at_entry)
+    }
+
+    fn find_in_current_scope(&self, name: &str) -> Option<Rc<Symbol>> {
+        let store = match self.blocks.last() {
+            Some(block) => &block.store,
+            None => &self.store,
+        };
+        store
+            .get(name)
+            .filter(|s| matches!(s.what_type(), SymbolType::GLOBAL | SymbolType::LOCAL))
+            .cloned()
+    }
+
+    fn mark_captured(&mut self, symbol: &Symbol) {
+        if let Some(block) = self
+            .blocks
+            .iter_mut()
+            .rev()
+            .find(|block| block.store.contains_key(&symbol.name))
+        {
+            block.captured = true;
+        }
+    }
+
+    fn find_local(&self, name: &str) -> Option<Rc<Symbol>> {
+        self.blocks
+            .iter()
+            .rev()
+            .find_map(|block| block.store.get(name))
+            .or_else(|| self.store.get(name))
+            .cloned()
+    }
+
     pub fn define_builtin(symbol_table: &Rc<RefCell<SymbolTable>>, name: &str) -> Rc<Symbol> {
         let builtin = symbol_table.borrow().builtin_scope.clone();
         let symbol = Rc::new(Symbol {
@@ -105,7 +176,7 @@ impl SymbolTable {
     }
 
     pub fn number_of_locals(symbol_table: &Rc<RefCell<SymbolTable>>) -> usize {
-        symbol_table.borrow().store.len()
+        symbol_table.borrow().max_counter as usize
     }
 
     pub fn is_enclosed(symbol_table: &Rc<RefCell<SymbolTable>>) -> bool {
@@ -116,17 +187,26 @@ impl SymbolTable {
         symbol_table: &Rc<RefCell<SymbolTable>>,
         name: &str,
     ) -> Option<Rc<Symbol>> {
-        if let Some(symbol) = symbol_table.borrow().store.get(name).cloned() {
+        let local = symbol_table.borrow().find_local(name);
+        if let Some(symbol) = local {
             return Some(symbol);
         }
 
         let mut current_outer = symbol_table.borrow().outer.clone();
         while let Some(outer) = current_outer {
-            if let Some(symbol) = outer.borrow().store.get(name).cloned() {
-                return Some(if matches!(symbol.what_type(), SymbolType::LOCAL) {
-                    SymbolTable::define_free(symbol_table, symbol)
-                } else {
-                    symbol
+            let found = outer.borrow().find_local(name);
+            if let Some(symbol) = found {
+                //name of an enclosing function is not current closure any more and free
+                //variables are indexed per closure, both have to be captured as locals are
+                return Some(match symbol.what_type() {
+                    SymbolType::LOCAL => {
+                        outer.borrow_mut().mark_captured(&symbol);
+                        SymbolTable::define_free(symbol_table, symbol)
+                    }
+                    SymbolType::FUNCTION | SymbolType::FREE => {
+                        SymbolTable::define_free(symbol_table, symbol)
+                    }
+                    _ => symbol,
                 });
             }
             current_outer = outer.borrow().outer.clone();
@@ -177,7 +257,9 @@ impl SymbolTable {
     pub fn enclosed(symbol_table: &Rc<RefCell<SymbolTable>>) -> Rc<RefCell<SymbolTable>> {
         let symbol = SymbolTable {
             store: HashMap::new(),
+            blocks: vec![],
             counter: 0,
+            max_counter: 0,
             level: symbol_table.borrow().level + 1,
             outer: Some(symbol_table.clone()),
             builtin_scope: symbol_table.borrow().builtin_scope.clone(),
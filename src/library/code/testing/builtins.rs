@@ -68,7 +68,7 @@ builtin_in_function: (
             test_bytecode(vec![
                 make(OpCodes::GetBuiltin.into(), &[BuiltInFunction::Len.index() as u16]),
                 make(OpCodes::Array.into(), &[0]),
-                make(OpCodes::Call.into(), &[1]),
+                make(OpCodes::TailCall.into(), &[1]),
                 make(OpCodes::ReturnValue.into(), &[]),
             ])
         ]
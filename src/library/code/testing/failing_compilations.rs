@@ -41,6 +41,12 @@ tests! {
              should_fail_with_arg(ArgumentMismatch { expected_arguments_size: 2, given_arguments_size: 1 })
          ]),
              */
+    block_variable_out_of_scope: (
+        "if (true) { let a = 1; }; a",
+        vec![should_fail_with_undefined("a")]),
+    assignment_to_undefined: (
+        "b = 1",
+        vec![should_fail_with_undefined("b")]),
 }
 
 struct ArgumentMismatch {
@@ -68,3 +74,16 @@ fn should_fail_with_arg(mismatch: ArgumentMismatch) -> Box<dyn Fn(&Vec<Compilati
         panic!("No error about argument size errors, found {errors:?}")
     })
 }
+
+fn should_fail_with_undefined(expected: &'static str) -> Box<dyn Fn(&Vec<CompilationError>)> {
+    Box::new(move |errors: &Vec<CompilationError>| {
+        for error in errors {
+            if let CompilationError::UndefinedVariable(_, name) = error
+                && name == expected
+            {
+                return;
+            }
+        }
+        panic!("No error about undefined {expected}, found {errors:?}")
+    })
+}
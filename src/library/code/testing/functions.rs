@@ -278,7 +278,7 @@ fn(a) { fn(b){ a + b } }
             make(OpCodes::ReturnValue.into(), &[])
         ]),
         test_bytecode(vec![
-            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::CaptureLocal.into(), &[0]),
             make(OpCodes::Closure.into(), &[0, 1]),
             make(OpCodes::ReturnValue.into(), &[])
         ])
@@ -286,7 +286,7 @@ fn(a) { fn(b){ a + b } }
 ),
 deep_closure: (
 "
-fn(a) { fn(b){ fn(c) { a + b + c } }
+fn(a) { fn(b){ fn(c) { a + b + c } } }
 ",
     vec![
          make(OpCodes::Closure.into(), &[2,0]),
@@ -303,18 +303,133 @@ fn(a) { fn(b){ fn(c) { a + b + c } }
             make(OpCodes::ReturnValue.into(), &[])
         ]),
         test_bytecode(vec![
-            make(OpCodes::GetFree.into(), &[0]),
-            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::CaptureFree.into(), &[0]),
+            make(OpCodes::CaptureLocal.into(), &[0]),
             make(OpCodes::Closure.into(), &[0, 2]),
             make(OpCodes::ReturnValue.into(), &[])
         ]),
         test_bytecode(vec![
-            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::CaptureLocal.into(), &[0]),
             make(OpCodes::Closure.into(), &[1, 1]),
             make(OpCodes::ReturnValue.into(), &[])
         ])
     ]
 ),
+assigning_free: (
+"
+fn(a) { fn(){ a = 1; } }
+",
+    vec![
+         make(OpCodes::Closure.into(), &[2,0]),
+         make(OpCodes::Pop.into(), &[]),
+
+    ],
+    vec![
+        test_be_integer(1),
+        test_bytecode(vec![
+            make(OpCodes::Constant.into(), &[0]),
+            make(OpCodes::SetFree.into(), &[0]),
+            make(OpCodes::ReturnNone.into(), &[])
+        ]),
+        test_bytecode(vec![
+            make(OpCodes::CaptureLocal.into(), &[0]),
+            make(OpCodes::Closure.into(), &[1, 1]),
+            make(OpCodes::ReturnValue.into(), &[])
+        ])
+    ]
+),
+enclosing_function_from_closure: (
+"
+let f = fn() { fn() { f } }
+",
+    vec![
+         make(OpCodes::Closure.into(), &[1,0]),
+         make(OpCodes::SetGlobal.into(), &[0]),
+    ],
+    vec![
+        test_bytecode(vec![
+            make(OpCodes::GetFree.into(), &[0]),
+            make(OpCodes::ReturnValue.into(), &[])
+        ]),
+        test_bytecode(vec![
+            make(OpCodes::CaptureClosure.into(), &[]),
+            make(OpCodes::Closure.into(), &[0, 1]),
+            make(OpCodes::ReturnValue.into(), &[])
+        ])
+    ]
+),
+declared_functions: (
+"
+fn a() { b() }
+fn b() { 1 }
+",
+    vec![
+         make(OpCodes::Closure.into(), &[0,0]),
+         make(OpCodes::SetGlobal.into(), &[0]),
+         make(OpCodes::Closure.into(), &[2,0]),
+         make(OpCodes::SetGlobal.into(), &[1]),
+    ],
+    vec![
+        test_bytecode(vec![
+            make(OpCodes::GetGlobal.into(), &[1]),
+            make(OpCodes::TailCall.into(), &[0]),
+            make(OpCodes::ReturnValue.into(), &[])
+        ]),
+        test_be_integer(1),
+        test_bytecode(vec![
+            make(OpCodes::Constant.into(), &[1]),
+            make(OpCodes::ReturnValue.into(), &[])
+        ]),
+    ]
+),
+tail_call_in_branch: (
+"
+fn(f, x) { if (x) { f(x) } else { 1 + f(x) } }
+",
+    vec![
+         make(OpCodes::Closure.into(), &[1,0]),
+         make(OpCodes::Pop.into(), &[]),
+    ],
+    vec![
+        test_be_integer(1),
+        test_bytecode(vec![
+            make(OpCodes::GetLocal.into(), &[1]),
+            make(OpCodes::JumpNotTruthy.into(), &[14]),
+            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::GetLocal.into(), &[1]),
+            make(OpCodes::TailCall.into(), &[1]),
+            make(OpCodes::Jump.into(), &[24]),
+            make(OpCodes::Constant.into(), &[0]),
+            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::GetLocal.into(), &[1]),
+            make(OpCodes::Call.into(), &[1]),
+            make(OpCodes::Add.into(), &[]),
+            make(OpCodes::ReturnValue.into(), &[]),
+        ])
+    ]
+),
+generator_body: (
+"
+fn*(g) { yield 1; yield* g; g(g) }
+",
+    vec![
+         make(OpCodes::Closure.into(), &[1,0]),
+         make(OpCodes::Pop.into(), &[]),
+    ],
+    vec![
+        test_be_integer(1),
+        test_bytecode(vec![
+            make(OpCodes::Constant.into(), &[0]),
+            make(OpCodes::Yield.into(), &[]),
+            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::Delegate.into(), &[]),
+            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::Call.into(), &[1]),
+            make(OpCodes::ReturnValue.into(), &[]),
+        ])
+    ]
+),
 using_closures: (
 "
 let global = 1;
@@ -355,15 +470,15 @@ fn() {
         test_bytecode(vec![
             make(OpCodes::Constant.into(), &[2]),
             make(OpCodes::SetLocal.into(), &[0]),
-            make(OpCodes::GetFree.into(), &[0]),
-            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::CaptureFree.into(), &[0]),
+            make(OpCodes::CaptureLocal.into(), &[0]),
             make(OpCodes::Closure.into(), &[4, 2]),
             make(OpCodes::ReturnValue.into(), &[])
         ]),
         test_bytecode(vec![
             make(OpCodes::Constant.into(), &[1]),
             make(OpCodes::SetLocal.into(), &[0]),
-            make(OpCodes::GetLocal.into(), &[0]),
+            make(OpCodes::CaptureLocal.into(), &[0]),
             make(OpCodes::Closure.into(), &[5, 1]),
             make(OpCodes::ReturnValue.into(), &[])
         ]),
@@ -389,7 +504,7 @@ vec![
         make(OpCodes::GetLocal.into(),&[0]),
         make(OpCodes::Constant.into(),&[0]),
         make(OpCodes::Subtitute.into(),&[]),
-        make(OpCodes::Call.into(),&[1]),
+        make(OpCodes::TailCall.into(),&[1]),
         make(OpCodes::ReturnValue.into(),&[]),
     ]),
     test_be_integer(1)
@@ -418,7 +533,7 @@ vec![
         make(OpCodes::GetLocal.into(),&[0]),
         make(OpCodes::Constant.into(),&[0]),
         make(OpCodes::Subtitute.into(),&[]),
-        make(OpCodes::Call.into(),&[1]),
+        make(OpCodes::TailCall.into(),&[1]),
         make(OpCodes::ReturnValue.into(),&[]),
     ]),
     test_be_integer(1),
@@ -427,7 +542,7 @@ vec![
         make(OpCodes::SetLocal.into(),&[0]),
         make(OpCodes::GetLocal.into(),&[0]),
         make(OpCodes::Constant.into(),&[2]),
-        make(OpCodes::Call.into(),&[1]),
+        make(OpCodes::TailCall.into(),&[1]),
         make(OpCodes::ReturnValue.into(),&[]),
     ]),
 ]
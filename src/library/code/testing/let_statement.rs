@@ -50,4 +50,22 @@ reassing: (
 
 ),
 
+decorated_let: (
+        "
+        let d = 1;
+        @d @d let x = 2;
+        ",
+        vec![
+            make(OpCodes::Constant.into(), &[0]),
+            make(OpCodes::SetGlobal.into(), &[0]),
+            make(OpCodes::GetGlobal.into(), &[0]),
+            make(OpCodes::GetGlobal.into(), &[0]),
+            make(OpCodes::Constant.into(), &[1]),
+            make(OpCodes::Call.into(), &[1]),
+            make(OpCodes::Call.into(), &[1]),
+            make(OpCodes::SetGlobal.into(), &[1]),
+        ],
+        vec![test_be_integer(1),test_be_integer(2)]
+),
+
 }
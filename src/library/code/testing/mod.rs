@@ -9,8 +9,10 @@ mod index;
 mod integer_arithmethics;
 mod let_statement;
 mod scopes;
+mod set;
 mod strings;
 mod symbol_table_test;
 mod test_compiler;
 mod test_make;
 mod test_unmake;
+mod tuple;
@@ -0,0 +1,29 @@
+use crate::code::definitions::OpCodes;
+use crate::code::make::make;
+use crate::code::testing::test_compiler::test_be_integer;
+use crate::code::testing::test_compiler::test_compilation;
+use crate::generate_tests_for_compiler;
+
+generate_tests_for_compiler! {
+
+empty_set: (
+    "#{}",
+    vec![
+            make(OpCodes::Set.into(), &[0]),
+            make(OpCodes::Pop.into(), &[]),
+    ],
+    vec![]
+    ),
+
+a_set: (
+    "#{1, 2}",
+    vec![
+            make(OpCodes::Constant.into(), &[0]),
+            make(OpCodes::Constant.into(), &[1]),
+            make(OpCodes::Set.into(), &[2]),
+            make(OpCodes::Pop.into(), &[]),
+    ],
+    vec![test_be_integer(1), test_be_integer(2)]
+    ),
+
+}
@@ -407,6 +407,82 @@ fn shadowing_function() {
     find_in!(global,Expected {index: 0,identifier: "fun",level: 0});
 }
 
+#[test]
+fn block_shadows_and_restores() {
+    let global = &SymbolTable::new_table();
+    let local = &SymbolTable::enclosed(global);
+    SymbolTable::define(local, "a");
+    SymbolTable::enter_block(local);
+    SymbolTable::define(local, "a");
+    #[rustfmt::skip]
+    find_in!(local,Expected {index: 1,identifier: "a",level: 1});
+    SymbolTable::leave_block(local);
+    #[rustfmt::skip]
+    find_in!(local,Expected {index: 0,identifier: "a",level: 1});
+}
+
+#[test]
+fn sibling_blocks_reuse_local_slots() {
+    let global = &SymbolTable::new_table();
+    let local = &SymbolTable::enclosed(global);
+    SymbolTable::define(local, "a");
+    SymbolTable::enter_block(local);
+    SymbolTable::define(local, "b");
+    SymbolTable::define(local, "c");
+    SymbolTable::leave_block(local);
+    do_not_find_in!(local, "b");
+    SymbolTable::enter_block(local);
+    SymbolTable::define(local, "d");
+    #[rustfmt::skip]
+    find_in!(local,Expected {index: 1,identifier: "d",level: 1});
+    SymbolTable::leave_block(local);
+    assert_eq!(3, SymbolTable::number_of_locals(local));
+}
+
+#[test]
+fn global_blocks_do_not_reuse_slots() {
+    let global = &SymbolTable::new_table();
+    SymbolTable::enter_block(global);
+    SymbolTable::define(global, "a");
+    SymbolTable::leave_block(global);
+    SymbolTable::define(global, "b");
+    #[rustfmt::skip]
+    find_in!(global,Expected {index: 1,identifier: "b",level: 0});
+}
+
+#[test]
+fn block_variable_of_outer_function_is_free() {
+    let global = &SymbolTable::new_table();
+    let upper = &SymbolTable::enclosed(global);
+    SymbolTable::enter_block(upper);
+    SymbolTable::define(upper, "a");
+    let inner = &SymbolTable::enclosed(upper);
+    should_be_free!(inner, "a", 0);
+}
+
+#[test]
+fn enclosing_function_name_is_free() {
+    let global = &SymbolTable::new_table();
+    let upper = &SymbolTable::enclosed(global);
+    SymbolTable::define_function_name(upper, "fun");
+    let inner = &SymbolTable::enclosed(upper);
+    should_be_free!(inner, "fun", 0);
+    should_be_function!(upper, "fun", 0);
+}
+
+#[test]
+fn free_variable_of_outer_closure_gets_own_index() {
+    let global = &SymbolTable::new_table();
+    let upper = &SymbolTable::enclosed(global);
+    SymbolTable::define(upper, "a");
+    SymbolTable::define(upper, "b");
+    let middle = &SymbolTable::enclosed(upper);
+    should_be_free!(middle, "a", 0);
+    should_be_free!(middle, "b", 1);
+    let inner = &SymbolTable::enclosed(middle);
+    should_be_free!(inner, "b", 0);
+}
+
 #[derive(Debug)]
 struct Expected<'a> {
     index: u16,
@@ -0,0 +1,37 @@
+use crate::code::definitions::OpCodes;
+use crate::code::make::make;
+use crate::code::testing::test_compiler::test_be_integer;
+use crate::code::testing::test_compiler::test_compilation;
+use crate::generate_tests_for_compiler;
+
+generate_tests_for_compiler! {
+
+a_tuple: (
+    "(1, 2)",
+    vec![
+            make(OpCodes::Constant.into(), &[0]),
+            make(OpCodes::Constant.into(), &[1]),
+            make(OpCodes::Tuple.into(), &[2]),
+            make(OpCodes::Pop.into(), &[]),
+    ],
+    vec![test_be_integer(1), test_be_integer(2)]
+    ),
+
+destructuring_let: (
+    "let (a, (b, c)) = (1, (2, 3));",
+    vec![
+            make(OpCodes::Constant.into(), &[0]),
+            make(OpCodes::Constant.into(), &[1]),
+            make(OpCodes::Constant.into(), &[2]),
+            make(OpCodes::Tuple.into(), &[2]),
+            make(OpCodes::Tuple.into(), &[2]),
+            make(OpCodes::Destructure.into(), &[2]),
+            make(OpCodes::Destructure.into(), &[2]),
+            make(OpCodes::SetGlobal.into(), &[0]),
+            make(OpCodes::SetGlobal.into(), &[1]),
+            make(OpCodes::SetGlobal.into(), &[2]),
+    ],
+    vec![test_be_integer(1), test_be_integer(2), test_be_integer(3)]
+    ),
+
+}
@@ -4,7 +4,7 @@ use crate::{
     ast::expression::Expression,
     end_flow,
     evaluator::evaluate_expressions::evaluate_expressions,
-    object::{Environment, Object, error_at, hash, null_value},
+    object::{Environment, Object, char_at, error_at, hash, null_value},
     tokens::Token,
 };
 
@@ -21,6 +21,16 @@ pub(super) fn parse_array_literal(
     return Rc::new(Object::Array { elements: parsed });
 }
 
+pub(super) fn parse_tuple_literal(
+    elements: &[Expression],
+    env: Rc<RefCell<Environment>>,
+) -> Rc<Object> {
+    match evaluate_expressions(elements, env.clone()) {
+        Ok(parsed) => Rc::new(Object::Tuple { elements: parsed }),
+        Err(v) => v,
+    }
+}
+
 pub(super) fn parse_index_expression(
     token: &Token,
     array: &Expression,
@@ -40,12 +50,18 @@ fn evaluate_index_expression(
     right_value: Rc<Object>,
 ) -> Rc<Object> {
     match *left_value {
-        Object::Array { ref elements } => {
+        Object::Array { ref elements } | Object::Tuple { ref elements } => {
             if let Object::Int(value) = *right_value {
                 return parse_integer_index(token, elements, value);
             }
             error_at("Index must be an integer", token)
         }
+        Object::String(ref text) => {
+            if let Object::Int(value) = *right_value {
+                return parse_string_index(token, text, value);
+            }
+            error_at("Index must be an integer", token)
+        }
         Object::HashMap(ref a_map) => parse_hashmap_index(a_map, right_value.as_ref()),
         _ => error_at("Index operator can only be applied to arrays", token),
     }
@@ -63,6 +79,17 @@ fn parse_hashmap_index(
     }
 }
 
+fn parse_string_index(token: &Token, text: &str, value: i64) -> Rc<Object> {
+    match char_at(text, value) {
+        Some(found) => Rc::new(Object::Char(found)),
+        None if value == 0 => error_at(
+            format!("Index out of bounds: {} (strings are 1 indexed)", value).as_str(),
+            token,
+        ),
+        None => error_at(format!("Index out of bounds: {}", value).as_str(), token),
+    }
+}
+
 fn parse_integer_index(token: &Token, elements: &[Rc<Object>], value: i64) -> Rc<Object> {
     if value == 0 {
         error_at(
@@ -2,20 +2,23 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     ast::{
-        base::Node,
         expression::{self, Expression},
         modify,
+        statements::Statement,
+    },
+    evaluator::{evaluate, evaluate_expressions::evaluate_expressions, hoist_functions},
+    lines::SourceSpan,
+    object::{
+        BuiltInFunction, Environment, Identifier, Object, display_method, error_at, is_truthy,
+        null_value, sorted_elements, symbol_name,
     },
-    end_flow,
-    evaluator::{evaluate, evaluate_expressions::evaluate_expressions},
-    object::{Environment, Identifier, Object, error_at},
     tokens::{Token, TokenKind},
 };
 
-use super::evaluate_expression;
+use super::{evaluate_expression, generators};
 
 pub fn evaluate_call_expression(
-    token: &Token,
+    token: &Rc<Token>,
     function: &Expression,
     arguments: &[Expression],
     env: Rc<RefCell<Environment>>,
@@ -24,22 +27,35 @@ pub fn evaluate_call_expression(
         Expression::Identifier(id) => match &id.kind {
             TokenKind::Identifier(name) => {
                 if name == "quote" {
-                    return evaluate_quote(&arguments[0], &token, env.clone());
+                    return evaluate_quote(&arguments[0], env.clone());
                 }
             }
             _ => {}
         },
         _ => {}
     }
+    match evaluate_callee(token, function, arguments, env) {
+        Ok((function, parsed)) => apply_function(token.clone(), function, parsed),
+        Err(error) => error,
+    }
+}
+
+type Callee = (Rc<Object>, Vec<Rc<Object>>);
+
+fn evaluate_callee(
+    token: &Rc<Token>,
+    function: &Expression,
+    arguments: &[Expression],
+    env: Rc<RefCell<Environment>>,
+) -> Result<Callee, Rc<Object>> {
     let function = evaluate_expression(function, env.clone());
-    end_flow!(function);
+    if let Object::Error { .. } | Object::ReturnValue(_) = function.as_ref() {
+        return Err(function);
+    }
 
-    let parsed = match evaluate_expressions(arguments, env.clone()) {
-        Ok(all) => all,
-        Err(v) => return v,
-    };
+    let parsed = evaluate_expressions(arguments, env.clone())?;
     if parsed.len() != arguments.len() {
-        return error_at(
+        return Err(error_at(
             format!(
                 "Function call expected {} arguments, got {}",
                 arguments.len(),
@@ -47,40 +63,173 @@ pub fn evaluate_call_expression(
             )
             .as_str(),
             token,
-        );
+        ));
     }
-    apply_function(token, function, &parsed)
+    Ok((function, parsed))
 }
 
-fn apply_function(token: &Token, function: Rc<Object>, arguments: &[Rc<Object>]) -> Rc<Object> {
-    match *function {
-        Object::Function {
-            ref parameters,
-            ref body,
-            env: ref func_env,
-        } => {
-            /*
-             * Function environment is extended so even if variables are not visible in current scope they can
-             * still be accessed in the function body.
-             */
-            let extended_env = extend_env(func_env.clone(), parameters, arguments);
-            let body_fun = body.as_ref();
-            let result = evaluate(body_fun, extended_env);
-            match *result {
-                Object::ReturnValue(ref value) => value.clone(),
-                Object::Error { .. } => result,
-                _ => result,
+//call in tail position is handed back, so `apply_function` runs it without growing the stack
+enum Tail {
+    Value(Rc<Object>),
+    Call {
+        token: Rc<Token>,
+        function: Rc<Object>,
+        arguments: Vec<Rc<Object>>,
+    },
+}
+
+pub(super) fn apply_function(
+    token: Rc<Token>,
+    function: Rc<Object>,
+    arguments: Vec<Rc<Object>>,
+) -> Rc<Object> {
+    let (mut token, mut function, mut arguments) = (token, function, arguments);
+    loop {
+        match *function {
+            Object::Function {
+                ref parameters,
+                ref body,
+                env: ref func_env,
+                generator,
+            } => {
+                /*
+                 * Function environment is extended so even if variables are not visible in current scope they can
+                 * still be accessed in the function body.
+                 */
+                let extended_env = extend_env(func_env.clone(), parameters, &arguments);
+                if generator {
+                    return generators::create(body, extended_env);
+                }
+                let result = match evaluate_tail(body.as_ref(), extended_env) {
+                    Tail::Value(result) => result,
+                    Tail::Call {
+                        token: next_token,
+                        function: next_function,
+                        arguments: next_arguments,
+                    } => {
+                        (token, function, arguments) = (next_token, next_function, next_arguments);
+                        continue;
+                    }
+                };
+                return match *result {
+                    Object::ReturnValue(ref value) => value.clone(),
+                    Object::Error { .. } => result,
+                    _ => result,
+                };
+            }
+            Object::Builtin(ref func) => {
+                if let Some(result) = generators::apply_builtin(func, &arguments, &token) {
+                    return result;
+                }
+                if matches!(func, BuiltInFunction::Puts | BuiltInFunction::Str) {
+                    arguments = match display_arguments(&token, arguments) {
+                        Ok(displayed) => displayed,
+                        Err(error) => return error,
+                    };
+                }
+                let evaluation = func.apply(&arguments);
+                return match evaluation {
+                    crate::object::BuiltInResult::Unit => Rc::new(Object::Null),
+                    crate::object::BuiltInResult::Value(object) => object.clone(),
+                    crate::object::BuiltInResult::Failure(e) => error_at(&e, &token),
+                };
+            }
+            _ => return error_at("Call expression is not a function.", &token),
+        }
+    }
+}
+
+fn display_arguments(
+    token: &Rc<Token>,
+    arguments: Vec<Rc<Object>>,
+) -> Result<Vec<Rc<Object>>, Rc<Object>> {
+    arguments
+        .into_iter()
+        .map(|argument| match display_method(&argument) {
+            Some(function) => {
+                let text = apply_function(token.clone(), function, vec![argument]);
+                match text.as_ref() {
+                    Object::Error { .. } => Err(text),
+                    _ => Ok(text),
+                }
+            }
+            None => Ok(argument),
+        })
+        .collect()
+}
+
+//value in tail position is not computed when it is a call
+fn evaluate_tail(body: &Statement, env: Rc<RefCell<Environment>>) -> Tail {
+    let statements = match body {
+        Statement::Block { statements, .. } => statements,
+        Statement::Return { return_value, .. } => return tail_expression(return_value, env),
+        Statement::AExpression { expression, .. } => return tail_expression(expression, env),
+        _ => return Tail::Value(evaluate(body, env)),
+    };
+    let block_env = Rc::new(RefCell::new(Environment::enclosed(env)));
+    if let Err(error) = hoist_functions(statements, block_env.clone()) {
+        return Tail::Value(error);
+    }
+    let mut result = null_value();
+    for (index, statement) in statements.iter().enumerate() {
+        match statement {
+            Statement::Return { .. } => return evaluate_tail(statement, block_env),
+            Statement::AExpression { .. } if index == statements.len() - 1 => {
+                return evaluate_tail(statement, block_env);
             }
+            _ => {}
         }
-        Object::Builtin(ref func) => {
-            let evaluation = func.apply(arguments);
-            match evaluation {
-                crate::object::BuiltInResult::Unit => Rc::new(Object::Null),
-                crate::object::BuiltInResult::Value(object) => object.clone(),
-                crate::object::BuiltInResult::Failure(e) => error_at(&e, token),
+        result = evaluate(statement, block_env.clone());
+        if let Object::Error { .. } | Object::ReturnValue(_) = result.as_ref() {
+            return Tail::Value(result);
+        }
+    }
+    Tail::Value(result)
+}
+
+fn tail_expression(expression: &Expression, env: Rc<RefCell<Environment>>) -> Tail {
+    match expression {
+        Expression::Call {
+            token,
+            function,
+            arguments,
+            ..
+        } if !is_quote_call(expression) => match evaluate_callee(token, function, arguments, env) {
+            Ok((function, arguments)) => Tail::Call {
+                token: token.clone(),
+                function,
+                arguments,
+            },
+            Err(error) => Tail::Value(error),
+        },
+        Expression::AIf {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            let condition_value = evaluate_expression(condition, env.clone());
+            if is_truthy(condition_value.as_ref()) {
+                evaluate_tail(consequence.as_ref(), env)
+            } else if let Some(alternative) = alternative {
+                evaluate_tail(alternative.as_ref(), env)
+            } else {
+                Tail::Value(null_value())
             }
         }
-        _ => error_at("Call expression is not a function.", token),
+        _ => Tail::Value(evaluate_expression(expression, env)),
+    }
+}
+
+fn is_quote_call(expression: &Expression) -> bool {
+    match expression {
+        Expression::Call { function, .. } => match function.as_ref() {
+            Expression::Identifier(token) => {
+                matches!(&token.kind, TokenKind::Identifier(name) if name == "quote")
+            }
+            _ => false,
+        },
+        _ => false,
     }
 }
 
@@ -97,84 +246,65 @@ fn extend_env(
     new_env
 }
 
-fn evaluate_quote(
-    argument: &Expression,
-    token: &Token,
-    env: Rc<RefCell<Environment>>,
-) -> Rc<Object> {
-    let modified_argument = evaluate_unqote(argument.clone(), &token, env);
+fn evaluate_quote(argument: &Expression, env: Rc<RefCell<Environment>>) -> Rc<Object> {
+    let modified_argument = evaluate_unqote(argument.clone(), env);
     return Rc::new(Object::Quote(modified_argument));
 }
 
-fn evaluate_unqote(
-    expression: Expression,
-    token: &Token,
-    env: Rc<RefCell<Environment>>,
-) -> Rc<Expression> {
-    fn traverse<'a>(
-        node: Rc<dyn Node + 'a>,
-        token: &Token,
-        env: Rc<RefCell<Environment>>,
-    ) -> Rc<dyn Node + 'a> {
-        let expression = node.as_any().downcast_ref::<Expression>();
-        let expression = match expression {
-            Some(v) => v,
-            None => return node,
-        };
-        if !is_unquote_call(expression) {
-            return node;
+fn evaluate_unqote(expression: Expression, env: Rc<RefCell<Environment>>) -> Rc<Expression> {
+    fn traverse(expression: Expression, env: Rc<RefCell<Environment>>) -> Expression {
+        if !is_unquote_call(&expression) {
+            return expression;
         }
 
-        return match expression {
+        return match &expression {
             Expression::Call {
-                token: _,
                 function: _,
                 arguments,
+                ..
             } => {
                 if arguments.len() != 1 {
-                    return node;
+                    return expression;
                 }
 
                 println!("unquoting {:?}", &arguments[0]);
                 let unqoted = evaluate(&arguments[0], env);
-                return convert_unqoted_into_ast(unqoted, token);
+                //the value takes place of the `unquote` call, so do its nodes in the source
+                return convert_unqoted_into_ast(unqoted, expression.span())
+                    .as_ref()
+                    .clone();
             }
-            _ => node,
+            _ => expression,
         };
     }
-    let node = modify(Rc::new(expression), |n| traverse(n, token, env.clone()))
-        .as_any()
-        .downcast_ref::<Expression>()
-        .unwrap()
-        .clone();
-    Rc::new(node)
+    Rc::new(modify(expression, |expression| {
+        traverse(expression, env.clone())
+    }))
 }
 
-fn convert_unqoted_into_ast(unqoted: Rc<Object>, token: &Token) -> Rc<Expression> {
-    let position = token.position();
-    let position =
-        crate::lines::TokenPosition::single_character(position.0.into(), position.1.into());
+fn convert_unqoted_into_ast(unqoted: Rc<Object>, span: SourceSpan) -> Rc<Expression> {
     match *unqoted {
         Object::Int(v) => {
             if v > 0 {
-                Rc::new(Expression::IntegerLiteral(Rc::new(Token::new(
-                    position,
-                    TokenKind::Integer(v.try_into().unwrap()),
+                Rc::new(Expression::IntegerLiteral(Rc::new(Token::at(
+                    span,
+                    TokenKind::Integer(v),
                 ))))
             } else {
                 Rc::new(Expression::PrefixOperator {
-                    token: Rc::new(Token::new(position, TokenKind::Negation)),
+                    span,
+                    token: Rc::new(Token::at(span, TokenKind::Negation)),
                     operator: expression::PrefixOperatorType::Minus,
-                    right: Box::new(Expression::IntegerLiteral(Rc::new(Token::new(
-                        position,
-                        TokenKind::Integer((-v).try_into().unwrap()),
+                    right: Box::new(Expression::IntegerLiteral(Rc::new(Token::at(
+                        span,
+                        TokenKind::Integer(-v),
                     )))),
                 })
             }
         }
         Object::Boolean(value) => Rc::new(Expression::BooleanLiteral {
-            token: Rc::new(Token::new(
-                position,
+            token: Rc::new(Token::at(
+                span,
                 if value {
                     TokenKind::True
                 } else {
@@ -184,10 +314,47 @@ fn convert_unqoted_into_ast(unqoted: Rc<Object>, token: &Token) -> Rc<Expression
             value: value,
         }),
         Object::Quote(ref value) => value.clone(),
-        Object::String(ref value) => Rc::new(Expression::StringLiteral(Rc::new(Token::new(
-            position,
+        Object::String(ref value) => Rc::new(Expression::StringLiteral(Rc::new(Token::at(
+            span,
             TokenKind::StringLiteral(value.clone()),
         )))),
+        Object::Symbol(index) => Rc::new(Expression::SymbolLiteral(Rc::new(Token::at(
+            span,
+            TokenKind::Symbol(symbol_name(index)),
+        )))),
+        //there is no literal of a single character, it is taken from a string of it
+        Object::Char(value) => Rc::new(Expression::Index {
+            span,
+            token: Rc::new(Token::at(span, TokenKind::LeftBracket)),
+            array: Box::new(Expression::StringLiteral(Rc::new(Token::at(
+                span,
+                TokenKind::StringLiteral(value.to_string()),
+            )))),
+            index: Box::new(Expression::IntegerLiteral(Rc::new(Token::at(
+                span,
+                TokenKind::Integer(1),
+            )))),
+        }),
+        Object::Tuple { ref elements } => Rc::new(Expression::TupleLiteral {
+            span,
+            token: Rc::new(Token::at(span, TokenKind::LeftParen)),
+            elements: elements
+                .iter()
+                .map(|element| {
+                    convert_unqoted_into_ast(element.clone(), span)
+                        .as_ref()
+                        .clone()
+                })
+                .collect(),
+        }),
+        Object::Set(ref set) => Rc::new(Expression::SetLiteral {
+            span,
+            token: Rc::new(Token::at(span, TokenKind::SetStart)),
+            elements: sorted_elements(set)
+                .into_iter()
+                .map(|element| convert_unqoted_into_ast(element, span).as_ref().clone())
+                .collect(),
+        }),
         _ => todo!("To fill"),
     }
 }
@@ -195,9 +362,9 @@ fn convert_unqoted_into_ast(unqoted: Rc<Object>, token: &Token) -> Rc<Expression
 fn is_unquote_call(expression: &Expression) -> bool {
     match expression {
         Expression::Call {
-            token: _,
             function,
             arguments: _,
+            ..
         } => {
             if let Expression::Identifier(token) = *function.clone() {
                 match &token.kind {
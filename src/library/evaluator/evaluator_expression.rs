@@ -9,14 +9,14 @@ use crate::{
 };
 
 use super::{
-    arrays::{parse_array_literal, parse_index_expression},
+    arrays::{parse_array_literal, parse_index_expression, parse_tuple_literal},
     evaluate,
-    evaluate_call::evaluate_call_expression,
+    evaluate_call::{apply_function, evaluate_call_expression},
     evaluate_identifier::evaluate_indentifier,
     functional_literal_evaluations::function_literal_evaluation,
     infixs::infix_operator_evaluation,
     int_value,
-    maps::parse_map_literal,
+    maps::{parse_map_literal, parse_set_literal},
     prefixs::prefix_operator_evaluation,
     string_value,
 };
@@ -31,7 +31,6 @@ pub(super) fn evaluate_expression(
             match token.as_ref().kind {
                 TokenKind::Integer(value) => {
                     // Handle integer literal evaluation
-                    let value = value as i64;
                     allocation_counting!(int_value(value), value)
                 }
                 _ => unreachable!("Expected an integer token, got: {:?}", token),
@@ -42,6 +41,10 @@ pub(super) fn evaluate_expression(
             TokenKind::False => false_value(),
             _ => unreachable!("Expected a boolean token, got: {:?}", token),
         },
+        Expression::SymbolLiteral(token) => match token.as_ref().kind {
+            TokenKind::Symbol(ref name) => Rc::new(Object::Symbol(intern(name))),
+            _ => error_at("Expected symbol", token),
+        },
         Expression::StringLiteral(token) => match token.as_ref().kind {
             TokenKind::StringLiteral(ref value) => string_value(value.to_string()),
             _ => unreachable!("Expected a string token, got: {:?}", token),
@@ -50,24 +53,27 @@ pub(super) fn evaluate_expression(
             token,
             operator,
             right,
+            ..
         } => prefix_operator_evaluation(token, operator, right.as_ref(), env.clone()),
         Expression::Infix {
             token,
             left,
             operator,
             right,
+            ..
         } => {
             let left_value = evaluate_expression(left, env.clone());
             end_flow!(left_value);
             let right_value = evaluate_expression(right, env.clone());
             end_flow!(right_value);
-            infix_operator_evaluation(token, operator, left_value, right_value)
+            let strict = env.borrow().is_strict();
+            infix_operator_evaluation(token, operator, left_value, right_value, strict)
         }
         Expression::AIf {
-            token: _,
             condition,
             consequence,
             alternative,
+            ..
         } => {
             let condition_value = evaluate_expression(condition, env.clone());
             if is_truthy(condition_value.as_ref()) {
@@ -83,26 +89,50 @@ pub(super) fn evaluate_expression(
             token,
             function,
             arguments,
+            ..
         } => evaluate_call_expression(token, function, arguments, env.clone()),
         Expression::FunctionLiteral {
             token,
             parameters,
             body,
             name: _,
-        } => function_literal_evaluation(token, parameters, body, env.clone()),
-        Expression::ArrayLiteral { token: _, elements } => {
-            parse_array_literal(elements, env.clone())
+            generator,
+            signature: _,
+            ..
+        } => function_literal_evaluation(token, parameters, body, env.clone(), *generator),
+        Expression::ArrayLiteral { elements, .. } => parse_array_literal(elements, env.clone()),
+        Expression::TupleLiteral { elements, .. } => parse_tuple_literal(elements, env.clone()),
+        Expression::SetLiteral { elements, .. } => parse_set_literal(elements, env.clone()),
+        Expression::Decorated {
+            token,
+            decorators,
+            value,
+            ..
+        } => {
+            let mut functions = Vec::with_capacity(decorators.len());
+            for decorator in decorators {
+                let function = evaluate_expression(decorator, env.clone());
+                end_flow!(function);
+                functions.push(function);
+            }
+            let mut decorated = evaluate_expression(value, env.clone());
+            for function in functions.into_iter().rev() {
+                end_flow!(decorated);
+                decorated = apply_function(token.clone(), function, vec![decorated]);
+            }
+            decorated
         }
         Expression::Index {
             token,
             array,
             index,
+            ..
         } => parse_index_expression(token, array, index, env.clone()),
-        Expression::MapLiteral { token: _, elements } => parse_map_literal(elements, env.clone()),
+        Expression::MapLiteral { elements, .. } => parse_map_literal(elements, env.clone()),
         Expression::MacroLiteral {
-            token: _,
             parameters: _,
             body: _,
+            ..
         } => todo!("Should not go into macro literal"),
     }
 }
@@ -11,6 +11,7 @@ pub fn function_literal_evaluation(
     parameters: &Vec<Expression>,
     body: &Statement,
     env: Rc<RefCell<Environment>>,
+    generator: bool,
 ) -> Rc<Object> {
     let mut parsed_parameters: Vec<Identifier> = vec![];
     for parameter in parameters {
@@ -27,13 +28,19 @@ pub fn function_literal_evaluation(
         }
     }
     match body {
-        Statement::Block { token, statements } => Rc::new(Object::Function {
+        Statement::Block {
+            token,
+            span,
+            statements,
+        } => Rc::new(Object::Function {
             parameters: parsed_parameters,
             body: Rc::new(Statement::Block {
                 token: token.clone(),
+                span: *span,
                 statements: statements.clone(),
             }),
             env: env.clone(),
+            generator,
         }),
         _ => error_at("Function body must be a block statement.", token),
     }
@@ -0,0 +1,235 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    ast::{expression::Expression, statements::Statement},
+    evaluator::{evaluate, hoist_functions},
+    object::{
+        BuiltInFunction, Environment, Generator, GeneratorState, GeneratorStep, Object,
+        SuspendedTree, error_at, is_truthy, null_value, type_of,
+    },
+    tokens::Token,
+};
+
+use super::evaluate_expression;
+
+//calling a generator function only prepares the steps, `next` and `take` run them
+pub(super) fn create(body: &Rc<Statement>, env: Rc<RefCell<Environment>>) -> Rc<Object> {
+    let mut steps = vec![];
+    lower_statement(body, &mut steps);
+    let tree = SuspendedTree {
+        steps: Rc::new(steps),
+        position: 0,
+        scopes: vec![env],
+        delegate: None,
+    };
+    Rc::new(Object::Generator(Rc::new(RefCell::new(
+        GeneratorState::Tree(tree),
+    ))))
+}
+
+fn lower_block(statements: &[Statement], steps: &mut Vec<GeneratorStep>) {
+    let declarations = statements
+        .iter()
+        .filter(|statement| matches!(statement, Statement::Function { .. }))
+        .cloned()
+        .collect();
+    steps.push(GeneratorStep::EnterBlock(declarations));
+    for statement in statements {
+        lower_statement(statement, steps);
+    }
+    steps.push(GeneratorStep::LeaveBlock);
+}
+
+//parser allows `yield` only in the body, its blocks and `if` branches, only those are split
+fn lower_statement(statement: &Statement, steps: &mut Vec<GeneratorStep>) {
+    match statement {
+        Statement::Yield {
+            value, delegate, ..
+        } => steps.push(match delegate {
+            true => GeneratorStep::Delegate(value.clone()),
+            false => GeneratorStep::Yield(value.clone()),
+        }),
+        Statement::Block { statements, .. } => lower_block(statements, steps),
+        Statement::AExpression {
+            expression:
+                Expression::AIf {
+                    condition,
+                    consequence,
+                    alternative,
+                    ..
+                },
+            ..
+        } => {
+            let jump_unless = steps.len();
+            steps.push(GeneratorStep::JumpUnless(condition.as_ref().clone(), 0));
+            lower_statement(consequence, steps);
+            let jump = steps.len();
+            steps.push(GeneratorStep::Jump(0));
+            patch(steps, jump_unless);
+            if let Some(alternative) = alternative {
+                lower_statement(alternative, steps);
+            }
+            patch(steps, jump);
+        }
+        // defined by `EnterBlock` of its block
+        Statement::Function { .. } => {}
+        _ => steps.push(GeneratorStep::Run(statement.clone())),
+    }
+}
+
+fn patch(steps: &mut [GeneratorStep], position: usize) {
+    let end = steps.len();
+    match &mut steps[position] {
+        GeneratorStep::JumpUnless(_, target) | GeneratorStep::Jump(target) => *target = end,
+        _ => panic!("Only jumps can be patched"),
+    }
+}
+
+//`None` when the generator has finished, now or by an earlier call
+pub(super) fn resume(
+    generator: &Generator,
+    token: &Token,
+) -> Result<Option<Rc<Object>>, Rc<Object>> {
+    let mut tree = match generator.replace(GeneratorState::Running) {
+        GeneratorState::Tree(tree) => tree,
+        GeneratorState::Done => {
+            generator.replace(GeneratorState::Done);
+            return Ok(None);
+        }
+        GeneratorState::Running => return Err(error_at("Generator is already running", token)),
+        GeneratorState::Frame(frame) => {
+            generator.replace(GeneratorState::Frame(frame));
+            return Err(error_at("Generator was created by the vm", token));
+        }
+    };
+    let result = run(&mut tree, token);
+    generator.replace(match result {
+        Ok(Some(_)) => GeneratorState::Tree(tree),
+        _ => GeneratorState::Done,
+    });
+    result
+}
+
+fn run(tree: &mut SuspendedTree, token: &Token) -> Result<Option<Rc<Object>>, Rc<Object>> {
+    loop {
+        if let Some(delegate) = tree.delegate.clone() {
+            match resume(&delegate, token)? {
+                Some(value) => return Ok(Some(value)),
+                None => tree.delegate = None,
+            }
+        }
+        let steps = tree.steps.clone();
+        let Some(step) = steps.get(tree.position) else {
+            return Ok(None);
+        };
+        let env = tree.scopes.last().expect("Generator has no scope").clone();
+        tree.position += 1;
+        match step {
+            GeneratorStep::Run(statement) => {
+                let result = evaluate(statement, env);
+                match result.as_ref() {
+                    Object::ReturnValue(_) => return Ok(None),
+                    Object::Error { .. } => return Err(result),
+                    _ => {}
+                }
+            }
+            GeneratorStep::Yield(expression) => {
+                return evaluate_value(expression, env).map(Some);
+            }
+            GeneratorStep::Delegate(expression) => {
+                let value = evaluate_value(expression, env)?;
+                let Object::Generator(delegate) = value.as_ref() else {
+                    return Err(error_at(
+                        &format!("yield* expected Generator, got {}", type_of(&value)),
+                        token,
+                    ));
+                };
+                //delegating as the last step only passes values through, so an unshared delegate
+                //continues in place of ours and recursion through `yield*` does not nest
+                let owned = Rc::strong_count(&value) == 1 && Rc::strong_count(delegate) == 1;
+                if owned && is_tail(&steps, tree.position) {
+                    if let GeneratorState::Tree(inner) = delegate.replace(GeneratorState::Done) {
+                        *tree = inner;
+                        continue;
+                    }
+                    return Err(error_at("Generator was created by the vm", token));
+                }
+                tree.delegate = Some(delegate.clone());
+            }
+            GeneratorStep::JumpUnless(condition, target) => {
+                if !is_truthy(evaluate_value(condition, env)?.as_ref()) {
+                    tree.position = *target;
+                }
+            }
+            GeneratorStep::Jump(target) => tree.position = *target,
+            GeneratorStep::EnterBlock(declarations) => {
+                let block_env = Rc::new(RefCell::new(Environment::enclosed(env)));
+                hoist_functions(declarations, block_env.clone())?;
+                tree.scopes.push(block_env);
+            }
+            GeneratorStep::LeaveBlock => {
+                tree.scopes.pop();
+            }
+        }
+    }
+}
+
+fn is_tail(steps: &[GeneratorStep], position: usize) -> bool {
+    let mut position = position;
+    while let Some(step) = steps.get(position) {
+        match step {
+            GeneratorStep::LeaveBlock => position += 1,
+            GeneratorStep::Jump(target) => position = *target,
+            _ => return false,
+        }
+    }
+    true
+}
+
+fn evaluate_value(
+    expression: &Expression,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Rc<Object>, Rc<Object>> {
+    let value = evaluate_expression(expression, env);
+    match value.as_ref() {
+        Object::Error { .. } => Err(value),
+        _ => Ok(value),
+    }
+}
+
+pub(super) fn apply_builtin(
+    function: &BuiltInFunction,
+    arguments: &[Rc<Object>],
+    token: &Token,
+) -> Option<Rc<Object>> {
+    let Some(Object::Generator(generator)) = arguments.first().map(|a| a.as_ref()) else {
+        return None;
+    };
+    let result = match (function, arguments.len()) {
+        (BuiltInFunction::Next, 1) => {
+            resume(generator, token).map(|v| v.unwrap_or_else(null_value))
+        }
+        // generator is done once `next` found nothing more, not right after its last value
+        (BuiltInFunction::Done, 1) => Ok(Rc::new(Object::Boolean(matches!(
+            *generator.borrow(),
+            GeneratorState::Done
+        )))),
+        (BuiltInFunction::Take, 2) => match arguments[1].as_ref() {
+            Object::Int(count) => take(generator, *count, token),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(result.unwrap_or_else(|error| error))
+}
+
+fn take(generator: &Generator, count: i64, token: &Token) -> Result<Rc<Object>, Rc<Object>> {
+    let mut elements = vec![];
+    while (elements.len() as i64) < count {
+        match resume(generator, token)? {
+            Some(value) => elements.push(value),
+            None => break,
+        }
+    }
+    Ok(Rc::new(Object::Array { elements }))
+}
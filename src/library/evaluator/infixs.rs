@@ -7,12 +7,18 @@ use crate::{
     tokens::Token,
 };
 
+use super::evaluate_call::apply_function;
+
 pub(super) fn infix_operator_evaluation(
-    token: &Token,
+    token: &Rc<Token>,
     operator: &InfixOperatorType,
     left: Rc<Object>,
     right: Rc<Object>,
+    strict: bool,
 ) -> Rc<Object> {
+    if strict && let Some(message) = strict_violation(operator, &left, &right) {
+        return error_at(&message, token);
+    }
     let some_value: Option<Rc<Object>> = match *left {
         Object::Int(left_value) => match *right {
             Object::Int(right_value) => {
@@ -30,11 +36,18 @@ pub(super) fn infix_operator_evaluation(
             Object::String(ref right_value) => {
                 string_infix_evaluation(operator, left_value, right_value)
             }
-            Object::Boolean(_) | Object::Null => {
+            Object::Boolean(_) | Object::Null | Object::Char(_) => {
                 string_infix_evaluation(operator, left_value, &right.to_string())
             }
             _ => None,
         },
+        Object::Char(left_value) => match *right {
+            Object::Char(right_value) => char_infix_evaluation(operator, left_value, right_value),
+            Object::String(ref right_value) => {
+                string_infix_evaluation(operator, &left_value.to_string(), right_value)
+            }
+            _ => None,
+        },
         Object::Boolean(left_value) => match *right {
             Object::Boolean(right_value) => match operator {
                 InfixOperatorType::Equal => Some(if left_value == right_value {
@@ -59,8 +72,36 @@ pub(super) fn infix_operator_evaluation(
             }
             _ => None,
         },
+        //tuples are values, equal when all of their elements are
+        Object::Tuple { .. } => match (&*right, operator) {
+            (Object::Tuple { .. }, InfixOperatorType::Equal) => Some(boolean_value(left == right)),
+            (Object::Tuple { .. }, InfixOperatorType::NotEqual) => {
+                Some(boolean_value(left != right))
+            }
+            _ => None,
+        },
+        Object::Symbol(_) => match (&*right, operator) {
+            (Object::Symbol(_), InfixOperatorType::Equal) => Some(boolean_value(left == right)),
+            (Object::Symbol(_), InfixOperatorType::NotEqual) => Some(boolean_value(left != right)),
+            _ => None,
+        },
+        Object::Set(_) => match (&*right, operator) {
+            (Object::Set(_), InfixOperatorType::Equal) => Some(boolean_value(left == right)),
+            (Object::Set(_), InfixOperatorType::NotEqual) => Some(boolean_value(left != right)),
+            _ => None,
+        },
         _ => None,
     };
+    if some_value.is_none()
+        && let Some(overload) = find_overload(operator, &left, &right)
+    {
+        let result = apply_function(
+            token.clone(),
+            overload.function.clone(),
+            overload.arguments.clone(),
+        );
+        return overload.finish(result);
+    }
     some_value.unwrap_or_else(|| {
         error_at(
             format!(
@@ -92,6 +133,21 @@ fn string_infix_evaluation(
         _ => None,
     }
 }
+fn char_infix_evaluation(
+    operator: &InfixOperatorType,
+    left: char,
+    right: char,
+) -> Option<Rc<Object>> {
+    match operator {
+        InfixOperatorType::Plus => Some(string_value(format!("{}{}", left, right))),
+        InfixOperatorType::Equal => Some(boolean_value(left == right)),
+        InfixOperatorType::NotEqual => Some(boolean_value(left != right)),
+        InfixOperatorType::LessThan => Some(boolean_value(left < right)),
+        InfixOperatorType::GreaterThan => Some(boolean_value(left > right)),
+        _ => None,
+    }
+}
+
 fn int_to_string_infix_evaluation(
     operator: &InfixOperatorType,
     left_value: i64,
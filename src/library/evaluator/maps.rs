@@ -3,6 +3,7 @@ use std::{cell::RefCell, rc::Rc};
 use crate::{
     ast::expression::Expression,
     end_flow,
+    evaluator::evaluate_expressions::evaluate_expressions,
     object::{Environment, HashEntry, Object, hash},
 };
 
@@ -27,3 +28,18 @@ pub(crate) fn parse_map_literal(
     }
     Rc::new(Object::HashMap(map))
 }
+
+pub(crate) fn parse_set_literal(
+    elements: &[Expression],
+    environment: Rc<RefCell<Environment>>,
+) -> Rc<Object> {
+    match evaluate_expressions(elements, environment) {
+        Ok(values) => Rc::new(Object::Set(
+            values
+                .into_iter()
+                .map(|value| (hash(&value), value))
+                .collect(),
+        )),
+        Err(error) => error,
+    }
+}
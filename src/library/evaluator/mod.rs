@@ -4,7 +4,7 @@ use crate::ast::modify;
 use crate::object::*;
 use crate::{
     ast::{
-        base::Node,
+        base::{Node, NodeRef},
         expression::Expression,
         statements::{Program, Statement},
     },
@@ -20,6 +20,7 @@ mod evaluate_expressions;
 mod evaluate_identifier;
 mod evaluator_expression;
 mod functional_literal_evaluations;
+mod generators;
 mod infixs;
 mod macros;
 mod maps;
@@ -28,23 +29,20 @@ mod prefixs;
 mod tests;
 
 pub fn evaluate(node: &dyn Node, env: Rc<RefCell<Environment>>) -> Rc<Object> {
-    let statement = node.as_any().downcast_ref::<Statement>();
-    if let Some(statement) = statement {
-        return evaluate_statement(statement, env.clone());
+    match node.as_node() {
+        NodeRef::Program(program) => evaluate_program(program, env),
+        NodeRef::Statement(statement) => evaluate_statement(statement, env),
+        NodeRef::Expression(expression) => evaluate_expression(expression, env),
     }
-    let program = node.as_any().downcast_ref::<Program>();
-    if let Some(program) = program {
-        return evaluate_program(program, env.clone());
-    }
-
-    let expression = node.as_any().downcast_ref::<Expression>();
-    if let Some(expression) = expression {
-        return evaluate_expression(expression, env.clone());
-    }
-    panic!("Should never reach here, node: {:?}", node);
 }
 
 fn evaluate_program(program: &Program, env: Rc<RefCell<Environment>>) -> Rc<Object> {
+    if program.is_strict() {
+        env.borrow_mut().set_strict(true);
+    }
+    if let Err(error) = hoist_functions(&program.statements, env.clone()) {
+        return error;
+    }
     let mut result = null_value();
     for statement in &program.statements {
         result = evaluate(statement, env.clone());
@@ -62,33 +60,62 @@ fn evaluate_block_statements(
     statements: &Vec<Statement>,
     env: Rc<RefCell<Environment>>,
 ) -> Rc<Object> {
+    //let inside of a block only shadows variables of the outer scope until the end of block
+    let block_env = Rc::new(RefCell::new(Environment::enclosed(env)));
+    if let Err(error) = hoist_functions(statements, block_env.clone()) {
+        return error;
+    }
     let mut result = null_value();
     for statement in statements {
-        result = evaluate(statement, env.clone());
+        result = evaluate(statement, block_env.clone());
         end_flow!(result);
     }
     result
 }
 
+fn hoist_functions(
+    statements: &[Statement],
+    env: Rc<RefCell<Environment>>,
+) -> Result<(), Rc<Object>> {
+    for statement in statements {
+        if let Statement::Function {
+            token, name, value, ..
+        } = statement
+        {
+            let function = let_statement(token, name, value, env.clone());
+            if let Object::Error { .. } = function.as_ref() {
+                return Err(function);
+            }
+        }
+    }
+    Ok(())
+}
+
 fn evaluate_statement(statement: &Statement, env: Rc<RefCell<Environment>>) -> Rc<Object> {
     match statement {
         Statement::AExpression { expression, .. } => evaluate_expression(expression, env.clone()),
-        Statement::Block {
-            token: _,
-            statements,
-        } => evaluate_block_statements(statements, env.clone()),
-        Statement::Return {
-            token: _,
-            return_value,
-        } => {
+        Statement::Block { statements, .. } => evaluate_block_statements(statements, env.clone()),
+        Statement::Return { return_value, .. } => {
             let return_value = evaluate_expression(return_value, env.clone());
             Rc::new(Object::ReturnValue(return_value))
         }
-        Statement::Let { token, name, value } => let_statement(token, name, value, env.clone()),
+        Statement::Let {
+            token, name, value, ..
+        }
+        | Statement::Operator {
+            token, name, value, ..
+        } => let_statement(token, name, value, env.clone()),
+        Statement::Assign {
+            token, name, value, ..
+        } => assign_statement(token, name, value, env.clone()),
+        // already defined when its scope was entered
+        Statement::Function { .. } => null_value(),
+        // generator runs its body through steps, yields never get here
+        Statement::Yield { token, .. } => error_at("yield outside of generator", token),
     }
 }
 
-fn let_statement(
+fn assign_statement(
     token: &Token,
     name: &Expression,
     value: &Expression,
@@ -97,16 +124,69 @@ fn let_statement(
     let name = match name {
         Expression::Identifier(token) => match &token.kind {
             TokenKind::Identifier(name) => name.clone(),
-            _ => return error_at("Let statement name must be an identifier", token),
+            _ => return error_at("Assignment target must be an identifier", token),
         },
-        _ => return error_at("Let statement name must be an identifier", token),
+        _ => return error_at("Assignment target must be an identifier", token),
     };
     let value = evaluate_expression(value, env.clone());
     end_flow!(value);
-    env.borrow_mut().set(name, value.clone());
+    if !env.borrow_mut().assign(&name, value.clone()) {
+        return error_at(format!("Identifier '{}' not found.", name).as_str(), token);
+    }
     value
 }
 
+fn let_statement(
+    token: &Token,
+    name: &Expression,
+    value: &Expression,
+    env: Rc<RefCell<Environment>>,
+) -> Rc<Object> {
+    let value = evaluate_expression(value, env.clone());
+    end_flow!(value);
+    if let Err(error) = bind_pattern(token, name, value.clone(), &env) {
+        return error;
+    }
+    value
+}
+
+fn bind_pattern(
+    token: &Token,
+    pattern: &Expression,
+    value: Rc<Object>,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<(), Rc<Object>> {
+    match pattern {
+        Expression::Identifier(name_token) => match &name_token.kind {
+            TokenKind::Identifier(name) => {
+                env.borrow_mut().set(name.clone(), value);
+                Ok(())
+            }
+            _ => Err(error_at(
+                "Let statement name must be an identifier",
+                name_token,
+            )),
+        },
+        Expression::TupleLiteral { elements, .. } => match value.as_ref() {
+            Object::Tuple { elements: values } if values.len() == elements.len() => elements
+                .iter()
+                .zip(values)
+                .try_for_each(|(element, value)| bind_pattern(token, element, value.clone(), env)),
+            _ => Err(error_at(
+                format!(
+                    "Cannot destructure {}({}) into tuple of {} elements",
+                    type_of(&value),
+                    value,
+                    elements.len()
+                )
+                .as_str(),
+                token,
+            )),
+        },
+        _ => Err(error_at("Let statement name must be an identifier", token)),
+    }
+}
+
 //TODO: make more then top level macros
 pub fn define_macros(program: Program, env: Rc<RefCell<Environment>>) -> Program {
     let macros = program
@@ -122,14 +202,13 @@ pub fn define_macros(program: Program, env: Rc<RefCell<Environment>>) -> Program
         .collect::<Vec<_>>();
     macros.iter().for_each(|m| match m {
         Statement::Let {
-            token: _,
             name,
             value,
+            annotation: _,
+            ..
         } => match value {
             Expression::MacroLiteral {
-                token: _,
-                parameters,
-                body,
+                parameters, body, ..
             } => {
                 let name_value = match name {
                     Expression::Identifier(token) => match &token.kind {
@@ -170,60 +249,43 @@ pub fn define_macros(program: Program, env: Rc<RefCell<Environment>>) -> Program
 }
 
 pub fn expand_macros(program: Program, env: Rc<RefCell<Environment>>) -> Program {
-    let modified = modify(Rc::new(program), |node| {
-        let maybe_expression = node.as_any().downcast_ref::<Expression>();
-        let expression = match maybe_expression {
+    modify(program, |expression| {
+        let Expression::Call {
+            function,
+            arguments,
+            ..
+        } = &expression
+        else {
+            return expression;
+        };
+        let obj = match is_macro_call(function, env.clone()) {
             Some(v) => v,
-            None => return node,
+            None => return expression,
         };
-        match expression {
-            Expression::Call {
-                token: _,
-                function,
-                arguments,
-            } => {
-                let obj = match is_macro_call(function, env.clone()) {
-                    Some(v) => v,
-                    None => return node,
-                };
 
-                return match obj.as_ref() {
-                    Object::Macro {
-                        parameters,
-                        body,
-                        env,
-                    } => {
-                        let mut extended_env = Environment::enclosed(env.clone());
-                        let arguments = quote_arguments(arguments);
-                        for (i, parameter) in parameters.iter().enumerate() {
-                            extended_env
-                                .set(parameter.name.clone(), arguments.get(i).unwrap().clone());
-                        }
-                        let evaluated =
-                            evaluate(body.as_ref(), Rc::new(RefCell::new(extended_env)));
-                        match evaluated.as_ref() {
-                            Object::Quote(expression) => expression.clone(),
-                            _ => panic!(
-                                "You can only return AST node from macro. Returned {:?}",
-                                evaluated
-                            ),
-                        }
-                    }
-                    _ => return node,
-                };
+        match obj.as_ref() {
+            Object::Macro {
+                parameters,
+                body,
+                env,
+            } => {
+                let mut extended_env = Environment::enclosed(env.clone());
+                let arguments = quote_arguments(arguments);
+                for (i, parameter) in parameters.iter().enumerate() {
+                    extended_env.set(parameter.name.clone(), arguments.get(i).unwrap().clone());
+                }
+                let evaluated = evaluate(body.as_ref(), Rc::new(RefCell::new(extended_env)));
+                match evaluated.as_ref() {
+                    Object::Quote(expression) => expression.as_ref().clone(),
+                    _ => panic!(
+                        "You can only return AST node from macro. Returned {:?}",
+                        evaluated
+                    ),
+                }
             }
-            _ => return node,
+            _ => expression,
         }
-    });
-    let modified_program = modified
-        .as_any()
-        .downcast_ref::<Program>()
-        .unwrap()
-        .statements
-        .clone();
-    return Program {
-        statements: modified_program,
-    };
+    })
 }
 
 fn quote_arguments(arguments: &Vec<Expression>) -> Vec<Rc<Object>> {
@@ -259,14 +321,15 @@ fn is_macro_call(function: &Expression, env: Rc<RefCell<Environment>>) -> Option
 fn is_macro(statement: &Statement) -> bool {
     match statement {
         Statement::Let {
-            token: _,
             name: _,
             value,
+            annotation: _,
+            ..
         } => match value {
             Expression::MacroLiteral {
-                token: _,
                 parameters: _,
                 body: _,
+                ..
             } => true,
             _ => false,
         },
@@ -0,0 +1,27 @@
+use crate::expected_integer_as_result_tests;
+
+use super::evaluator_tests::should_be_error_with_text;
+
+expected_integer_as_result_tests! {
+    let_in_if_does_not_leak: ("let x = 5; if (true) { let x = 10; x }; x", 5),
+    let_in_if_shadows: ("let x = 5; if (true) { let x = 10; x }", 10),
+    let_in_else_does_not_leak: ("let x = 5; if (false) { 1 } else { let x = 10; x }; x", 5),
+    shadowing_reads_outer: ("let x = 5; if (true) { let x = x + 1; x }", 6),
+    nested_blocks: ("let x = 1; if (true) { let x = 2; if (true) { let x = 3; x }; x }", 2),
+    block_in_function: (r#"
+        let f = fn(a) {
+            let x = 1;
+            if (a > 0) { let x = 10; x + a } else { x }
+        };
+        f(1) + f(0)
+    "#, 12),
+    closure_captures_block_variable: (r#"
+        let f = if (true) { let hidden = 42; fn() { hidden } };
+        f()
+    "#, 42),
+}
+
+#[test]
+fn block_variable_not_visible_outside() {
+    should_be_error_with_text("if (true) { let y = 10; }; y", "Identifier 'y' not found");
+}
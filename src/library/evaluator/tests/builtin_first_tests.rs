@@ -8,7 +8,6 @@ expected_error_with_text! { "Invalid argument",
     {
         cannot_first_integer: "first(1)",
         cannot_first_boolean: "first(true)",
-        cannot_first_string: "first(\"x\")",
     }
 }
 expected_error_with_text! { "Function first expected 1 argument",
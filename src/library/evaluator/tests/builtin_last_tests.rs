@@ -8,7 +8,6 @@ expected_error_with_text! { "Invalid argument",
     {
         cannot_last_integer: "last(1)",
         cannot_last_boolean: "last(true)",
-        cannot_last_string: "last(\"x\")",
     }
 }
 expected_error_with_text! { "Function last expected 1 argument",
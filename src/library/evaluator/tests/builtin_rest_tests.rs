@@ -10,7 +10,6 @@ expected_error_with_text! { "Invalid argument",
     {
         cannot_integer: "rest(1)",
         cannot_boolean: "rest(true)",
-        cannot_string: "rest(\"x\")",
     }
 }
 expected_error_with_text! { "Function rest expected 1 argument",
@@ -0,0 +1,46 @@
+use crate::expected_integer_as_result_tests;
+
+expected_integer_as_result_tests! {
+    called_before_declaration: ("let r = twice(3); fn twice(x) { x * 2 } r", 6),
+    mutual_recursion: (r#"
+        fn even(n) { if (n == 0) { 1 } else { odd(n - 1) } }
+        fn odd(n) { if (n == 0) { 0 } else { even(n - 1) } }
+        even(10) + odd(7)
+    "#, 2),
+    local_mutual_recursion: (r#"
+        let count = fn(limit) {
+            fn ping(n) { if (n == limit) { n } else { pong(n + 1) } }
+            fn pong(n) { ping(n + 1) }
+            ping(0)
+        };
+        count(6)
+    "#, 6),
+    nested_declaration_before_sibling: (r#"
+        let f = fn() {
+            fn a() { b() + 1 }
+            fn b() { 41 }
+            a()
+        };
+        f()
+    "#, 42),
+    hoisted_inside_block: ("if (true) { let r = f(); fn f() { 3 } r }", 3),
+    closure_calls_enclosing_function: (r#"
+        fn countdown(n) {
+            let step = fn() { countdown(n - 1) };
+            if (n == 0) { 0 } else { step() + 1 }
+        }
+        countdown(5)
+    "#, 5),
+    free_variable_through_two_closures: (r#"
+        fn outer() {
+            let x = 7;
+            let mid = fn() {
+                let y = x;
+                let inner = fn() { x };
+                inner() + y
+            };
+            mid()
+        }
+        outer()
+    "#, 14),
+}
@@ -0,0 +1,54 @@
+use crate::{expected_error_with_text, expected_integer_as_result_tests};
+
+expected_integer_as_result_tests! {
+    decorator_wraps_function: (r#"
+        let add_one = fn(f) { fn(x) { f(x) + 1 } };
+        @add_one let f = fn(x) { x * 10 };
+        f(2)
+    "#, 21),
+    nearest_decorator_is_applied_first: (r#"
+        let add_one = fn(f) { fn(x) { f(x) + 1 } };
+        let double = fn(f) { fn(x) { f(x) * 2 } };
+        @add_one @double let f = fn(x) { x };
+        f(3)
+    "#, 7),
+    decorator_with_arguments: (r#"
+        let times = fn(n) { fn(f) { fn(x) { f(x) * n } } };
+        @times(3) let f = fn(x) { x + 1 };
+        f(1)
+    "#, 6),
+    decorator_runs_at_definition: (r#"
+        let defined = 0;
+        let register = fn(f) { defined = defined + 1; f };
+        @register let f = fn(x) { x };
+        @register let g = fn(x) { x };
+        defined
+    "#, 2),
+    recursion_goes_through_decorator: (r#"
+        let calls = 0;
+        let counted = fn(f) { fn(n) { calls = calls + 1; f(n) } };
+        @counted let fact = fn(n) { if (n < 2) { 1 } else { n * fact(n - 1) } };
+        let result = fact(5);
+        result * 10 + calls
+    "#, 1205),
+    decorators_are_evaluated_before_value: (r#"
+        let order = 0;
+        let step = fn(n) { order = order * 10 + n; fn(f) { order = order * 10 + n + 5; f } };
+        @step(1) @step(2) let f = step(3);
+        order
+    "#, 12376),
+    decorated_local: (r#"
+        let outer = fn() {
+            let double = fn(f) { fn(x) { f(x) * 2 } };
+            @double let f = fn(x) { x + 1 };
+            f(4)
+        };
+        outer()
+    "#, 10),
+}
+
+expected_error_with_text! { "Call expression is not a function.",
+    {
+        decorator_is_not_a_function: "@1 let f = fn(x) { x }; f(1)",
+    }
+}
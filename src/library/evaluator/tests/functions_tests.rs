@@ -15,6 +15,7 @@ fn can_construct_function() {
             parameters,
             body,
             env: _,
+            generator: _,
         } => {
             assert_eq!(parameters.len(), 2);
             assert_eq!(parameters[0].to_string(), "a");
@@ -0,0 +1,79 @@
+use crate::expected_integer_as_result_tests;
+
+expected_integer_as_result_tests! {
+    next_runs_until_yield: (r#"
+        let numbers = fn*() { yield 1; yield 2; yield 3; };
+        let g = numbers();
+        next(g);
+        next(g) * 10 + next(g)
+    "#, 23),
+    next_of_finished_generator_is_null: (r#"
+        let g = fn*() { yield 1; }();
+        next(g);
+        if (next(g)) { 1 } else { 0 }
+    "#, 0),
+    done_after_last_value: (r#"
+        let g = fn*() { yield 1; }();
+        let before = done(g);
+        next(g);
+        next(g);
+        if (!before) { if (done(g)) { 1 } else { 2 } } else { 3 }
+    "#, 1),
+    body_does_not_run_before_next: (r#"
+        let counter = 0;
+        let g = fn*() { counter = counter + 1; yield counter; }();
+        let before = counter;
+        next(g) * 10 + before
+    "#, 10),
+    take_stops_at_end: (r#"
+        let g = fn*(a, b) { yield a; yield b; }(4, 5);
+        let values = take(g, 10);
+        len(values) * 100 + values[1] * 10 + values[2]
+    "#, 245),
+    generator_keeps_local_state: (r#"
+        fn* counter(from) {
+            let n = from;
+            yield n;
+            n = n + 1;
+            yield n;
+        }
+        let g = counter(7);
+        next(g) * 10 + next(g)
+    "#, 78),
+    yield_in_branches: (r#"
+        fn* parity(n) {
+            if (n == 0) { yield 1; } else { yield 2; }
+            yield 3;
+        }
+        let g = parity(5);
+        next(g) * 10 + next(g)
+    "#, 23),
+    infinite_naturals: (r#"
+        fn* naturals(n) { yield n; yield* naturals(n + 1); }
+        let values = take(naturals(1), 500);
+        values[500]
+    "#, 500),
+    delegate_then_continue: (r#"
+        fn* inner() { yield 1; yield 2; }
+        fn* outer() { yield* inner(); yield 3; }
+        let values = take(outer(), 5);
+        len(values) * 1000 + values[1] * 100 + values[2] * 10 + values[3]
+    "#, 3123),
+    closure_created_in_generator: (r#"
+        fn* adders() {
+            let step = 1;
+            yield fn(x) { x + step };
+            step = 10;
+            yield 0;
+        }
+        let g = adders();
+        let add = next(g);
+        next(g);
+        add(5)
+    "#, 15),
+    generator_reads_captured_variable: (r#"
+        let make = fn(start) { fn*() { yield start; yield start * 2; } };
+        let g = make(3)();
+        next(g) + next(g)
+    "#, 9),
+}
@@ -12,6 +12,14 @@ fn text_evalaution_of_integers() {
     should_be_integer_equal_to("3", 3);
 }
 
+#[test]
+fn text_evaluation_of_integers_in_other_bases() {
+    should_be_integer_equal_to("0xFF", 255);
+    should_be_integer_equal_to("0b1010", 10);
+    should_be_integer_equal_to("0o755", 493);
+    should_be_integer_equal_to("1_000_000", 1000000);
+}
+
 #[test]
 fn text_evaluation_of_booleans() {
     should_be_boolean_equal_to("true", true);
@@ -80,15 +80,12 @@ fn expand_macro_for_simple_infix() {
     assert_eq!(program.statements.len(), 1);
     let statement = program.statements.get(0).unwrap();
     match statement {
-        crate::ast::statements::Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        crate::ast::statements::Statement::AExpression { expression, .. } => match expression {
             crate::ast::expression::Expression::Infix {
-                token: _,
                 left,
                 operator,
                 right,
+                ..
             } => {
                 check_expression_value!(left.as_ref(), IntegerLiteral, Integer, 1);
                 check_expression_value!(right.as_ref(), IntegerLiteral, Integer, 2);
@@ -115,15 +112,12 @@ fn expand_macro_for_complex_infixc() {
     assert_eq!(program.statements.len(), 1);
     let statement = program.statements.get(0).unwrap();
     match statement {
-        crate::ast::statements::Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        crate::ast::statements::Statement::AExpression { expression, .. } => match expression {
             crate::ast::expression::Expression::Infix {
-                token: _,
                 left,
                 operator,
                 right,
+                ..
             } => {
                 match operator {
                     crate::ast::expression::InfixOperatorType::Minus => {}
@@ -131,10 +125,10 @@ fn expand_macro_for_complex_infixc() {
                 };
                 match left.as_ref() {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         check_expression_value!(left.as_ref(), IntegerLiteral, Integer, 5);
                         check_expression_value!(right.as_ref(), IntegerLiteral, Integer, 2);
@@ -149,10 +143,10 @@ fn expand_macro_for_complex_infixc() {
 
                 match right.as_ref() {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         check_expression_value!(left.as_ref(), IntegerLiteral, Integer, 2);
                         check_expression_value!(right.as_ref(), IntegerLiteral, Integer, 2);
@@ -203,3 +197,37 @@ fn unless_macro() {
         _ => panic!("Expected int got {:?}", result),
     }
 }
+
+#[test]
+fn macros_expand_in_decorated_let() {
+    let program = r#"
+        let unless = macro(condition, consequence, alternative) {
+          quote(if (!(unquote(condition))) { unquote(consequence) } else { unquote(alternative) });
+        };
+        let twice = fn(f) { fn(x) { f(x) * 2 } };
+        @twice let f = fn(x) { unless(x > 10, x, 0) };
+        f(3) + f(11)
+    "#;
+    let result = evaluate_input(program);
+    match result.as_ref() {
+        Object::Int(v) => assert_eq!(6, *v),
+        _ => panic!("Expected int got {:?}", result),
+    }
+}
+
+#[test]
+fn expanded_nodes_keep_spans() {
+    let source = "let m = macro(a) { quote(unquote(1 + 1) * unquote(a)) };\nm(x + y)";
+    let program = prepare_for_evaluation_modify_program(source);
+    let Some(crate::ast::statements::Statement::AExpression {
+        expression: expression @ Expression::Infix { left, right, .. },
+        ..
+    }) = program.statements.first()
+    else {
+        panic!("Expected infix expression, got {}", program);
+    };
+    let text = |expression: &Expression| expression.span().text(source);
+    assert_eq!(text(expression), Some("unquote(1 + 1) * unquote(a)"));
+    assert_eq!(text(left), Some("unquote(1 + 1)"));
+    assert_eq!(text(right), Some("x + y"));
+}
@@ -21,10 +21,10 @@ fn should_quote_addition() {
     match result.as_ref() {
         crate::object::Object::Quote(quoted) => match &quoted.as_ref() {
             Expression::Infix {
-                token: _,
                 left,
                 operator: _,
                 right,
+                ..
             } => {
                 check_if_integer_literal_equals(left, 4);
                 check_if_integer_literal_equals(right, 2);
@@ -44,10 +44,10 @@ fn should_quote_addition_of_ident() {
     match result.as_ref() {
         crate::object::Object::Quote(quoted) => match quoted.as_ref() {
             Expression::Infix {
-                token: _,
                 left,
                 operator: _,
                 right,
+                ..
             } => {
                 check_if_identifiers_equals(left, "one".to_string());
                 check_if_identifiers_equals(right, "two".to_string());
@@ -94,9 +94,7 @@ fn should_unquote_negative_value() {
     match result.as_ref() {
         crate::object::Object::Quote(quoted) => match quoted.as_ref() {
             Expression::PrefixOperator {
-                token: _,
-                operator,
-                right,
+                operator, right, ..
             } => {
                 match operator {
                     crate::ast::expression::PrefixOperatorType::Minus => {}
@@ -120,10 +118,10 @@ fn should_unquote_addition_of_addition() {
     match result.as_ref() {
         crate::object::Object::Quote(quoted) => match quoted.as_ref() {
             Expression::Infix {
-                token: _,
                 left,
                 operator: _,
                 right,
+                ..
             } => {
                 check_if_integer_literal_equals(left, 4);
                 check_if_integer_literal_equals(right, 8);
@@ -169,10 +167,10 @@ fn should_unquote_quote_value() {
     match result.as_ref() {
         crate::object::Object::Quote(quoted) => match quoted.as_ref() {
             Expression::Infix {
-                token: _,
                 left,
                 operator,
                 right,
+                ..
             } => {
                 match operator {
                     InfixOperatorType::Plus => {}
@@ -196,10 +194,10 @@ fn should_unquote_quote_with_addition_avalue() {
     match result.as_ref() {
         crate::object::Object::Quote(quoted) => match quoted.as_ref() {
             Expression::Infix {
-                token: _,
                 left,
                 operator,
                 right,
+                ..
             } => {
                 match operator {
                     InfixOperatorType::Plus => {}
@@ -208,10 +206,10 @@ fn should_unquote_quote_with_addition_avalue() {
                 check_if_integer_literal_equals(&left, 8);
                 match right.as_ref() {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         match operator {
                             InfixOperatorType::Plus => {}
@@ -277,7 +275,7 @@ fn should_unquote_string() {
     }
 }
 
-fn check_if_integer_literal_equals(expression: &Expression, expected_value: u32) {
+fn check_if_integer_literal_equals(expression: &Expression, expected_value: i64) {
     check_expression_value!(expression, IntegerLiteral, Integer, expected_value);
 }
 
@@ -1,15 +1,25 @@
+mod block_scope_tests;
+mod declared_functions_tests;
+mod decorators_tests;
 mod evaluator_tests;
 mod hashmaps_tests;
 
 mod functions_tests;
+mod generators_tests;
 mod if_expression_tests;
 
 mod infixs_tests;
 mod let_tests;
 mod literals_tests;
+mod operators_tests;
 
 mod prefixs_tests;
 mod return_tests;
+mod sets_tests;
+mod strict_tests;
+mod strings_tests;
+mod symbols_tests;
+mod tuples_tests;
 
 mod arrays_tests;
 mod builtin_first_tests;
@@ -19,4 +29,6 @@ mod builtin_push_tests;
 mod builtin_rest_tests;
 mod macro_evaluation_tests;
 mod macro_testing;
+mod mutable_closures_tests;
+mod tail_calls_tests;
 mod using_builtins_to_make_map;
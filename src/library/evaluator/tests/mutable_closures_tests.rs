@@ -0,0 +1,80 @@
+use crate::expected_integer_as_result_tests;
+
+use super::evaluator_tests::should_be_error_with_text;
+
+expected_integer_as_result_tests! {
+    global_assignment: ("let a = 1; a = a + 1; a", 2),
+    counter: (r#"
+        let makeCounter = fn() {
+            let count = 0;
+            fn() { count = count + 1; count }
+        };
+        let counter = makeCounter();
+        counter();
+        counter();
+        counter()
+    "#, 3),
+    closures_share_variable: (r#"
+        let pair = fn() {
+            let value = 0;
+            let inc = fn() { value = value + 10; };
+            let get = fn() { value };
+            [inc, get]
+        };
+        let p = pair();
+        p[1]();
+        p[1]();
+        p[2]()
+    "#, 20),
+    defining_function_sees_write: (r#"
+        let f = fn() {
+            let x = 1;
+            let set = fn() { x = 5; };
+            set();
+            x
+        };
+        f()
+    "#, 5),
+    closure_sees_later_assignment: (r#"
+        let f = fn() {
+            let x = 1;
+            let get = fn() { x };
+            x = 2;
+            get()
+        };
+        f()
+    "#, 2),
+    closure_sees_redefinition: (r#"
+        let f = fn() {
+            let x = 1;
+            let get = fn() { x };
+            let x = 3;
+            get()
+        };
+        f()
+    "#, 3),
+    write_through_nested_closure: (r#"
+        let outer = fn() {
+            let n = 0;
+            let middle = fn() { fn() { n = n + 1; n } };
+            let inc = middle();
+            inc();
+            inc();
+            n
+        };
+        outer()
+    "#, 2),
+    block_variable_survives_slot_reuse: (r#"
+        let f = fn() {
+            let g = if (true) { let hidden = 1; fn() { hidden } };
+            if (true) { let other = 100; other };
+            g()
+        };
+        f()
+    "#, 1),
+}
+
+#[test]
+fn assignment_to_undefined_variable() {
+    should_be_error_with_text("y = 10", "Identifier 'y' not found");
+}
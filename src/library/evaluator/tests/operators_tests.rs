@@ -0,0 +1,64 @@
+use crate::{
+    evaluator::tests::evaluator_tests::eval_input, expected_error_with_text,
+    expected_integer_as_result_tests, expected_string_to_be_equal,
+};
+
+macro_rules! with_vector {
+    ($program:literal) => {
+        concat!(
+            r#"
+            fn vector(x, y) {
+                {
+                    "x": x,
+                    "y": y,
+                    "__add__": fn(a, b) { vector(a["x"] + b["x"], a["y"] + b["y"]) },
+                    "__mul__": fn(a, n) { vector(a["x"] * n, a["y"] * n) },
+                    "__eq__": fn(a, b) { if (a["x"] == b["x"]) { a["y"] == b["y"] } else { false } },
+                    "__lt__": fn(a, b) { a["x"] * a["x"] + a["y"] * a["y"] < b["x"] * b["x"] + b["y"] * b["y"] },
+                    "__str__": fn(v) { "(" + v["x"] + ", " + v["y"] + ")" }
+                }
+            }
+            "#,
+            $program
+        )
+    };
+}
+
+expected_integer_as_result_tests! {
+    overloaded_addition: (with_vector!(r#"let v = vector(1, 2) + vector(3, 4); v["x"] * 10 + v["y"]"#), 46),
+    overloaded_multiplication: (with_vector!(r#"let v = vector(1, 2) * 3; v["x"] * 10 + v["y"]"#), 36),
+    overloaded_equality: (with_vector!("if (vector(1, 2) == vector(1, 2)) { 1 } else { 0 }"), 1),
+    overloaded_inequality: (with_vector!("if (vector(1, 2) != vector(2, 1)) { 1 } else { 0 }"), 1),
+    overloaded_less_than: (with_vector!("if (vector(1, 1) < vector(2, 2)) { 1 } else { 0 }"), 1),
+    overloaded_greater_than: (with_vector!("if (vector(1, 1) > vector(2, 2)) { 1 } else { 0 }"), 0),
+    builtin_rule_comes_first: (with_vector!(r#"vector(1, 2)["x"] + 1"#), 2),
+}
+
+#[test]
+fn overloaded_display() {
+    let result = eval_input(with_vector!("str(vector(1, 2))"));
+    expected_string_to_be_equal!(result, "(1, 2)");
+}
+
+#[test]
+fn overloaded_display_in_concatenation() {
+    let result = eval_input(with_vector!(r#""v = " + vector(1, 2) + "!""#));
+    expected_string_to_be_equal!(result, "v = (1, 2)!");
+}
+
+expected_error_with_text! { "Cannot use + on HashMap",
+    {
+        map_without_operator: r#"{"a": 1} + {"b": 2}"#,
+    }
+}
+
+expected_integer_as_result_tests! {
+    declared_operator: ("infix 60 <+> = fn(a, b) { a * 10 + b }; 1 <+> 2 <+> 3", 123),
+    declared_right_associative: ("infixr 70 ^^ = fn(a, b) { a - b }; 10 ^^ 4 ^^ 1 * 2", 8),
+    declared_from_function: ("fn sub(a, b) { a - b }; infix 60 -- = sub; 10 -- 1 + 2", 11),
+    declared_recursive: (r#"
+        infix 30 |> = fn(x, f) { f(x) };
+        infix 70 ** = fn(a, n) { if (n == 0) { 1 } else { a * (a ** (n - 1)) } };
+        2 ** 3 |> fn(x) { x + 1 }
+    "#, 9),
+}
@@ -0,0 +1,41 @@
+use crate::{
+    evaluator::tests::evaluator_tests::eval_input, expected_error_with_text,
+    expected_integer_as_result_tests,
+};
+
+expected_integer_as_result_tests! {
+    duplicates_are_removed: ("len(#{1, 2, 2, 1, 3})", 3),
+    empty_set: ("len(#{})", 0),
+    contains_element: ("if (contains(#{1, \"a\"}, \"a\")) { 1 } else { 0 }", 1),
+    does_not_contain_element: ("if (contains(#{1, 2}, 3)) { 1 } else { 0 }", 0),
+    union_of_sets: ("len(union(#{1, 2}, #{2, 3}))", 3),
+    intersection_of_sets: ("len(intersection(#{1, 2, 3}, #{2, 3, 4}))", 2),
+    difference_of_sets: ("len(difference(#{1, 2, 3}, #{2}))", 2),
+    sets_are_equal_in_any_order: ("if (#{1, 2, 3} == #{3, 2, 1}) { 1 } else { 0 }", 1),
+    sets_are_different: ("if (#{1, 2} != #{1}) { 1 } else { 0 }", 1),
+    tuples_in_set: ("len(#{(1, 2), (1, 2), (2, 1)})", 2),
+    set_as_map_key: ("{#{1, 2}: 5}[#{2, 1}]", 5),
+}
+
+#[test]
+fn set_is_printed_in_order() {
+    let result = eval_input("#{10, 2, \"b\", 1, \"a\"}");
+    assert_eq!(result.to_string(), "#{1, 2, 10, a, b}");
+}
+
+#[test]
+fn union_is_printed_in_order() {
+    let result = eval_input("union(#{3, 1}, #{2})");
+    assert_eq!(result.to_string(), "#{1, 2, 3}");
+}
+
+expected_error_with_text! { "Invalid argument 2 for union: Array([1]) expected Set",
+    {
+        union_with_array: "union(#{1}, [1])",
+    }
+}
+expected_error_with_text! { "Invalid argument 1 for contains: Int(1) expected Set",
+    {
+        contains_of_integer: "contains(1, 1)",
+    }
+}
@@ -0,0 +1,67 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    evaluator::{evaluate, tests::evaluator_tests::eval_input},
+    expected_error_with_text, expected_integer_as_result_tests, expected_string_to_be_equal,
+    object::{Environment, Object},
+    parser::Parser,
+};
+
+expected_error_with_text! { "Strict mode does not allow + on Boolean(true) and Int(1)",
+    {
+        boolean_and_integer: "\"use strict\"; true + 1",
+    }
+}
+expected_error_with_text! { "Strict mode does not allow + on String(a) and Null(NULL)",
+    {
+        string_and_null: "\"use strict\"; let n = if (false) { 1 }; \"a\" + n",
+    }
+}
+expected_error_with_text! { "Strict mode does not allow + on Int(1) and String(a)",
+    {
+        integer_and_string: "\"use strict\"; 1 + \"a\"",
+        inside_of_function: "\"use strict\"; let f = fn(x) { x + \"a\" }; f(1)",
+    }
+}
+expected_error_with_text! { "Strict mode does not allow + on Char(a) and String(b)",
+    {
+        char_and_string: "\"use strict\"; \"a\"[1] + \"b\"",
+    }
+}
+
+expected_integer_as_result_tests! {
+    same_types_are_allowed: ("\"use strict\"; 1 + 2 * 3", 7),
+    repeating_string_is_allowed: ("\"use strict\"; len(\"ab\" * 3)", 6),
+    directive_must_come_first: ("1; \"use strict\"; len(\"a\" + 1)", 2),
+}
+
+#[test]
+fn coercion_without_strict_mode() {
+    let result = eval_input("let n = if (false) { 1 }; \"a\" + n");
+    expected_string_to_be_equal!(result, "aNULL");
+}
+
+#[test]
+fn error_is_positioned_at_operator() {
+    let result = eval_input("\"use strict\";\nlet a = 1;\na + \"b\"");
+    match result.as_ref() {
+        Object::Error { line, column, .. } => assert_eq!((*line, *column), (3, 3)),
+        _ => panic!("Expected error, got {result}"),
+    }
+}
+
+#[test]
+fn strict_environment() {
+    let mut parser = Parser::from_string("let n = if (false) { 1 }; \"a\" + n");
+    let program = parser.parse_program();
+    let mut env = Environment::new();
+    env.set_strict(true);
+    let result = evaluate(&program, Rc::new(RefCell::new(env)));
+    match result.as_ref() {
+        Object::Error { message, .. } => assert_eq!(
+            message,
+            "Strict mode does not allow + on String(a) and Null(NULL)"
+        ),
+        _ => panic!("Expected error, got {result}"),
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    evaluator::tests::evaluator_tests::eval_input, expected_error_with_text,
+    expected_integer_as_result_tests, expected_string_to_be_equal,
+};
+
+expected_integer_as_result_tests! {
+    len_counts_characters: (r#"len("żółw")"#, 4),
+    len_of_emoji: (r#"len("🦀🦀")"#, 2),
+    index_is_by_character: (r#"if ("żółw"[2] == "ó"[1]) { 1 } else { 0 }"#, 1),
+    negative_index: (r#"if ("żółw"[-1] == "w"[1]) { 1 } else { 0 }"#, 1),
+    chars_splits_into_characters: (r#"len(chars("żółw"))"#, 4),
+    first_of_string: (r#"if (first("żółw") == "ż"[1]) { 1 } else { 0 }"#, 1),
+    last_of_string: (r#"if (last("żółw") == "w"[1]) { 1 } else { 0 }"#, 1),
+    rest_of_string: (r#"len(rest("żółw"))"#, 3),
+    chars_are_ordered: (r#"if ("a"[1] < "b"[1]) { 1 } else { 0 }"#, 1),
+    unicode_identifiers: ("let żółw = 2; let _x = 3; żółw * _x", 6),
+}
+
+#[test]
+fn char_joins_into_string() {
+    let result = eval_input(r#""żółw"[1] + "ółw""#);
+    expected_string_to_be_equal!(result, "żółw");
+}
+
+#[test]
+fn rest_keeps_characters() {
+    let result = eval_input(r#"rest("żółw")"#);
+    expected_string_to_be_equal!(result, "ółw");
+}
+
+expected_error_with_text! { "strings are 1 indexed",
+    {
+        zero_index_of_string: r#""abc"[0]"#,
+    }
+}
+expected_error_with_text! { "Index out of bounds: 4",
+    {
+        index_after_end_of_string: r#""żół"[4]"#,
+    }
+}
+expected_error_with_text! { "Cannot get first character for empty string",
+    {
+        first_of_empty_string: r#"first("")"#,
+    }
+}
+expected_error_with_text! { "Invalid argument 1 for chars",
+    {
+        chars_of_integer: "chars(1)",
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{
+    evaluator::tests::evaluator_tests::eval_input, expected_error_with_text,
+    expected_integer_as_result_tests,
+};
+
+expected_integer_as_result_tests! {
+    same_symbols_are_equal: ("if (:ok == :ok) { 1 } else { 0 }", 1),
+    different_symbols: ("if (:ok != :error) { 1 } else { 0 }", 1),
+    symbol_as_map_key: ("let m = {:ok: 1, :error: 2}; m[:error]", 2),
+    map_with_identifier_keys: ("let a = 1; let b = 5; {a:b}[1]", 5),
+    symbols_in_set: ("len(#{:a, :b, :a})", 2),
+    tagged_result: (r#"
+        let check = fn(x) { if (x > 0) { (:ok, x) } else { (:error, 0) } };
+        let (tag, value) = check(3);
+        if (tag == :ok) { value } else { 0 - 1 }
+    "#, 3),
+}
+
+#[test]
+fn symbol_display() {
+    let result = eval_input("[:ok, :error]");
+    assert_eq!(result.to_string(), "[:ok, :error]");
+}
+
+expected_error_with_text! { "Cannot use + on Symbol(:ok) and Symbol(:ok)",
+    {
+        adding_symbols: ":ok + :ok",
+    }
+}
+expected_error_with_text! { "Cannot use == on Symbol(:ok) and String(ok)",
+    {
+        symbol_is_not_string: ":ok == \"ok\"",
+    }
+}
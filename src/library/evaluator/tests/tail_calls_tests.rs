@@ -0,0 +1,32 @@
+use crate::expected_integer_as_result_tests;
+
+expected_integer_as_result_tests! {
+    tail_recursive_loop: (r#"
+        fn count(n, acc) { if (n == 0) { acc } else { count(n - 1, acc + 1) } }
+        count(10000, 0)
+    "#, 10000),
+    tail_call_in_return: (r#"
+        fn down(n) {
+            if (n == 0) { return 7; }
+            return down(n - 1);
+        }
+        down(10000)
+    "#, 7),
+    mutual_tail_calls: (r#"
+        fn even(n) { if (n == 0) { 1 } else { odd(n - 1) } }
+        fn odd(n) { if (n == 0) { 0 } else { even(n - 1) } }
+        even(10000)
+    "#, 1),
+    tail_call_of_local_closure: (r#"
+        let sum = fn(limit) {
+            fn go(n, acc) { if (n > limit) { acc } else { go(n + 1, acc + n) } }
+            go(1, 0)
+        };
+        sum(1000)
+    "#, 500500),
+    call_which_is_not_in_tail_position: (r#"
+        fn depth(n) { if (n == 0) { 0 } else { 1 + depth(n - 1) } }
+        depth(50)
+    "#, 50),
+    tail_call_of_builtin: ("fn size(a) { len(a) } size([1, 2, 3])", 3),
+}
@@ -0,0 +1,40 @@
+use crate::{
+    evaluator::tests::evaluator_tests::eval_input, expected_error_with_text,
+    expected_integer_as_result_tests, expected_string_to_be_equal,
+};
+
+expected_integer_as_result_tests! {
+    len_of_tuple: ("len((1, 2, 3))", 3),
+    index_of_tuple: ("(4, 5, 6)[2]", 5),
+    negative_index_of_tuple: ("(4, 5, 6)[-1]", 6),
+    grouped_expression_is_not_tuple: ("(1 + 2) * 3", 9),
+    tuples_are_equal: ("if ((1, \"a\") == (1, \"a\")) { 1 } else { 0 }", 1),
+    tuples_are_different: ("if ((1, 2) != (2, 1)) { 1 } else { 0 }", 1),
+    tuple_as_map_key: ("{(1, 2): 5, (2, 1): 7}[(1, 2)]", 5),
+    destructuring_let: ("let (a, b) = (3, 4); a * 10 + b", 34),
+    nested_destructuring: ("let (a, (b, c)) = (1, (2, 3)); a * 100 + b * 10 + c", 123),
+    destructuring_returned_tuple: ("let pair = fn(x) { (x, x * 2) }; let (a, b) = pair(3); a + b", 9),
+}
+
+#[test]
+fn tuple_display() {
+    let result = eval_input("((1,), (), \"a\")");
+    assert_eq!(result.to_string(), "((1,), (), a)");
+}
+
+#[test]
+fn tuple_element_is_kept() {
+    let result = eval_input("let (_, s) = (1, \"two\"); s");
+    expected_string_to_be_equal!(result, "two");
+}
+
+expected_error_with_text! { "Cannot destructure Tuple((1, 2, 3)) into tuple of 2 elements",
+    {
+        destructuring_wrong_length: "let (a, b) = (1, 2, 3); a",
+    }
+}
+expected_error_with_text! { "Cannot destructure Int(1) into tuple of 2 elements",
+    {
+        destructuring_not_tuple: "let (a, b) = 1; a",
+    }
+}
@@ -0,0 +1,139 @@
+//group is printed on one line when it fits, hard lines break even inside of a flat group
+#[derive(Debug, Clone)]
+pub(super) enum Doc {
+    Text(String),
+    Line,     //space, new line when the group breaks
+    SoftLine, //nothing, new line when the group breaks
+    HardLine,
+    IfBreak(&'static str),
+    Suffix(String),   //comment at the end of the line, not counted into the width
+    Indent(Vec<Doc>), //body of a block, always indented
+    IndentIfBreak(Vec<Doc>), //elements of a list, indented only when the group breaks
+    Group(Vec<Doc>),
+    Concat(Vec<Doc>),
+}
+
+impl Doc {
+    pub(super) fn is_multiline(&self) -> bool {
+        match self {
+            Doc::HardLine | Doc::Suffix(_) => true,
+            Doc::Indent(docs) | Doc::IndentIfBreak(docs) | Doc::Group(docs) | Doc::Concat(docs) => {
+                docs.iter().any(Doc::is_multiline)
+            }
+            _ => false,
+        }
+    }
+}
+
+pub(super) fn text(text: impl Into<String>) -> Doc {
+    Doc::Text(text.into())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+pub(super) fn render(doc: &Doc, width: usize, indent_width: usize) -> String {
+    let mut printer = Printer::default();
+    let mut stack = vec![(0, Mode::Break, doc)];
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Text(text) | Doc::Suffix(text) => printer.write(text),
+            Doc::Line if mode == Mode::Flat => printer.write(" "),
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => printer.new_line(indent),
+            Doc::IfBreak(text) if mode == Mode::Break => printer.write(text),
+            Doc::IfBreak(_) => {}
+            Doc::Indent(docs) => {
+                let indent = indent + indent_width;
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            }
+            Doc::IndentIfBreak(docs) => {
+                let indent = match mode {
+                    Mode::Flat => indent,
+                    Mode::Break => indent + indent_width,
+                };
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            }
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+            Doc::Group(docs) => {
+                let start = printer.pending_indent.unwrap_or(printer.column);
+                let flat = mode == Mode::Flat || fits(docs, &stack, width.saturating_sub(start));
+                let mode = if flat { Mode::Flat } else { Mode::Break };
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            }
+        }
+    }
+    printer.output
+}
+
+#[derive(Default)]
+struct Printer {
+    output: String,
+    column: usize,
+    pending_indent: Option<usize>, //written with the next text, so empty lines stay empty
+}
+
+impl Printer {
+    fn write(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if let Some(indent) = self.pending_indent.take() {
+            self.output.extend(std::iter::repeat_n(' ', indent));
+            self.column = indent;
+        }
+        self.output.push_str(text);
+        self.column = match text.rfind('\n') {
+            Some(line_start) => text[line_start + 1..].chars().count(),
+            None => self.column + text.chars().count(),
+        };
+    }
+
+    fn new_line(&mut self, indent: usize) {
+        self.output.push('\n');
+        self.column = 0;
+        self.pending_indent = Some(indent);
+    }
+}
+
+//only the line the group starts on is measured
+fn fits(group: &[Doc], rest: &[(usize, Mode, &Doc)], width: usize) -> bool {
+    let mut remaining = width as isize;
+    let mut pending: Vec<(Mode, &Doc)> = group.iter().rev().map(|doc| (Mode::Flat, doc)).collect();
+    let mut rest = rest.iter().rev();
+    loop {
+        let (mode, doc) = match pending.pop() {
+            Some(next) => next,
+            None => match rest.next() {
+                Some((_, mode, doc)) => (*mode, *doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(text) => {
+                let line = text.split('\n').next().unwrap_or_default();
+                remaining -= line.chars().count() as isize;
+                if remaining < 0 {
+                    return false;
+                }
+                if line.len() < text.len() {
+                    return true;
+                }
+            }
+            Doc::Line if mode == Mode::Flat => remaining -= 1,
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => return true,
+            Doc::IfBreak(text) if mode == Mode::Break => remaining -= text.len() as isize,
+            Doc::IfBreak(_) | Doc::Suffix(_) => {}
+            Doc::Indent(docs) | Doc::IndentIfBreak(docs) | Doc::Concat(docs) | Doc::Group(docs) => {
+                pending.extend(docs.iter().rev().map(|doc| (mode, doc)))
+            }
+        }
+        if remaining < 0 {
+            return false;
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use indoc::indoc;
+
+use crate::parser::Parser;
+
+use super::{FormatOptions, TrailingCommas, format_source};
+
+fn format(source: &str) -> String {
+    format_with(source, &FormatOptions::default())
+}
+
+fn format_with(source: &str, options: &FormatOptions) -> String {
+    format_source(source, options).unwrap_or_else(|errors| panic!("{:?}", errors))
+}
+
+fn narrow(width: usize, trailing_commas: TrailingCommas) -> FormatOptions {
+    FormatOptions {
+        width,
+        trailing_commas,
+        ..FormatOptions::default()
+    }
+}
+
+#[test]
+fn canonical_layout() {
+    let source = indoc! {"
+        let   add=fn(a,b){a+b}
+        fn fib(n: Int) -> Int { if (n < 2) { return n; } else { fib(n - 1) + fib(n - 2) } }
+        let m = {\"one\": 1, \"two\": 2,};let t=(1,)
+        @memo @trace(:calls)
+        let g = fn*(a) { yield a; yield* other(a) };
+        map(xs, fn(x) { let y = x * 2; y })
+    "};
+    let expected = indoc! {"
+        let add = fn(a, b) { a + b };
+        fn fib(n: Int) -> Int {
+            if (n < 2) {
+                return n;
+            } else {
+                fib(n - 1) + fib(n - 2)
+            }
+        }
+        let m = {\"one\": 1, \"two\": 2};
+        let t = (1,);
+        @memo
+        @trace(:calls)
+        let g = fn*(a) {
+            yield a;
+            yield* other(a);
+        };
+        map(xs, fn(x) {
+            let y = x * 2;
+            y
+        });
+    "};
+    assert_eq!(format(source), expected);
+}
+
+#[test]
+fn minimal_parentheses() {
+    let tests = [
+        ("((a + b) * c)", "(a + b) * c;\n"),
+        ("(a - b) - c", "a - b - c;\n"),
+        ("a - (b - c)", "a - (b - c);\n"),
+        ("a / (b * c)", "a / (b * c);\n"),
+        ("(a * b) + (c * d)", "a * b + c * d;\n"),
+        ("-(a + b)", "-(a + b);\n"),
+        ("(-a) * b", "-a * b;\n"),
+        ("(a < b) == (c > d)", "a < b == c > d;\n"),
+        ("(a + b)(1)[2]", "(a + b)(1)[2];\n"),
+        ("(-f)(1)", "(-f)(1);\n"),
+        ("(f(1))[(2)]", "f(1)[2];\n"),
+        ("--a", "--a;\n"),
+    ];
+    for (source, expected) in tests {
+        assert_eq!(format(source), expected, "for {}", source);
+    }
+}
+
+#[test]
+fn declared_operators() {
+    let declarations = "infix 60 <+> = add; infixr 75 ** = pow;";
+    let tests = [
+        ("(a <+> b) <+> c", "a <+> b <+> c;"),
+        ("a <+> (b <+> c)", "a <+> (b <+> c);"),
+        ("a ** (b ** c)", "a ** b ** c;"),
+        ("(a ** b) ** c", "(a ** b) ** c;"),
+        ("(a * b) <+> (c + d)", "a * b <+> (c + d);"),
+        ("(a <+> b) * (c ** -d)", "(a <+> b) * c ** -d;"),
+    ];
+    for (source, expected) in tests {
+        let formatted = format(&format!("{}{}", declarations, source));
+        assert_eq!(
+            formatted,
+            format!("infix 60 <+> = add;\ninfixr 75 ** = pow;\n{}\n", expected),
+            "for {}",
+            source
+        );
+    }
+}
+
+#[test]
+fn breaking_long_lines() {
+    let source = "let total = first + second * third_value - fourth;\n\
+                  puts(message(\"hello\", name, 1 + 2), other_argument);";
+    let expected = indoc! {"
+        let total = first +
+            second * third_value -
+            fourth;
+        puts(
+            message(\"hello\", name, 1 + 2),
+            other_argument,
+        );
+    "};
+    assert_eq!(
+        format_with(source, &narrow(36, TrailingCommas::Vertical)),
+        expected
+    );
+    assert_eq!(
+        format_with(expected, &FormatOptions::default()),
+        "let total = first + second * third_value - fourth;\n\
+         puts(message(\"hello\", name, 1 + 2), other_argument);\n"
+    );
+}
+
+#[test]
+fn trailing_commas() {
+    let source = "let a = [first_element, second_element];";
+    let tests = [
+        (
+            TrailingCommas::Never,
+            80,
+            "let a = [first_element, second_element];\n",
+        ),
+        (
+            TrailingCommas::Always,
+            80,
+            "let a = [first_element, second_element,];\n",
+        ),
+        (
+            TrailingCommas::Vertical,
+            80,
+            "let a = [first_element, second_element];\n",
+        ),
+        (
+            TrailingCommas::Vertical,
+            20,
+            "let a = [\n    first_element,\n    second_element,\n];\n",
+        ),
+        (
+            TrailingCommas::Never,
+            20,
+            "let a = [\n    first_element,\n    second_element\n];\n",
+        ),
+    ];
+    for (trailing_commas, width, expected) in tests {
+        let options = narrow(width, trailing_commas);
+        assert_eq!(format_with(source, &options), expected);
+    }
+}
+
+#[test]
+fn keeping_comments_and_empty_lines() {
+    let source = indoc! {"
+        // helpers
+        let a = 1; // one
+
+
+        // adds numbers
+        let add = fn(a, b) { // body
+            a + b // sum
+            // nothing after
+        };
+        add(a, [1, // inside
+            2]) // trailing
+        // end
+    "};
+    let expected = indoc! {"
+        // helpers
+        let a = 1; // one
+
+        // adds numbers
+        let add = fn(a, b) {
+            // body
+            a + b // sum
+            // nothing after
+        };
+        add(a, [1, 2]); // inside // trailing
+        // end
+    "};
+    assert_eq!(format(source), expected);
+}
+
+#[test]
+fn statements_continued_by_next_line() {
+    //without `;` the tuple would become arguments of a call of the `if`
+    let source =
+        "if (a) { b };\n(1, 2)\nif (a) { b };\n[1][1]\nif (a) { b };-1;\nif (a) { b }\nputs(1)";
+    let expected = indoc! {"
+        if (a) { b };
+        (1, 2);
+        if (a) { b };
+        [1][1];
+        if (a) { b };
+        -1;
+        if (a) { b }
+        puts(1);
+    "};
+    assert_eq!(format(source), expected);
+}
+
+#[test]
+fn symbols_after_braces() {
+    //`:` right after `}` belongs to a map, it starts a symbol only after `;`
+    let source = "fn f() {};\n:a\nif (a) { b };\n:b + 1";
+    let expected = "fn f() {};\n:a;\nif (a) { b };\n:b + 1;\n";
+    assert_eq!(format(source), expected);
+}
+
+#[test]
+fn formatting_is_idempotent_and_keeps_program() {
+    let sources = [
+        "let add = fn(a, b) { a + b }; add(1, 2)",
+        "fn* numbers(n) { let i = 0; if (i < n) { yield i; } }",
+        "let (a, (b, c)) = (1, (2, 3)); let s = #{a, b}; let m = {:k: [a, b]}",
+        "let f: fn(Int) -> Int = fn(x: Int) -> Int { if (x > 0) { x } else { -x } };",
+        "let m = macro(a, b) { quote(unquote(a) + unquote(b)) }; m(1, 2)",
+        "// only a comment",
+        "",
+        "\"use strict\"; let x = 0x1F * (2 + 3) - f(1)[2] / -(4 - 5);",
+        "let long = [[1, 2, 3], {\"key\": fn(a) { if (a) { a } else { [a, a, a, a, a, a] } }}];",
+    ];
+    for source in sources {
+        for width in [10, 40, 80] {
+            let options = narrow(width, TrailingCommas::Vertical);
+            let formatted = format_with(source, &options);
+            assert_eq!(
+                format_with(&formatted, &options),
+                formatted,
+                "for {}",
+                source
+            );
+            let original = Parser::from_string(source).parse_program();
+            let reformatted = Parser::from_string(&formatted).parse_program();
+            assert_eq!(reformatted.to_string(), original.to_string());
+        }
+    }
+}
+
+#[test]
+fn program_with_errors_is_not_formatted() {
+    let errors = format_source("let 1 = 2;", &FormatOptions::default()).unwrap_err();
+    assert!(!errors.is_empty());
+}
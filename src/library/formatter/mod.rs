@@ -0,0 +1,682 @@
+mod document;
+#[cfg(test)]
+mod formatter_tests;
+
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    ast::{
+        Visitor,
+        expression::{Expression, PrefixOperatorType},
+        statements::{Associativity, Fixity, Program, Statement},
+        types::{Signature, display_result},
+        visit,
+    },
+    parser::{ParseError, Parser, Precedence, operator_precedence, parse_lossless},
+    tokens::{Token, TokenKind, Trivia},
+};
+
+use document::{Doc, render, text};
+
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    pub width: usize,  //lines longer than this are broken where the syntax allows it
+    pub indent: usize, //spaces of one level of indentation
+    pub trailing_commas: TrailingCommas,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            width: 80,
+            indent: 4,
+            trailing_commas: TrailingCommas::Vertical,
+        }
+    }
+}
+
+//tuple of one element always has it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailingCommas {
+    Never,
+    Vertical, //only when the elements are broken into lines
+    Always,
+}
+
+//formatting the result again gives the same text
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, Vec<String>> {
+    let (tree, errors) = parse_lossless(source);
+    if !errors.is_empty() {
+        return Err(errors.iter().map(ParseError::to_string).collect());
+    }
+    let program = tree.to_program();
+    let formatted = print(&program, &tree.tokens(), options);
+    //guards the promise that only the layout changes, program parsed from the result is the same
+    let mut parser = Parser::from_string(&formatted);
+    let formatted_program = parser.parse_program();
+    if !parser.errors.is_empty() || formatted_program != program {
+        return Err(vec![String::from(
+            "Formatting would change the program, source is left as it is",
+        )]);
+    }
+    Ok(formatted)
+}
+
+//trees without source, from macros or imports, print literals in their plain form
+pub fn print_program(program: &Program) -> String {
+    print(program, &[], &FormatOptions::default())
+}
+
+fn print(program: &Program, tokens: &[Rc<Token>], options: &FormatOptions) -> String {
+    let mut formatter = Formatter::new(tokens, options);
+    formatter.visit_program(program);
+    let lines = formatter.statements(&program.statements, usize::MAX, false);
+    match lines.is_empty() {
+        true => String::new(),
+        false => render(
+            &Doc::Concat(vec![join_lines(lines), Doc::HardLine]),
+            options.width,
+            options.indent,
+        ),
+    }
+}
+
+struct Comment {
+    before: usize, //offset of the token following the comment
+    text: String,
+    own_line: bool,     //nothing but whitespace in front of it on its line
+    blank_before: bool, //separated from the previous line by an empty line
+}
+
+struct Formatter<'a> {
+    options: &'a FormatOptions,
+    comments: Vec<Comment>,
+    next_comment: usize,
+    blank_lines: HashSet<usize>, //offsets of tokens with an empty line right in front of them
+    block_ends: HashMap<usize, usize>, //offset of `{` to offset of its `}`
+    operators: HashMap<String, Fixity>, //declared by the program
+}
+
+impl<'a> Formatter<'a> {
+    fn new(tokens: &[Rc<Token>], options: &'a FormatOptions) -> Self {
+        let mut formatter = Formatter {
+            options,
+            comments: vec![],
+            next_comment: 0,
+            blank_lines: HashSet::new(),
+            block_ends: HashMap::new(),
+            operators: HashMap::new(),
+        };
+        let mut open_braces = vec![];
+        for (index, token) in tokens.iter().enumerate() {
+            let (Some(span), Some(layout)) = (token.span, &token.layout) else {
+                continue;
+            };
+            let mut own_line = index == 0;
+            let mut blank = false;
+            for trivia in &layout.trivia {
+                match trivia {
+                    Trivia::Newline(_) => {
+                        blank = own_line;
+                        own_line = true;
+                    }
+                    Trivia::Comment(comment) => {
+                        formatter.comments.push(Comment {
+                            before: span.start,
+                            text: comment.trim_end().to_string(),
+                            own_line,
+                            blank_before: blank,
+                        });
+                        (own_line, blank) = (false, false);
+                    }
+                    Trivia::Whitespace(_) | Trivia::Skipped(_) => {}
+                }
+            }
+            if blank {
+                formatter.blank_lines.insert(span.start);
+            }
+            match token.kind {
+                TokenKind::LeftBrace | TokenKind::SetStart => open_braces.push(span.start),
+                TokenKind::RightBrace => {
+                    if let Some(open) = open_braces.pop() {
+                        formatter.block_ends.insert(open, span.start);
+                    }
+                }
+                _ => {}
+            }
+        }
+        formatter
+    }
+
+    //comment after a statement stays on its line, others go on their own lines before the next one
+    fn statements(&mut self, statements: &[Statement], end: usize, block: bool) -> Vec<Doc> {
+        let mut lines = vec![];
+        for (index, statement) in statements.iter().enumerate() {
+            let start = statement_start(statement);
+            self.comments_before(start, &mut lines);
+            if self.blank_lines.contains(&start) && !lines.is_empty() {
+                lines.push(Doc::Concat(vec![]));
+            }
+            let next = statements.get(index + 1);
+            let mut line = vec![self.statement(statement)];
+            if self.needs_semicolon(statement, next, block) {
+                line.push(text(";"));
+            }
+            let limit = next.map_or(end, statement_start);
+            while let Some(comment) = self.comments.get(self.next_comment)
+                && comment.before <= limit
+                && !comment.own_line
+            {
+                line.push(Doc::Suffix(format!(" {}", comment.text)));
+                self.next_comment += 1;
+            }
+            lines.push(Doc::Concat(line));
+        }
+        self.comments_before(end, &mut lines);
+        lines
+    }
+
+    fn comments_before(&mut self, offset: usize, lines: &mut Vec<Doc>) {
+        while let Some(comment) = self.comments.get(self.next_comment)
+            && comment.before <= offset
+        {
+            if comment.blank_before && !lines.is_empty() {
+                lines.push(Doc::Concat(vec![]));
+            }
+            lines.push(text(comment.text.clone()));
+            self.next_comment += 1;
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Doc {
+        match statement {
+            Statement::Let {
+                name,
+                value,
+                annotation,
+                ..
+            } => {
+                let mut docs = vec![];
+                let value = match value {
+                    Expression::Decorated {
+                        decorators, value, ..
+                    } => {
+                        for decorator in decorators {
+                            docs.extend([text("@"), self.expression(decorator), Doc::HardLine]);
+                        }
+                        value
+                    }
+                    value => value,
+                };
+                docs.extend([text("let "), self.expression(name)]);
+                if let Some(annotation) = annotation {
+                    docs.push(text(format!(": {}", annotation)));
+                }
+                docs.extend([text(" = "), self.expression(value)]);
+                Doc::Concat(docs)
+            }
+            Statement::Assign { name, value, .. } => Doc::Concat(vec![
+                self.expression(name),
+                text(" = "),
+                self.expression(value),
+            ]),
+            Statement::Return { return_value, .. } => {
+                Doc::Concat(vec![text("return "), self.expression(return_value)])
+            }
+            Statement::Yield {
+                value, delegate, ..
+            } => {
+                let keyword = if *delegate { "yield* " } else { "yield " };
+                Doc::Concat(vec![text(keyword), self.expression(value)])
+            }
+            Statement::Function { name, value, .. } => match value {
+                Expression::FunctionLiteral {
+                    parameters,
+                    body,
+                    generator,
+                    signature,
+                    ..
+                } => Doc::Concat(vec![
+                    text(if *generator { "fn* " } else { "fn " }),
+                    self.expression(name),
+                    self.parameters(parameters, signature),
+                    text(display_result(signature)),
+                    text(" "),
+                    self.block(body),
+                ]),
+                value => Doc::Concat(vec![
+                    text("fn "),
+                    self.expression(name),
+                    text(" = "),
+                    self.expression(value),
+                ]),
+            },
+            Statement::AExpression { expression, .. } => self.expression(expression),
+            Statement::Block { .. } => self.block(statement),
+            Statement::Operator {
+                fixity,
+                name,
+                value,
+                ..
+            } => Doc::Concat(vec![
+                text(format!("{} ", fixity)),
+                self.expression(name),
+                text(" = "),
+                self.expression(value),
+            ]),
+        }
+    }
+
+    fn block(&mut self, block: &Statement) -> Doc {
+        let (lines, compact) = self.block_lines(block);
+        Doc::Group(vec![braces(lines, compact)])
+    }
+
+    //only a single short expression may stay on the line of the braces
+    fn block_lines(&mut self, block: &Statement) -> (Vec<Doc>, bool) {
+        let Statement::Block {
+            token, statements, ..
+        } = block
+        else {
+            return (vec![self.statement(block)], false);
+        };
+        let end = token
+            .span
+            .and_then(|span| self.block_ends.get(&span.start).copied())
+            .unwrap_or_default();
+        let lines = self.statements(statements, end, true);
+        let compact = match (statements.as_slice(), lines.as_slice()) {
+            ([Statement::AExpression { .. }], [line]) => !line.is_multiline(),
+            (_, lines) => lines.is_empty(),
+        };
+        (lines, compact)
+    }
+
+    fn expression(&mut self, expression: &Expression) -> Doc {
+        if let Some((operator, left, right)) = expression.operator_call() {
+            let precedence = self.binding(expression);
+            let left = self.operand(left, precedence, self.right_associative(left));
+            let grouped_on_equal = !self.right_associative(expression);
+            let right = self.operand(right, precedence, grouped_on_equal);
+            return Doc::Group(vec![
+                left,
+                Doc::IndentIfBreak(vec![text(format!(" {}", operator)), Doc::Line, right]),
+            ]);
+        }
+        match expression {
+            Expression::Identifier(token)
+            | Expression::IntegerLiteral(token)
+            | Expression::StringLiteral(token)
+            | Expression::SymbolLiteral(token) => text(token_text(token)),
+            Expression::BooleanLiteral { value, .. } => text(value.to_string()),
+            Expression::PrefixOperator {
+                operator, right, ..
+            } => {
+                let operand = match self.glues_operator(operator, right) {
+                    true => Doc::Concat(vec![text("("), self.expression(right), text(")")]),
+                    false => self.operand(right, Precedence::PREFIX, false),
+                };
+                Doc::Concat(vec![text(operator.to_string()), operand])
+            }
+            Expression::Infix { .. } => {
+                //chain of operators of the same precedence breaks as a whole, `a + b - c` is
+                //`(a + b) - c` so only right operands of the same precedence need parentheses
+                let precedence = self.binding(expression);
+                let mut chain = vec![];
+                let mut first = expression;
+                while let Expression::Infix {
+                    left,
+                    operator,
+                    right,
+                    ..
+                } = first
+                    && operator_precedence(operator) == precedence
+                {
+                    chain.push((operator, right));
+                    first = left;
+                }
+                let first = self.operand(first, precedence, self.right_associative(first));
+                let mut rest = vec![];
+                for (operator, right) in chain.into_iter().rev() {
+                    rest.extend([
+                        text(format!(" {}", operator)),
+                        Doc::Line,
+                        self.operand(right, precedence, true),
+                    ]);
+                }
+                Doc::Group(vec![first, Doc::IndentIfBreak(rest)])
+            }
+            Expression::Call {
+                function,
+                arguments,
+                ..
+            } => {
+                let function = self.operand(function, Precedence::CALL, false);
+                let arguments = arguments.iter().map(|a| self.expression(a)).collect();
+                Doc::Concat(vec![function, self.list("(", arguments, ")")])
+            }
+            Expression::Index { array, index, .. } => Doc::Concat(vec![
+                self.operand(array, Precedence::CALL, false),
+                text("["),
+                self.expression(index),
+                text("]"),
+            ]),
+            Expression::AIf {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => {
+                //branches are broken into lines together, or both stay on one line
+                let condition = self.expression(condition);
+                let (consequence, mut compact) = self.block_lines(consequence);
+                let alternative = alternative.as_ref().map(|alternative| {
+                    let (lines, alternative_compact) = self.block_lines(alternative);
+                    compact &= alternative_compact;
+                    lines
+                });
+                let mut docs = vec![
+                    text("if ("),
+                    condition,
+                    text(") "),
+                    braces(consequence, compact),
+                ];
+                if let Some(alternative) = alternative {
+                    docs.extend([text(" else "), braces(alternative, compact)]);
+                }
+                Doc::Group(docs)
+            }
+            Expression::FunctionLiteral {
+                parameters,
+                body,
+                generator,
+                signature,
+                ..
+            } => Doc::Concat(vec![
+                text(if *generator { "fn*" } else { "fn" }),
+                self.parameters(parameters, signature),
+                text(display_result(signature)),
+                text(" "),
+                self.block(body),
+            ]),
+            Expression::MacroLiteral {
+                parameters, body, ..
+            } => Doc::Concat(vec![
+                text("macro"),
+                self.parameters(parameters, &None),
+                text(" "),
+                self.block(body),
+            ]),
+            Expression::ArrayLiteral { elements, .. } => {
+                let elements = elements.iter().map(|e| self.expression(e)).collect();
+                self.list("[", elements, "]")
+            }
+            Expression::TupleLiteral { elements, .. } if elements.len() == 1 => {
+                Doc::Concat(vec![text("("), self.expression(&elements[0]), text(",)")])
+            }
+            Expression::TupleLiteral { elements, .. } => {
+                let elements = elements.iter().map(|e| self.expression(e)).collect();
+                self.list("(", elements, ")")
+            }
+            Expression::SetLiteral { elements, .. } => {
+                let elements = elements.iter().map(|e| self.expression(e)).collect();
+                self.list("#{", elements, "}")
+            }
+            Expression::MapLiteral { elements, .. } => {
+                let entries = elements
+                    .iter()
+                    .map(|(key, value)| {
+                        Doc::Concat(vec![
+                            self.expression(key),
+                            text(": "),
+                            self.expression(value),
+                        ])
+                    })
+                    .collect();
+                self.list("{", entries, "}")
+            }
+            Expression::Decorated {
+                decorators, value, ..
+            } => {
+                let mut docs = vec![];
+                for decorator in decorators {
+                    docs.extend([text("@"), self.expression(decorator), text(" ")]);
+                }
+                docs.push(self.expression(value));
+                Doc::Concat(docs)
+            }
+        }
+    }
+
+    //`grouped_on_equal` is set for the right operand, `a - (b - c)` needs parentheses
+    fn operand(
+        &mut self,
+        operand: &Expression,
+        precedence: Precedence,
+        grouped_on_equal: bool,
+    ) -> Doc {
+        let binding = self.binding(operand);
+        let doc = self.expression(operand);
+        if binding < precedence || (grouped_on_equal && binding == precedence) {
+            Doc::Concat(vec![text("("), doc, text(")")])
+        } else {
+            doc
+        }
+    }
+
+    fn parameters(&mut self, parameters: &[Expression], signature: &Option<Rc<Signature>>) -> Doc {
+        let parameters = parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| {
+                let annotation = signature
+                    .as_ref()
+                    .and_then(|signature| signature.parameters.get(index).cloned().flatten());
+                match annotation {
+                    Some(annotation) => Doc::Concat(vec![
+                        self.expression(parameter),
+                        text(format!(": {}", annotation)),
+                    ]),
+                    None => self.expression(parameter),
+                }
+            })
+            .collect();
+        self.list("(", parameters, ")")
+    }
+
+    fn list(&self, open: &str, elements: Vec<Doc>, close: &str) -> Doc {
+        if elements.is_empty() {
+            return text(format!("{}{}", open, close));
+        }
+        let mut inner = vec![Doc::SoftLine];
+        for (index, element) in elements.into_iter().enumerate() {
+            if index > 0 {
+                inner.extend([text(","), Doc::Line]);
+            }
+            inner.push(element);
+        }
+        match self.options.trailing_commas {
+            TrailingCommas::Never => {}
+            TrailingCommas::Vertical => inner.push(Doc::IfBreak(",")),
+            TrailingCommas::Always => inner.push(text(",")),
+        }
+        Doc::Group(vec![
+            text(open),
+            Doc::IndentIfBreak(inner),
+            Doc::SoftLine,
+            text(close),
+        ])
+    }
+
+    //`-!a` would be read as a declared operator `-!`
+    fn glues_operator(&self, operator: &PrefixOperatorType, right: &Expression) -> bool {
+        let Expression::PrefixOperator { operator: next, .. } = right else {
+            return false;
+        };
+        let glued = format!("{}{}", operator, next);
+        self.operators
+            .keys()
+            .any(|declared| declared.starts_with(&glued))
+    }
+
+    fn binding(&self, expression: &Expression) -> Precedence {
+        if let Some((operator, ..)) = expression.operator_call() {
+            return self
+                .operators
+                .get(operator)
+                .map_or(Precedence::LOWEST, |fixity| Precedence(fixity.precedence));
+        }
+        match expression {
+            Expression::Infix { operator, .. } => operator_precedence(operator),
+            Expression::PrefixOperator { .. } => Precedence::PREFIX,
+            Expression::Call { .. } => Precedence::CALL,
+            _ => Precedence::INDEX,
+        }
+    }
+
+    //`infixr` takes operators of its precedence on the right, on the left they need parentheses
+    fn right_associative(&self, expression: &Expression) -> bool {
+        expression.operator_call().is_some_and(|(operator, ..)| {
+            self.operators
+                .get(operator)
+                .is_some_and(|fixity| fixity.associativity == Associativity::Right)
+        })
+    }
+
+    //`if` needs one when the next statement would continue it, `if (a) { f }\n(1, 2)`
+    //symbol right after `}` would be read as `:` of a map
+    fn needs_semicolon(
+        &self,
+        statement: &Statement,
+        next: Option<&Statement>,
+        block: bool,
+    ) -> bool {
+        let next_expression = match next {
+            Some(Statement::AExpression { expression, .. }) => Some(expression),
+            _ => None,
+        };
+        match statement {
+            Statement::Block { .. } => false,
+            Statement::Function { .. } => {
+                next_expression.is_some_and(|next| self.starts_with_symbol(next))
+            }
+            Statement::AExpression {
+                expression: Expression::AIf { .. },
+                ..
+            } => next_expression.is_some_and(|next| {
+                self.continues_expression(next) || self.starts_with_symbol(next)
+            }),
+            Statement::AExpression { .. } => !block || next.is_some(),
+            _ => true,
+        }
+    }
+
+    fn continues_expression(&self, expression: &Expression) -> bool {
+        if let Some((_, left, _)) = expression.operator_call() {
+            return self.binding(left) < self.binding(expression)
+                || self.continues_expression(left);
+        }
+        match expression {
+            Expression::TupleLiteral { .. }
+            | Expression::ArrayLiteral { .. }
+            | Expression::PrefixOperator {
+                operator: PrefixOperatorType::Minus,
+                ..
+            } => true,
+            Expression::Infix { left, operator, .. } => {
+                self.binding(left) < operator_precedence(operator)
+                    || self.continues_expression(left)
+            }
+            Expression::Call { function, .. } => {
+                self.binding(function) < Precedence::CALL || self.continues_expression(function)
+            }
+            Expression::Index { array, .. } => {
+                self.binding(array) < Precedence::CALL || self.continues_expression(array)
+            }
+            _ => false,
+        }
+    }
+
+    //may say yes more often than it should
+    fn starts_with_symbol(&self, expression: &Expression) -> bool {
+        if let Some((_, left, _)) = expression.operator_call() {
+            return self.binding(left) >= self.binding(expression) && self.starts_with_symbol(left);
+        }
+        match expression {
+            Expression::SymbolLiteral(_) => true,
+            Expression::Infix { left, operator, .. } => {
+                self.binding(left) >= operator_precedence(operator) && self.starts_with_symbol(left)
+            }
+            Expression::Call { function, .. } => {
+                self.binding(function) >= Precedence::CALL && self.starts_with_symbol(function)
+            }
+            Expression::Index { array, .. } => {
+                self.binding(array) >= Precedence::CALL && self.starts_with_symbol(array)
+            }
+            _ => false,
+        }
+    }
+}
+
+//operators are collected first, a use never comes before its declaration
+impl Visitor for Formatter<'_> {
+    fn visit_statement(&mut self, statement: &Statement) {
+        if let Statement::Operator { name, fixity, .. } = statement {
+            self.operators.insert(name.to_string(), *fixity);
+        }
+        visit::walk_statement(self, statement);
+    }
+}
+
+fn braces(lines: Vec<Doc>, compact: bool) -> Doc {
+    if lines.is_empty() {
+        return text("{}");
+    }
+    let line = if compact { Doc::Line } else { Doc::HardLine };
+    Doc::Concat(vec![
+        text("{"),
+        Doc::Indent(vec![line.clone(), join_lines(lines)]),
+        line,
+        text("}"),
+    ])
+}
+
+fn join_lines(lines: Vec<Doc>) -> Doc {
+    let mut docs = vec![];
+    for (index, line) in lines.into_iter().enumerate() {
+        if index > 0 {
+            docs.push(Doc::HardLine);
+        }
+        docs.push(line);
+    }
+    Doc::Concat(docs)
+}
+
+fn statement_start(statement: &Statement) -> usize {
+    let token = match statement {
+        Statement::Let {
+            value: Expression::Decorated { token, .. },
+            ..
+        } => token,
+        Statement::Assign { name, .. } => name.token(),
+        Statement::Let { token, .. }
+        | Statement::Return { token, .. }
+        | Statement::Function { token, .. }
+        | Statement::Yield { token, .. }
+        | Statement::AExpression { token, .. }
+        | Statement::Block { token, .. }
+        | Statement::Operator { token, .. } => token,
+    };
+    token.span.map_or(0, |span| span.start)
+}
+
+fn token_text(token: &Token) -> String {
+    match (&token.layout, &token.kind) {
+        (Some(layout), _) => layout.text.clone(),
+        (None, TokenKind::StringLiteral(value)) => format!("\"{}\"", value),
+        (None, kind) => kind.literal(),
+    }
+}
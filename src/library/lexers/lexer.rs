@@ -1,83 +1,299 @@
+use std::borrow::Cow;
+use std::io::{ErrorKind, Read};
 use std::rc::Rc;
-use std::{cmp, collections::VecDeque, env};
 
-use crate::tokens::Token;
-
-use super::{
-    dispatch::{dispatch, end_of_line, finish_it},
-    parsing_states::LexerState,
+use crate::{
+    lines::{Span, TokenPosition},
+    tokens::{Layout, Token, TokenKind, Trivia},
 };
 
-pub struct Lexer {
-    source: VecDeque<Rc<Token>>,
-    current_line: u16,
-    current_column: u16,
-    state: LexerState,
+use super::scanner::{Cursor, Lexeme, Scanner};
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+//tokens are scanned from a buffer filled in chunks, text already read is dropped
+//source given whole is borrowed, it is copied only when lines are added to it
+pub struct Lexer<'a> {
+    source: Cow<'a, str>, //text not read yet, together with the token being read
+    dropped: usize,       //bytes of the input dropped from the front of the buffer
+    cursor: Cursor,       //relative to the buffer
+    input: Input,
+    started: bool,           //lines after the first one are separated with a new line
+    failure: Option<String>, //input could not be read, reported after the last token
+    lossless: bool,
+    trailing: String,       //trivia after the last token, kept in lossless mode
+    operators: Vec<String>, //declared by the script, scanned as single tokens
+    operator_follows: bool, //next token is an operator being declared
+    peeked: Option<(Option<Rc<Token>>, Cursor)>, //next token, until the state it depends on changes
 }
 
-impl Lexer {
+enum Input {
+    Finished,
+    Reader {
+        reader: Box<dyn Read>,
+        undecoded: Vec<u8>, //bytes of a character split between chunks
+    },
+    Chunks(Box<dyn Iterator<Item = String>>),
+}
+
+impl<'a> Lexer<'a> {
     pub fn new() -> Self {
+        Self::with_input(Cow::Borrowed(""), Input::Finished)
+    }
+
+    pub fn from_source(source: &'a str) -> Self {
+        let mut lexer = Self::with_input(Cow::Borrowed(source), Input::Finished);
+        lexer.started = true;
+        lexer
+    }
+
+    pub fn from_reader(reader: impl Read + 'static) -> Self {
+        Self::with_input(
+            Cow::Borrowed(""),
+            Input::Reader {
+                reader: Box::new(reader),
+                undecoded: vec![],
+            },
+        )
+    }
+
+    pub fn from_chunks<I>(chunks: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+        I::IntoIter: 'static,
+    {
+        Self::with_input(
+            Cow::Borrowed(""),
+            Input::Chunks(Box::new(chunks.into_iter())),
+        )
+    }
+
+    fn with_input(source: Cow<'a, str>, input: Input) -> Self {
         Lexer {
-            source: VecDeque::new(),
-            current_line: 0,
-            current_column: 0,
-            state: LexerState::Idle,
+            source,
+            dropped: 0,
+            cursor: Cursor::start(),
+            input,
+            started: false,
+            failure: None,
+            lossless: false,
+            trailing: String::new(),
+            operators: vec![],
+            operator_follows: false,
+            peeked: None,
+        }
+    }
+
+    pub fn set_lossless(&mut self, lossless: bool) {
+        self.lossless = lossless;
+        self.peeked = None;
+    }
+
+    pub(crate) fn declare_operator(&mut self, operator: &str) {
+        if !self.operators.iter().any(|declared| declared == operator) {
+            self.operators.push(operator.to_string());
+            self.peeked = None;
         }
     }
 
+    //operator being declared, `infix 60 <+>`, is not known yet
+    pub(crate) fn expect_operator(&mut self) {
+        self.operator_follows = true;
+        self.peeked = None;
+    }
+
     pub fn process(&mut self, line: &str) {
-        self.current_line += 1;
-        self.current_column = 0;
-        for (i, c) in line.chars().enumerate() {
-            self.current_column = i as u16 + 1;
-            if env::var("DEBUG").is_ok() {
-                println!("Processing character: {}, state: {:?}", c, self.state);
-            }
-            let result = dispatch(self.current_line, self.current_column, c, &self.state);
-            self.state = result.0;
-            let tokens = result.1;
-            for token in tokens {
-                self.source.push_back(Rc::new(token));
-            }
+        let source = self.source.to_mut();
+        if self.started {
+            source.push('\n');
+        }
+        self.started = true;
+        source.push_str(line);
+        self.peeked = None;
+    }
+
+    pub fn peek(&mut self) -> Option<Rc<Token>> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.scan());
+        }
+        self.peeked.as_ref().and_then(|(token, _)| token.clone())
+    }
+
+    pub(crate) fn end_of_input(&mut self) -> Token {
+        let position = self.cursor.following();
+        let offset = self.dropped + self.source.len();
+        Token {
+            context: Some(TokenPosition::new(position, position)),
+            span: Some(Span::new(offset, offset)),
+            kind: TokenKind::EndOfInput,
+            layout: self.lossless.then(|| {
+                Box::new(Layout {
+                    trivia: split_trivia(&std::mem::take(&mut self.trailing)),
+                    text: String::new(),
+                })
+            }),
         }
-        let result = end_of_line(&self.state, self.current_line, self.current_column);
-        if let Some(s) = result.0 {
-            self.state = s;
+    }
+
+    //long runs read before the buffer ran out are not read again after the next chunk
+    fn scan(&mut self) -> (Option<Rc<Token>>, Cursor) {
+        let mut runs = vec![];
+        loop {
+            let mut scanner = Scanner::resume(&self.source, self.cursor)
+                .with_operators(&self.operators)
+                .with_read_runs(runs);
+            let lexeme = match self.operator_follows {
+                true => scanner.operator(),
+                false => scanner.next(),
+            };
+            //a declared operator split between chunks must not be read as shorter tokens
+            let complete = lexeme
+                .as_ref()
+                .is_some_and(|lexeme| lexeme.span.end + scanner.lookahead() <= self.source.len());
+            if complete || matches!(self.input, Input::Finished) {
+                return (lexeme.map(|lexeme| self.token(&lexeme)), scanner.cursor());
+            }
+            runs = scanner.into_read_runs();
+            self.fill();
         }
-        let tokens = result.1;
-        for token in tokens {
-            self.source.push_back(Rc::new(token));
+    }
+
+    fn token(&self, lexeme: &Lexeme) -> Rc<Token> {
+        let mut token = lexeme.to_token();
+        token.span = token.span.map(|span| span.shifted(self.dropped));
+        if self.lossless {
+            token.layout = Some(Box::new(Layout {
+                trivia: split_trivia(lexeme.trivia),
+                text: lexeme.text.to_string(),
+            }));
         }
-        self.current_column = cmp::max(1, self.current_column);
+        Rc::new(token)
     }
 
-    pub fn next(&mut self) -> Option<Rc<Token>> {
-        let result = self.source.pop_front();
-        match result {
-            Some(token) => Some(token),
-            None => {
-                if env::var("DEBUG").is_ok() {
-                    println!("Finishing up {:?}", self.state);
+    fn fill(&mut self) {
+        match &mut self.input {
+            Input::Finished => {}
+            Input::Chunks(chunks) => match chunks.next() {
+                Some(chunk) => self.source.to_mut().push_str(&chunk),
+                None => self.input = Input::Finished,
+            },
+            Input::Reader { reader, undecoded } => {
+                let mut buffer = [0; CHUNK_SIZE];
+                let read = loop {
+                    match reader.read(&mut buffer) {
+                        Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                        result => break result,
+                    }
+                };
+                match read {
+                    Ok(0) => {
+                        //bytes of an unfinished character are the last part of the input
+                        let rest = String::from_utf8_lossy(undecoded);
+                        self.source.to_mut().push_str(&rest);
+                        self.input = Input::Finished;
+                    }
+                    Ok(read) => {
+                        undecoded.extend_from_slice(&buffer[..read]);
+                        decode(undecoded, self.source.to_mut());
+                    }
+                    Err(error) => {
+                        self.failure = Some(format!("Cannot read input: {}", error));
+                        self.input = Input::Finished;
+                    }
                 }
-                self.finish()
             }
         }
     }
 
-    fn finish(&mut self) -> Option<Rc<Token>> {
-        if env::var("DEBUG").is_ok() {
-            println!("Finishing up {:?}", self.state);
+    fn drop_read(&mut self) {
+        if self.cursor.offset < CHUNK_SIZE || self.cursor.offset < self.source.len() / 2 {
+            return;
+        }
+        match &mut self.source {
+            Cow::Borrowed(source) => *source = &source[self.cursor.offset..],
+            Cow::Owned(source) => {
+                source.drain(..self.cursor.offset);
+            }
+        }
+        self.dropped += self.cursor.offset;
+        self.cursor.offset = 0;
+    }
+}
+
+//bytes which can still become a character wait for the next chunk
+fn decode(bytes: &mut Vec<u8>, into: &mut String) {
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => {
+                into.push_str(text);
+                bytes.clear();
+                return;
+            }
+            Err(error) => {
+                let valid = error.valid_up_to();
+                let Some(invalid) = error.error_len() else {
+                    into.push_str(std::str::from_utf8(&bytes[..valid]).unwrap());
+                    bytes.drain(..valid);
+                    return;
+                };
+                into.push_str(&String::from_utf8_lossy(&bytes[..valid + invalid]));
+                bytes.drain(..valid + invalid);
+            }
         }
-        let token = finish_it(&self.state, self.current_line, self.current_column);
-        self.state = LexerState::Idle;
-        token.map(Rc::new)
     }
 }
 
-impl Iterator for Lexer {
+fn split_trivia(text: &str) -> Vec<Trivia> {
+    let mut trivia = vec![];
+    let mut rest = text;
+    while let Some(first) = rest.chars().next() {
+        let length = match first {
+            '\n' => 1,
+            '\r' if rest.starts_with("\r\n") => 2,
+            '/' => rest.find('\n').unwrap_or(rest.len()),
+            first if first.is_whitespace() => rest
+                .find(|c: char| !c.is_whitespace() || c == '\n' || c == '\r')
+                .unwrap_or(rest.len())
+                .max(first.len_utf8()),
+            _ => rest
+                .find(|c: char| c.is_whitespace() || c == '/')
+                .unwrap_or(rest.len()),
+        };
+        let (piece, remaining) = rest.split_at(length);
+        trivia.push(match first {
+            '\n' | '\r' if piece.ends_with('\n') => Trivia::Newline(piece.to_string()),
+            '/' => Trivia::Comment(piece.to_string()),
+            first if first.is_whitespace() => Trivia::Whitespace(piece.to_string()),
+            _ => Trivia::Skipped(piece.to_string()),
+        });
+        rest = remaining;
+    }
+    trivia
+}
+
+impl Iterator for Lexer<'_> {
     type Item = Rc<Token>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.next()
+        let (token, cursor) = self.peeked.take().unwrap_or_else(|| self.scan());
+        if token.is_none() && self.lossless {
+            self.trailing
+                .push_str(&self.source[self.cursor.offset..cursor.offset]);
+        }
+        self.cursor = cursor;
+        self.operator_follows = false;
+        self.drop_read();
+        token.or_else(|| {
+            let problem = self.failure.take()?;
+            let mut token = self.end_of_input();
+            token.kind = TokenKind::Invalid(problem);
+            Some(Rc::new(token))
+        })
+    }
+}
+
+impl Default for Lexer<'_> {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -1,8 +1,8 @@
-use std::rc::Rc;
+use std::{io::Read, rc::Rc};
 
-use super::lexer::Lexer;
-use crate::lines::{ColumnNumber, LineNumber, TextPosition, TokenPosition};
-use crate::tokens::{Token, TokenKind};
+use super::{Lexeme, Scanner, lexer::Lexer};
+use crate::lines::{ColumnNumber, LineNumber, Span, TextPosition, TokenPosition};
+use crate::tokens::{Layout, PureTokenKind, Token, TokenKind, Trivia};
 
 #[test]
 fn next_sign() {
@@ -294,10 +294,10 @@ fn perform_test(input: Vec<&str>, expected: Vec<(TokenPosition, TokenKind)>) {
 }
 
 pub fn position(
-    start_line: u16,
-    start_column: u16,
-    end_line: u16,
-    end_column: u16,
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
 ) -> TokenPosition {
     TokenPosition {
         start: TextPosition {
@@ -310,7 +310,7 @@ pub fn position(
         },
     }
 }
-pub fn single(line_number: u16, column_number: u16) -> TokenPosition {
+pub fn single(line_number: u32, column_number: u32) -> TokenPosition {
     TokenPosition {
         start: TextPosition {
             line_number: LineNumber(line_number),
@@ -322,3 +322,469 @@ pub fn single(line_number: u16, column_number: u16) -> TokenPosition {
         },
     }
 }
+
+#[test]
+fn unicode_and_underscore_identifiers() {
+    let input = vec!["żółw _tmp x_1 π2 \"ółw\""];
+    let expected = vec![
+        (
+            position(1, 1, 1, 4),
+            TokenKind::Identifier(String::from("żółw")),
+        ),
+        (
+            position(1, 6, 1, 9),
+            TokenKind::Identifier(String::from("_tmp")),
+        ),
+        (
+            position(1, 11, 1, 13),
+            TokenKind::Identifier(String::from("x_1")),
+        ),
+        (
+            position(1, 15, 1, 16),
+            TokenKind::Identifier(String::from("π2")),
+        ),
+        (
+            position(1, 18, 1, 22),
+            TokenKind::StringLiteral(String::from("ółw")),
+        ),
+    ];
+
+    perform_test(input, expected);
+}
+
+#[test]
+fn non_ascii_punctuation_is_not_a_name() {
+    let kinds: Vec<TokenKind> = Lexer::from_source("a«b» — 😀c cafe\u{301} ∑")
+        .map(|token| token.kind.clone())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Identifier(String::from("a")),
+            TokenKind::Identifier(String::from("b")),
+            TokenKind::Identifier(String::from("c")),
+            TokenKind::Identifier(String::from("cafe\u{301}")),
+        ]
+    );
+}
+
+#[test]
+fn numbers_in_other_bases() {
+    let input = vec!["0xFF 0b1010 0o755 1_000_000 0x_1 1__0 2_ 0x 0b12 4294967296 0xffffffff"];
+    let expected = vec![
+        (position(1, 1, 1, 4), TokenKind::Integer(255)),
+        (position(1, 6, 1, 11), TokenKind::Integer(10)),
+        (position(1, 13, 1, 17), TokenKind::Integer(493)),
+        (position(1, 19, 1, 27), TokenKind::Integer(1_000_000)),
+        (
+            position(1, 29, 1, 32),
+            TokenKind::Invalid(String::from("Unexpected character '_' in number")),
+        ),
+        (
+            position(1, 34, 1, 37),
+            TokenKind::Invalid(String::from("Unexpected character '_' in number")),
+        ),
+        (
+            position(1, 39, 1, 40),
+            TokenKind::Invalid(String::from("Number can not end with '_'")),
+        ),
+        (
+            position(1, 42, 1, 43),
+            TokenKind::Invalid(String::from("Missing digits of hexadecimal number")),
+        ),
+        (
+            position(1, 45, 1, 48),
+            TokenKind::Invalid(String::from("Digit '2' is not allowed in binary number")),
+        ),
+        (position(1, 50, 1, 59), TokenKind::Integer(4_294_967_296)),
+        (position(1, 61, 1, 70), TokenKind::Integer(0xFFFF_FFFF)),
+    ];
+
+    perform_test(input, expected);
+}
+
+#[test]
+fn numbers_as_wide_as_runtime_integers() {
+    let input = vec![
+        "0xFFFFFFFFFF 9223372036854775807 9223372036854775808 0x7fffffffffffffff 0x8000000000000000",
+    ];
+    let out_of_range = || {
+        TokenKind::Invalid(String::from(
+            "Number is out of range, largest allowed is 9223372036854775807",
+        ))
+    };
+    let expected = vec![
+        (position(1, 1, 1, 12), TokenKind::Integer(0xFF_FFFF_FFFF)),
+        (position(1, 14, 1, 32), TokenKind::Integer(i64::MAX)),
+        (position(1, 34, 1, 52), out_of_range()),
+        (position(1, 54, 1, 71), TokenKind::Integer(i64::MAX)),
+        (position(1, 73, 1, 90), out_of_range()),
+    ];
+
+    perform_test(input, expected);
+}
+
+#[test]
+fn set_start() {
+    let input = vec!["#{1} # {"];
+    let expected = vec![
+        (position(1, 1, 1, 2), TokenKind::SetStart),
+        (single(1, 3), TokenKind::Integer(1)),
+        (single(1, 4), TokenKind::RightBrace),
+        (
+            single(1, 6),
+            TokenKind::Invalid(String::from("Expected { after #")),
+        ),
+        (single(1, 8), TokenKind::LeftBrace),
+    ];
+    perform_test(input, expected);
+}
+
+#[test]
+fn symbols_and_collons() {
+    let input = vec![r#"{:ok: :error, a:b, "s":c}"#];
+    let expected = vec![
+        (single(1, 1), TokenKind::LeftBrace),
+        (position(1, 2, 1, 4), TokenKind::Symbol(String::from("ok"))),
+        (single(1, 5), TokenKind::Collon),
+        (
+            position(1, 7, 1, 12),
+            TokenKind::Symbol(String::from("error")),
+        ),
+        (single(1, 13), TokenKind::Comma),
+        (single(1, 15), TokenKind::Identifier(String::from("a"))),
+        (single(1, 16), TokenKind::Collon),
+        (single(1, 17), TokenKind::Identifier(String::from("b"))),
+        (single(1, 18), TokenKind::Comma),
+        (
+            position(1, 20, 1, 22),
+            TokenKind::StringLiteral(String::from("s")),
+        ),
+        (single(1, 23), TokenKind::Collon),
+        (single(1, 24), TokenKind::Identifier(String::from("c"))),
+        (single(1, 25), TokenKind::RightBrace),
+    ];
+    perform_test(input, expected);
+}
+
+#[test]
+fn arrow_and_minus() {
+    let input = vec!["f -> -1 x-"];
+    let expected = vec![
+        (single(1, 1), TokenKind::Identifier(String::from("f"))),
+        (position(1, 3, 1, 4), TokenKind::Arrow),
+        (single(1, 6), TokenKind::Minus),
+        (single(1, 7), TokenKind::Integer(1)),
+        (single(1, 9), TokenKind::Identifier(String::from("x"))),
+        (single(1, 10), TokenKind::Minus),
+    ];
+    perform_test(input, expected);
+}
+
+#[test]
+fn lexemes_borrow_source() {
+    let source = "let żółw = \"ab\";";
+    let lexemes: Vec<Lexeme> = Scanner::new(source).collect();
+    let texts: Vec<&str> = lexemes.iter().map(|lexeme| lexeme.text).collect();
+    assert_eq!(texts, vec!["let", "żółw", "=", "\"ab\"", ";"]);
+    let spans: Vec<Span> = lexemes.iter().map(|lexeme| lexeme.span).collect();
+    assert_eq!(
+        spans,
+        vec![
+            Span::new(0, 3),
+            Span::new(4, 11),
+            Span::new(12, 13),
+            Span::new(14, 18),
+            Span::new(18, 19)
+        ]
+    );
+    assert_eq!(lexemes[1].span.text(source), Some("żółw"));
+    assert_eq!(Span::new(2, 40).text(source), None);
+    assert_eq!(Span::new(5, 11).text(source), None);
+    assert_eq!(lexemes[1].position, position(1, 5, 1, 8));
+    assert_eq!(lexemes[3].kind, PureTokenKind::StringLiteral);
+}
+
+#[test]
+fn positions_of_long_sources() {
+    let mut source = "x;\n".repeat(70_000);
+    source.push_str(&" ".repeat(70_000));
+    source.push_str("last");
+    let token = Lexer::from_source(&source).last().unwrap();
+    assert_eq!(token.kind, TokenKind::Identifier(String::from("last")));
+    assert_eq!(
+        token.context.unwrap(),
+        position(70_001, 70_001, 70_001, 70_004)
+    );
+    assert_eq!(
+        token.span.unwrap(),
+        Span::new(source.len() - 4, source.len())
+    );
+}
+
+#[test]
+fn tokens_split_between_chunks() {
+    let chunks = vec!["let s = \"ab", "\ncd\"; le", "t x", "y = 1"];
+    let lexer = Lexer::from_chunks(chunks.into_iter().map(String::from));
+    let tokens: Vec<(TokenKind, TokenPosition, Span)> = lexer
+        .map(|token| {
+            (
+                token.kind.clone(),
+                token.context.unwrap(),
+                token.span.unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenKind::Let, position(1, 1, 1, 3), Span::new(0, 3)),
+            (
+                TokenKind::Identifier(String::from("s")),
+                single(1, 5),
+                Span::new(4, 5)
+            ),
+            (TokenKind::Assign, single(1, 7), Span::new(6, 7)),
+            (
+                TokenKind::StringLiteral(String::from("ab\ncd")),
+                position(1, 9, 2, 3),
+                Span::new(8, 15)
+            ),
+            (TokenKind::Semicolon, single(2, 4), Span::new(15, 16)),
+            (TokenKind::Let, position(2, 6, 2, 8), Span::new(17, 20)),
+            (
+                TokenKind::Identifier(String::from("xy")),
+                position(2, 10, 2, 11),
+                Span::new(21, 23)
+            ),
+            (TokenKind::Assign, single(2, 13), Span::new(24, 25)),
+            (TokenKind::Integer(1), single(2, 15), Span::new(26, 27)),
+        ]
+    );
+}
+
+#[test]
+fn long_tokens_split_between_many_chunks() {
+    let text = "ab\nπ ".repeat(20_000);
+    let comment = "π ab ".repeat(20_000);
+    let source = format!(
+        "let s = \"{text}\";{} //{comment}\nxyz{} = 1",
+        " ".repeat(30_000),
+        "z".repeat(30_000)
+    );
+    let chunks: Vec<String> = source
+        .chars()
+        .collect::<Vec<char>>()
+        .chunks(7)
+        .map(|chunk| chunk.iter().collect())
+        .collect();
+    let describe = |mut lexer: Lexer| {
+        lexer.set_lossless(true);
+        lexer
+            .map(|token| {
+                (
+                    token.kind.clone(),
+                    token.context.unwrap(),
+                    token.span.unwrap(),
+                    token.source_text(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let tokens = describe(Lexer::from_chunks(chunks));
+    assert_eq!(tokens, describe(Lexer::from_source(&source)));
+    assert_eq!(tokens.len(), 8);
+    assert_eq!(tokens[3].0, TokenKind::StringLiteral(text.clone()));
+    assert_eq!(tokens[5].1, position(20_002, 1, 20_002, 30_003));
+    let lossless: String = tokens.iter().map(|(_, _, _, text)| text.as_str()).collect();
+    assert_eq!(lossless, source);
+}
+
+struct ByteByByte(Vec<u8>, usize);
+
+impl Read for ByteByByte {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.get(self.1) {
+            Some(byte) => {
+                buffer[0] = *byte;
+                self.1 += 1;
+                Ok(1)
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn characters_split_between_reads() {
+    let reader = ByteByByte("żółw \"π\"".as_bytes().to_vec(), 0);
+    let kinds: Vec<TokenKind> = Lexer::from_reader(reader)
+        .map(|token| token.kind.clone())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Identifier(String::from("żółw")),
+            TokenKind::StringLiteral(String::from("π"))
+        ]
+    );
+}
+
+#[test]
+fn spans_of_long_input_from_reader() {
+    let source = "abc ".repeat(50_000);
+    let reader = std::io::Cursor::new(source.clone().into_bytes());
+    let tokens: Vec<Rc<Token>> = Lexer::from_reader(reader).collect();
+    assert_eq!(tokens.len(), 50_000);
+    let last = tokens.last().unwrap();
+    assert_eq!(last.span.unwrap(), Span::new(199_996, 199_999));
+    assert_eq!(last.context.unwrap(), position(1, 199_997, 1, 199_999));
+}
+
+struct Failing;
+
+impl Read for Failing {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("broken pipe"))
+    }
+}
+
+#[test]
+fn failing_reader() {
+    let kinds: Vec<TokenKind> = Lexer::from_reader(std::io::Read::chain("x".as_bytes(), Failing))
+        .map(|token| token.kind.clone())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Identifier(String::from("x")),
+            TokenKind::Invalid(String::from("Cannot read input: broken pipe"))
+        ]
+    );
+}
+
+#[test]
+fn trivia_in_lossless_mode() {
+    let mut lexer = Lexer::from_source("  x // name\r\n\t$= 0x1\n");
+    lexer.set_lossless(true);
+    let layouts: Vec<Layout> = lexer.map(|token| *token.layout.clone().unwrap()).collect();
+    assert_eq!(
+        layouts,
+        vec![
+            Layout {
+                trivia: vec![Trivia::Whitespace(String::from("  "))],
+                text: String::from("x"),
+            },
+            Layout {
+                trivia: vec![
+                    Trivia::Whitespace(String::from(" ")),
+                    Trivia::Comment(String::from("// name\r")),
+                    Trivia::Newline(String::from("\n")),
+                    Trivia::Whitespace(String::from("\t")),
+                    Trivia::Skipped(String::from("$")),
+                ],
+                text: String::from("="),
+            },
+            Layout {
+                trivia: vec![Trivia::Whitespace(String::from(" "))],
+                text: String::from("0x1"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn comments_are_skipped() {
+    let kinds: Vec<TokenKind> = Lexer::from_source("a // b\n/ c //")
+        .map(|token| token.kind.clone())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Identifier(String::from("a")),
+            TokenKind::Slash,
+            TokenKind::Identifier(String::from("c")),
+        ]
+    );
+}
+
+#[test]
+fn declared_operators() {
+    let mut lexer = Lexer::from_source("|> <+> // c\n <+>|> a|>b <+ -> |");
+    let mut kinds = vec![];
+    lexer.expect_operator();
+    kinds.push(lexer.next().unwrap().kind.clone());
+    lexer.declare_operator("|>");
+    lexer.expect_operator();
+    kinds.push(lexer.next().unwrap().kind.clone());
+    lexer.declare_operator("<+>");
+    kinds.extend(lexer.map(|token| token.kind.clone()));
+    let operator = |text: &str| TokenKind::Operator(String::from(text));
+    assert_eq!(
+        kinds,
+        vec![
+            operator("|>"),
+            operator("<+>"),
+            operator("<+>"),
+            operator("|>"),
+            TokenKind::Identifier(String::from("a")),
+            operator("|>"),
+            TokenKind::Identifier(String::from("b")),
+            TokenKind::LessThen,
+            TokenKind::Plus,
+            TokenKind::Arrow,
+        ]
+    );
+}
+
+#[test]
+fn operator_expected_but_missing() {
+    let mut lexer = Lexer::from_source("1 // c\n x");
+    lexer.set_lossless(true);
+    lexer.next();
+    lexer.expect_operator();
+    let token = lexer.next().unwrap();
+    assert_eq!(token.kind, TokenKind::Identifier(String::from("x")));
+    assert_eq!(token.source_text(), " // c\n x");
+}
+
+#[test]
+fn peeked_token_is_scanned_once() {
+    let mut lexer = Lexer::new();
+    lexer.process("a <+");
+    let peeked = lexer.peek().unwrap();
+    assert!(Rc::ptr_eq(&peeked, &lexer.peek().unwrap()));
+    assert!(Rc::ptr_eq(&peeked, &lexer.next().unwrap()));
+    assert_eq!(lexer.peek().unwrap().kind, TokenKind::LessThen);
+    lexer.declare_operator("<+");
+    let operator = TokenKind::Operator(String::from("<+"));
+    assert_eq!(lexer.peek().unwrap().kind, operator);
+    assert_eq!(lexer.next().unwrap().kind, operator);
+    assert_eq!(lexer.peek(), None);
+    lexer.process("b");
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        TokenKind::Identifier(String::from("b"))
+    );
+}
+
+#[test]
+fn declared_operators_split_between_chunks() {
+    let chunks = vec!["a ", "|", "> b", " <", "+", "> c"];
+    let mut lexer = Lexer::from_chunks(chunks.into_iter().map(String::from));
+    lexer.declare_operator("|>");
+    lexer.declare_operator("<+>");
+    let kinds: Vec<TokenKind> = lexer.map(|token| token.kind.clone()).collect();
+    let operator = |text: &str| TokenKind::Operator(String::from(text));
+    let identifier = |text: &str| TokenKind::Identifier(String::from(text));
+    assert_eq!(
+        kinds,
+        vec![
+            identifier("a"),
+            operator("|>"),
+            identifier("b"),
+            operator("<+>"),
+            identifier("c"),
+        ]
+    );
+}
@@ -0,0 +1,93 @@
+use super::scanner::is_identifier_start;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct NumberLiteral {
+    value: Option<i64>, //None once the literal does not fit into an integer
+    radix: u32,
+    digits: usize,   //digits read after prefix of the radix
+    separated: bool, //last character was `_`
+}
+
+impl NumberLiteral {
+    pub(super) fn new(first: char) -> Self {
+        NumberLiteral {
+            value: first.to_digit(10).map(i64::from),
+            radix: 10,
+            digits: 1,
+            separated: false,
+        }
+    }
+
+    pub(super) fn next(self, character: char) -> Result<Option<NumberLiteral>, String> {
+        match character {
+            'x' | 'b' | 'o' if self.is_single_zero() => Ok(Some(NumberLiteral {
+                value: Some(0),
+                radix: match character {
+                    'x' => 16,
+                    'b' => 2,
+                    _ => 8,
+                },
+                digits: 0,
+                separated: false,
+            })),
+            '_' if self.digits == 0 || self.separated => {
+                Err(String::from("Unexpected character '_' in number"))
+            }
+            '_' => Ok(Some(NumberLiteral {
+                separated: true,
+                ..self
+            })),
+            character if character.is_digit(self.radix) => {
+                let digit = character.to_digit(self.radix).unwrap();
+                Ok(Some(NumberLiteral {
+                    value: self
+                        .value
+                        .and_then(|value| value.checked_mul(i64::from(self.radix)))
+                        .and_then(|value| value.checked_add(i64::from(digit))),
+                    digits: self.digits + 1,
+                    separated: false,
+                    ..self
+                }))
+            }
+            character if character.is_ascii_digit() => Err(format!(
+                "Digit '{}' is not allowed in {} number",
+                character,
+                radix_name(self.radix)
+            )),
+            character if is_identifier_start(character) => {
+                Err(format!("Unexpected character '{}' in number", character))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn is_single_zero(&self) -> bool {
+        self.radix == 10 && self.digits == 1 && self.value == Some(0)
+    }
+}
+
+fn radix_name(radix: u32) -> &'static str {
+    match radix {
+        16 => "hexadecimal",
+        8 => "octal",
+        2 => "binary",
+        _ => "decimal",
+    }
+}
+
+//`0x` without digits or `1_` are malformed
+pub(super) fn number_token(literal: &NumberLiteral) -> Result<i64, String> {
+    if literal.digits == 0 {
+        return Err(format!(
+            "Missing digits of {} number",
+            radix_name(literal.radix)
+        ));
+    }
+    if literal.separated {
+        return Err(String::from("Number can not end with '_'"));
+    }
+    literal.value.ok_or(format!(
+        "Number is out of range, largest allowed is {}",
+        i64::MAX
+    ))
+}
@@ -0,0 +1,506 @@
+use crate::{
+    lines::{Span, TextPosition, TokenPosition},
+    tokens::{PureTokenKind, Token, TokenKind},
+};
+
+use super::numbers::{NumberLiteral, number_token};
+
+//text is borrowed from the buffer, nothing is allocated until it becomes a `Token`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lexeme<'a> {
+    pub kind: PureTokenKind,
+    pub text: &'a str,
+    pub position: TokenPosition,
+    pub span: Span,
+    pub trivia: &'a str, //skipped source between the previous token and this one
+    value: i64,          //value of an integer
+    problem: Option<String>, //reason of an invalid token
+}
+
+impl Lexeme<'_> {
+    pub fn token_kind(&self) -> TokenKind {
+        match self.kind {
+            PureTokenKind::Invalid => TokenKind::Invalid(self.problem.clone().unwrap_or_default()),
+            PureTokenKind::Identifier => TokenKind::Identifier(self.text.to_string()),
+            PureTokenKind::StringLiteral => {
+                TokenKind::StringLiteral(self.text[1..self.text.len() - 1].to_string())
+            }
+            PureTokenKind::Symbol => TokenKind::Symbol(self.text[1..].to_string()),
+            PureTokenKind::Integer => TokenKind::Integer(self.value),
+            PureTokenKind::Operator => TokenKind::Operator(self.text.to_string()),
+            PureTokenKind::Comma => TokenKind::Comma,
+            PureTokenKind::Semicolon => TokenKind::Semicolon,
+            PureTokenKind::LeftParen => TokenKind::LeftParen,
+            PureTokenKind::RightParen => TokenKind::RightParen,
+            PureTokenKind::LeftBrace => TokenKind::LeftBrace,
+            PureTokenKind::RightBrace => TokenKind::RightBrace,
+            PureTokenKind::SetStart => TokenKind::SetStart,
+            PureTokenKind::Function => TokenKind::Function,
+            PureTokenKind::Let => TokenKind::Let,
+            PureTokenKind::Assign => TokenKind::Assign,
+            PureTokenKind::Plus => TokenKind::Plus,
+            PureTokenKind::Minus => TokenKind::Minus,
+            PureTokenKind::Equal => TokenKind::Equal,
+            PureTokenKind::Inequal => TokenKind::Inequal,
+            PureTokenKind::Negation => TokenKind::Negation,
+            PureTokenKind::LessThen => TokenKind::LessThen,
+            PureTokenKind::GreaterThen => TokenKind::GreaterThen,
+            PureTokenKind::Slash => TokenKind::Slash,
+            PureTokenKind::Asterisk => TokenKind::Asterisk,
+            PureTokenKind::True => TokenKind::True,
+            PureTokenKind::False => TokenKind::False,
+            PureTokenKind::If => TokenKind::If,
+            PureTokenKind::Else => TokenKind::Else,
+            PureTokenKind::Return => TokenKind::Return,
+            PureTokenKind::LeftBracket => TokenKind::LeftBracket,
+            PureTokenKind::RightBracket => TokenKind::RightBracket,
+            PureTokenKind::Collon => TokenKind::Collon,
+            PureTokenKind::Macro => TokenKind::Macro,
+            PureTokenKind::Yield => TokenKind::Yield,
+            PureTokenKind::At => TokenKind::At,
+            PureTokenKind::Arrow => TokenKind::Arrow,
+            PureTokenKind::EndOfInput => TokenKind::EndOfInput,
+        }
+    }
+
+    pub fn to_token(&self) -> Token {
+        Token {
+            context: Some(self.position),
+            span: Some(self.span),
+            kind: self.token_kind(),
+            layout: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Cursor {
+    pub(crate) offset: usize,
+    line: u32,
+    column: u32,      //column of the last character read, 0 at start of the line
+    ends_value: bool, //last token can end an operand, so `:` after it belongs to a map
+}
+
+impl Cursor {
+    pub(crate) fn start() -> Self {
+        Cursor {
+            line: 1,
+            ..Cursor::default()
+        }
+    }
+
+    pub(crate) fn following(&self) -> TextPosition {
+        TextPosition::new(self.line, self.column + 1)
+    }
+}
+
+pub struct Scanner<'a> {
+    source: &'a str,
+    cursor: Cursor,
+    trivia_start: usize,     //offset where skipping before current token started
+    operators: &'a [String], //declared by the script, they take precedence over other tokens
+    lookahead: usize,        //bytes after the cursor which decide how to go on
+    runs: usize,             //runs of characters read so far by `bump_while`
+    read: Vec<Run>,
+    resumed: Vec<Run>,
+}
+
+//long run of characters with its index among the runs of the token
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Run {
+    index: usize,
+    end: Cursor,
+}
+
+//shorter runs are cheap to read again
+const LONG_RUN: usize = 32;
+
+impl<'a> Scanner<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self::resume(source, Cursor::start())
+    }
+
+    pub(crate) fn resume(source: &'a str, cursor: Cursor) -> Self {
+        Scanner {
+            source,
+            cursor,
+            trivia_start: cursor.offset,
+            operators: &[],
+            lookahead: 2,
+            runs: 0,
+            read: vec![],
+            resumed: vec![],
+        }
+    }
+
+    pub(crate) fn with_operators(self, operators: &'a [String]) -> Self {
+        let longest = operators.iter().map(String::len).max().unwrap_or(0);
+        Scanner {
+            operators,
+            lookahead: self.lookahead.max(longest),
+            ..self
+        }
+    }
+
+    //kept runs started more than the lookahead before the old end, all before them scans the same
+    pub(crate) fn with_read_runs(self, resumed: Vec<Run>) -> Self {
+        Scanner { resumed, ..self }
+    }
+
+    pub(crate) fn lookahead(&self) -> usize {
+        self.lookahead
+    }
+
+    pub(crate) fn into_read_runs(self) -> Vec<Run> {
+        self.read
+    }
+
+    pub(crate) fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
+    fn peek(&self) -> Option<char> {
+        let byte = *self.source.as_bytes().get(self.cursor.offset)?;
+        if byte.is_ascii() {
+            return Some(byte as char);
+        }
+        self.source[self.cursor.offset..].chars().next()
+    }
+
+    fn bump(&mut self) -> char {
+        let character = self.peek().expect("Nothing left to read");
+        self.advance(character);
+        character
+    }
+
+    fn advance(&mut self, character: char) {
+        self.cursor.offset += character.len_utf8();
+        if character == '\n' {
+            self.cursor.line += 1;
+            self.cursor.column = 0;
+        } else {
+            self.cursor.column += 1;
+        }
+    }
+
+    fn bump_while(&mut self, condition: impl Fn(char) -> bool) {
+        let index = self.runs;
+        self.runs += 1;
+        let start = self.cursor.offset;
+        if let Ok(found) = self.resumed.binary_search_by_key(&index, |run| run.index) {
+            self.cursor = Cursor {
+                ends_value: self.cursor.ends_value,
+                ..self.resumed[found].end
+            };
+        }
+        while let Some(character) = self.peek()
+            && condition(character)
+        {
+            self.advance(character);
+        }
+        if self.cursor.offset - start > LONG_RUN && start + self.lookahead < self.source.len() {
+            self.read.push(Run {
+                index,
+                end: self.cursor,
+            });
+        }
+    }
+
+    fn here(&self) -> TextPosition {
+        TextPosition::new(self.cursor.line, self.cursor.column.max(1))
+    }
+
+    fn skip_ignored(&mut self) {
+        self.trivia_start = self.cursor.offset;
+        loop {
+            self.bump_while(char::is_whitespace);
+            if self.source[self.cursor.offset..].starts_with("//") {
+                self.bump_while(|character| character != '\n');
+                continue;
+            }
+            match self.peek() {
+                Some(character)
+                    if !starts_token(character) && self.declared_operator().is_none() =>
+                {
+                    self.advance(character)
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn lexeme(
+        &self,
+        kind: PureTokenKind,
+        offset: usize,
+        start: TextPosition,
+        problem: Option<String>,
+    ) -> Lexeme<'a> {
+        Lexeme {
+            kind,
+            text: &self.source[offset..self.cursor.offset],
+            position: TokenPosition::new(start, self.here()),
+            span: Span::new(offset, self.cursor.offset),
+            trivia: &self.source[self.trivia_start..offset],
+            value: 0,
+            problem,
+        }
+    }
+
+    fn scan(&mut self) -> Option<Lexeme<'a>> {
+        self.skip_ignored();
+        let offset = self.cursor.offset;
+        if let Some(operator) = self.declared_operator() {
+            let start = self.cursor.following();
+            operator
+                .chars()
+                .for_each(|character| self.advance(character));
+            return Some(self.lexeme(PureTokenKind::Operator, offset, start, None));
+        }
+        let character = self.bump_first()?;
+        let start = self.here();
+        let simple = |scanner: &mut Self, kind| Some(scanner.lexeme(kind, offset, start, None));
+        match character {
+            '=' => match self.peek() {
+                Some('=') => self.pair(PureTokenKind::Equal, offset, start),
+                _ => simple(self, PureTokenKind::Assign),
+            },
+            '!' => match self.peek() {
+                Some('=') => self.pair(PureTokenKind::Inequal, offset, start),
+                Some(_) => simple(self, PureTokenKind::Negation),
+                None => self.invalid(offset, start, "Single ! at end is not valid"),
+            },
+            '#' => match self.peek() {
+                Some('{') => self.pair(PureTokenKind::SetStart, offset, start),
+                Some(_) => self.invalid(offset, start, "Expected { after #"),
+                None => self.invalid(offset, start, "Single # at end is not valid"),
+            },
+            '-' => match self.peek() {
+                Some('>') => self.pair(PureTokenKind::Arrow, offset, start),
+                _ => simple(self, PureTokenKind::Minus),
+            },
+            '"' => {
+                self.bump_while(|character| character != '"');
+                match self.peek() {
+                    Some(_) => {
+                        self.bump();
+                        simple(self, PureTokenKind::StringLiteral)
+                    }
+                    None => self.invalid(offset, start, "Unclosed string literal"),
+                }
+            }
+            ':' if !self.cursor.ends_value && self.peek().is_some_and(is_identifier_start) => {
+                Some(self.symbol(offset, start))
+            }
+            character if character.is_ascii_digit() => Some(self.number(character, offset, start)),
+            character if is_identifier_start(character) => {
+                self.bump_while(is_identifier_part);
+                let text = &self.source[offset..self.cursor.offset];
+                simple(self, keyword(text).unwrap_or(PureTokenKind::Identifier))
+            }
+            character => simple(self, special_character(character).expect("Skipped above")),
+        }
+    }
+
+    fn declared_operator(&self) -> Option<&'a str> {
+        let rest = &self.source[self.cursor.offset..];
+        self.operators
+            .iter()
+            .filter(|operator| rest.starts_with(operator.as_str()))
+            .max_by_key(|operator| operator.len())
+            .map(String::as_str)
+    }
+
+    //any run of operator characters, even of those which are tokens on their own
+    pub(crate) fn operator(&mut self) -> Option<Lexeme<'a>> {
+        let resumed = self.cursor;
+        self.trivia_start = self.cursor.offset;
+        loop {
+            self.bump_while(char::is_whitespace);
+            if !self.source[self.cursor.offset..].starts_with("//") {
+                break;
+            }
+            self.bump_while(|character| character != '\n');
+        }
+        let offset = self.cursor.offset;
+        if !self.peek().is_some_and(is_operator_character) {
+            self.cursor = resumed;
+            return self.next();
+        }
+        let start = self.cursor.following();
+        while let Some(character) = self.peek()
+            && is_operator_character(character)
+            && !self.source[self.cursor.offset..].starts_with("//")
+        {
+            self.advance(character);
+        }
+        self.cursor.ends_value = false;
+        Some(self.lexeme(PureTokenKind::Operator, offset, start, None))
+    }
+
+    fn bump_first(&mut self) -> Option<char> {
+        self.peek()?;
+        Some(self.bump())
+    }
+
+    fn pair(
+        &mut self,
+        kind: PureTokenKind,
+        offset: usize,
+        start: TextPosition,
+    ) -> Option<Lexeme<'a>> {
+        self.bump();
+        Some(self.lexeme(kind, offset, start, None))
+    }
+
+    fn invalid(&self, offset: usize, start: TextPosition, problem: &str) -> Option<Lexeme<'a>> {
+        Some(self.lexeme(
+            PureTokenKind::Invalid,
+            offset,
+            start,
+            Some(problem.to_string()),
+        ))
+    }
+
+    //starts a symbol only after a non-operand, so `{a:b}` stays a map and `{:a: :b}` maps symbols
+    fn symbol(&mut self, offset: usize, start: TextPosition) -> Lexeme<'a> {
+        let colon = self.cursor;
+        self.bump_while(is_identifier_part);
+        if keyword(&self.source[colon.offset..self.cursor.offset]).is_some() {
+            self.cursor = colon;
+            return self.lexeme(PureTokenKind::Collon, offset, start, None);
+        }
+        self.lexeme(PureTokenKind::Symbol, offset, start, None)
+    }
+
+    fn number(&mut self, first: char, offset: usize, start: TextPosition) -> Lexeme<'a> {
+        let mut literal = NumberLiteral::new(first);
+        while let Some(character) = self.peek() {
+            match literal.next(character) {
+                Ok(Some(next)) => literal = next,
+                Ok(None) => break,
+                Err(problem) => {
+                    self.bump();
+                    self.bump_while(|character| !is_separator(character));
+                    return self.lexeme(PureTokenKind::Invalid, offset, start, Some(problem));
+                }
+            }
+            self.bump();
+        }
+        match number_token(&literal) {
+            Ok(value) => Lexeme {
+                value,
+                ..self.lexeme(PureTokenKind::Integer, offset, start, None)
+            },
+            Err(problem) => self.lexeme(PureTokenKind::Invalid, offset, start, Some(problem)),
+        }
+    }
+}
+
+impl<'a> Iterator for Scanner<'a> {
+    type Item = Lexeme<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lexeme = self.scan()?;
+        self.cursor.ends_value = matches!(
+            lexeme.kind,
+            PureTokenKind::Identifier
+                | PureTokenKind::Integer
+                | PureTokenKind::StringLiteral
+                | PureTokenKind::Symbol
+                | PureTokenKind::True
+                | PureTokenKind::False
+                | PureTokenKind::RightParen
+                | PureTokenKind::RightBracket
+                | PureTokenKind::RightBrace
+        );
+        Some(lexeme)
+    }
+}
+
+fn starts_token(character: char) -> bool {
+    matches!(character, '=' | '!' | '#' | '-' | '"')
+        || character.is_ascii_digit()
+        || is_identifier_start(character)
+        || special_character(character).is_some()
+}
+
+fn special_character(character: char) -> Option<PureTokenKind> {
+    match character {
+        '+' => Some(PureTokenKind::Plus),
+        '(' => Some(PureTokenKind::LeftParen),
+        ')' => Some(PureTokenKind::RightParen),
+        '{' => Some(PureTokenKind::LeftBrace),
+        '}' => Some(PureTokenKind::RightBrace),
+        ',' => Some(PureTokenKind::Comma),
+        ';' => Some(PureTokenKind::Semicolon),
+        '*' => Some(PureTokenKind::Asterisk),
+        '<' => Some(PureTokenKind::LessThen),
+        '>' => Some(PureTokenKind::GreaterThen),
+        '/' => Some(PureTokenKind::Slash),
+        ']' => Some(PureTokenKind::RightBracket),
+        '[' => Some(PureTokenKind::LeftBracket),
+        ':' => Some(PureTokenKind::Collon),
+        '@' => Some(PureTokenKind::At),
+        _ => None,
+    }
+}
+
+fn is_operator_character(character: char) -> bool {
+    matches!(
+        character,
+        '+' | '-' | '*' | '/' | '<' | '>' | '=' | '!' | '%' | '&' | '|' | '^' | '~' | '?' | '.'
+    )
+}
+
+pub(crate) fn is_builtin_operator(operator: &str) -> bool {
+    matches!(
+        operator,
+        "=" | "==" | "!=" | "!" | "+" | "-" | "*" | "/" | "<" | ">" | "->"
+    )
+}
+
+fn keyword(text: &str) -> Option<PureTokenKind> {
+    match text {
+        "true" => Some(PureTokenKind::True),
+        "false" => Some(PureTokenKind::False),
+        "let" => Some(PureTokenKind::Let),
+        "if" => Some(PureTokenKind::If),
+        "else" => Some(PureTokenKind::Else),
+        "return" => Some(PureTokenKind::Return),
+        "fn" => Some(PureTokenKind::Function),
+        "macro" => Some(PureTokenKind::Macro),
+        "yield" => Some(PureTokenKind::Yield),
+        _ => None,
+    }
+}
+
+fn is_separator(character: char) -> bool {
+    character == ';' || character == ',' || character.is_whitespace()
+}
+
+//punctuation outside of ASCII is skipped as any other character starting no token
+pub(super) fn is_identifier_start(character: char) -> bool {
+    if character.is_ascii() {
+        return character.is_ascii_alphabetic() || character == '_';
+    }
+    character.is_alphabetic()
+}
+
+fn is_identifier_part(character: char) -> bool {
+    if character.is_ascii() {
+        return character.is_ascii_alphanumeric() || character == '_';
+    }
+    character.is_alphanumeric() || is_combining_mark(character)
+}
+
+fn is_combining_mark(character: char) -> bool {
+    matches!(
+        character,
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+    )
+}
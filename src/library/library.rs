@@ -1,7 +1,9 @@
 mod allocator;
 mod ast;
+mod checker;
 mod code;
 mod evaluator;
+mod formatter;
 mod lexers;
 mod lines;
 mod object;
@@ -17,6 +19,19 @@ macro_rules! print_bash_error {
     };
 }
 
+pub use ast::concrete::{SyntaxElement, SyntaxKind, SyntaxNode};
+pub use ast::{
+    Fold, Foldable, Visitor, VisitorMut,
+    export::{ExportFormat, ImportError, export_source, from_json, to_json, to_sexp},
+    expression::Expression,
+    fold,
+    statements::{Program, Statement},
+    visit, visit_mut,
+};
+pub use checker::{Analysis, Type, check_reader, check_source, infer};
+pub use formatter::{FormatOptions, TrailingCommas, format_source, print_program};
+pub use lexers::{Lexeme, Lexer, Scanner};
+pub use parser::{ParseError, parse_lossless};
 pub use repl::start;
 
 pub fn bar() {
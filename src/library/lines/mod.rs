@@ -1,36 +1,36 @@
 use std::{env, fmt::Display, ops::Add};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct LineNumber(pub u16);
+pub struct LineNumber(pub u32);
 
 impl LineNumber {}
-impl Add<u16> for LineNumber {
+impl Add<u32> for LineNumber {
     type Output = LineNumber;
 
-    fn add(self, rhs: u16) -> Self::Output {
+    fn add(self, rhs: u32) -> Self::Output {
         Self(self.0 + rhs)
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct ColumnNumber(pub u16);
+pub struct ColumnNumber(pub u32);
 
-impl Add<u16> for ColumnNumber {
+impl Add<u32> for ColumnNumber {
     type Output = ColumnNumber;
 
-    fn add(self, rhs: u16) -> Self::Output {
+    fn add(self, rhs: u32) -> Self::Output {
         Self(self.0 + rhs)
     }
 }
 
 impl From<usize> for LineNumber {
     fn from(val: usize) -> Self {
-        LineNumber(val as u16)
+        LineNumber(val as u32)
     }
 }
 impl From<usize> for ColumnNumber {
     fn from(val: usize) -> Self {
-        ColumnNumber(val as u16)
+        ColumnNumber(val as u32)
     }
 }
 
@@ -41,14 +41,14 @@ pub struct TextPosition {
 }
 
 impl TextPosition {
-    pub fn new(line_number: u16, column_number: u16) -> Self {
+    pub fn new(line_number: u32, column_number: u32) -> Self {
         Self {
             line_number: LineNumber(line_number),
             column_number: ColumnNumber(column_number),
         }
     }
 
-    pub fn token_ends_with(&self, line_number: u16, column_number: u16) -> TokenPosition {
+    pub fn token_ends_with(&self, line_number: u32, column_number: u32) -> TokenPosition {
         TokenPosition::new(*self, TextPosition::new(line_number, column_number))
     }
 }
@@ -106,3 +106,62 @@ impl Display for TokenPosition {
         }
     }
 }
+
+//bytes of the source, `end` is not included
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn shifted(&self, by: usize) -> Self {
+        Self::new(self.start + by, self.end + by)
+    }
+
+    pub fn text<'a>(&self, source: &'a str) -> Option<&'a str> {
+        source.get(self.start..self.end)
+    }
+}
+
+//nodes made by macros take the span of the `unquote` they replaced
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub struct SourceSpan {
+    pub position: Option<TokenPosition>,
+    pub bytes: Option<Span>,
+}
+
+impl SourceSpan {
+    pub fn new(position: Option<TokenPosition>, bytes: Option<Span>) -> Self {
+        Self { position, bytes }
+    }
+
+    pub fn to(&self, end: SourceSpan) -> Self {
+        let position = match (self.position, end.position) {
+            (Some(start), Some(end)) => Some(TokenPosition::new(start.start, end.end)),
+            (start, end) => start.or(end),
+        };
+        let bytes = match (self.bytes, end.bytes) {
+            (Some(start), Some(end)) => Some(Span::new(start.start, end.end)),
+            (start, end) => start.or(end),
+        };
+        Self { position, bytes }
+    }
+
+    pub fn text<'a>(&self, source: &'a str) -> Option<&'a str> {
+        self.bytes?.text(source)
+    }
+}
+
+impl Display for SourceSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "{}", position),
+            None => write!(f, "(unknown)"),
+        }
+    }
+}
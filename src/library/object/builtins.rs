@@ -1,6 +1,6 @@
 use std::{fmt::Display, rc::Rc};
 
-use crate::object::{Object, int_value};
+use crate::object::{HashValue, Object, boolean_value, hash, int_value};
 
 macro_rules! end_flow {
     ($value:expr ) => {
@@ -30,6 +30,24 @@ macro_rules! expecting_array {
     };
 }
 
+macro_rules! expecting_set {
+    ($left:ident,  $function_name:expr, $argument_no:expr ) => {
+        (match $left.as_ref() {
+            super::Object::Set(set) => Ok(set),
+            _ => {
+                let error_message = format!(
+                    "Invalid argument {} for {}: {}({}) expected Set",
+                    $argument_no,
+                    $function_name,
+                    super::type_of($left),
+                    $left.to_string(),
+                );
+                Err(BuiltInResult::Failure(error_message))
+            }
+        })
+    };
+}
+
 macro_rules! value {
     ($value:expr  ) => {
         BuiltInResult::Value(Rc::new($value))
@@ -46,6 +64,15 @@ pub enum BuiltInFunction {
     Push,
     Puts,
     Quote,
+    Next,
+    Done,
+    Take,
+    Chars,
+    Union,
+    Intersection,
+    Difference,
+    Contains,
+    Str,
 }
 
 pub enum BuiltInResult {
@@ -54,13 +81,22 @@ pub enum BuiltInResult {
     Failure(String),
 }
 
-const BUILTINS_DATA: [BuiltInFunction; 6] = [
+const BUILTINS_DATA: [BuiltInFunction; 15] = [
     BuiltInFunction::Len,
     BuiltInFunction::First,
     BuiltInFunction::Last,
     BuiltInFunction::Rest,
     BuiltInFunction::Push,
     BuiltInFunction::Puts,
+    BuiltInFunction::Next,
+    BuiltInFunction::Done,
+    BuiltInFunction::Take,
+    BuiltInFunction::Chars,
+    BuiltInFunction::Union,
+    BuiltInFunction::Intersection,
+    BuiltInFunction::Difference,
+    BuiltInFunction::Contains,
+    BuiltInFunction::Str,
 ];
 impl BuiltInFunction {
     //TODO: replace to return Result either object or error ready structure -> function accepting
@@ -74,6 +110,23 @@ impl BuiltInFunction {
             BuiltInFunction::Push => apply_push(arguments),
             BuiltInFunction::Puts => apply_puts(arguments),
             BuiltInFunction::Quote => apply_quote(arguments),
+            BuiltInFunction::Next => invalid_generator_call("next", 1, arguments),
+            BuiltInFunction::Done => invalid_generator_call("done", 1, arguments),
+            BuiltInFunction::Take => invalid_generator_call("take", 2, arguments),
+            BuiltInFunction::Chars => apply_chars(arguments),
+            BuiltInFunction::Union => apply_set_operation("union", arguments, |_, _| true),
+            BuiltInFunction::Intersection => {
+                apply_set_operation("intersection", arguments, |in_left, in_right| {
+                    in_left && in_right
+                })
+            }
+            BuiltInFunction::Difference => {
+                apply_set_operation("difference", arguments, |in_left, in_right| {
+                    in_left && !in_right
+                })
+            }
+            BuiltInFunction::Contains => apply_contains(arguments),
+            BuiltInFunction::Str => apply_str(arguments),
         }
     }
     pub fn index(&self) -> u8 {
@@ -101,6 +154,15 @@ impl BuiltInFunction {
             BuiltInFunction::Rest,
             BuiltInFunction::Push,
             BuiltInFunction::Puts,
+            BuiltInFunction::Next,
+            BuiltInFunction::Done,
+            BuiltInFunction::Take,
+            BuiltInFunction::Chars,
+            BuiltInFunction::Union,
+            BuiltInFunction::Intersection,
+            BuiltInFunction::Difference,
+            BuiltInFunction::Contains,
+            BuiltInFunction::Str,
         ]
     }
 }
@@ -110,6 +172,28 @@ fn apply_quote(arguments: &[std::rc::Rc<super::Object>]) -> BuiltInResult {
     todo!()
 }
 
+//evaluator and vm handle valid calls before `apply`, only wrong arguments are left
+fn invalid_generator_call(name: &str, expected: usize, arguments: &[Rc<Object>]) -> BuiltInResult {
+    end_flow!(accept_n_arguments(name, expected, arguments));
+    let generator = &arguments[0];
+    if !matches!(generator.as_ref(), Object::Generator(_)) {
+        return BuiltInResult::Failure(format!(
+            "Invalid argument 1 for {}: {}({}) expected Generator",
+            name,
+            super::type_of(generator),
+            generator
+        ));
+    }
+    let count = &arguments[expected - 1];
+    BuiltInResult::Failure(format!(
+        "Invalid argument {} for {}: {}({}) expected Int",
+        expected,
+        name,
+        super::type_of(count),
+        count
+    ))
+}
+
 fn apply_puts(arguments: &[std::rc::Rc<super::Object>]) -> BuiltInResult {
     arguments.into_iter().for_each(|arg| {
         println!(">> {}", arg.to_string());
@@ -130,6 +214,9 @@ fn apply_push(arguments: &[std::rc::Rc<super::Object>]) -> BuiltInResult {
 fn apply_rest(arguments: &[std::rc::Rc<super::Object>]) -> BuiltInResult {
     end_flow!(accept_n_arguments("rest", 1, arguments));
     let argument = &arguments[0];
+    if let Object::String(text) = argument.as_ref() {
+        return value!(Object::String(text.chars().skip(1).collect()));
+    }
     let value = end_flow!(expecting_array!(argument, "rest", 1));
     if value.is_empty() {
         return value!(Object::Array { elements: vec![] });
@@ -149,6 +236,9 @@ fn apply_rest(arguments: &[std::rc::Rc<super::Object>]) -> BuiltInResult {
 fn apply_last(arguments: &[Rc<Object>]) -> BuiltInResult {
     end_flow!(accept_n_arguments("last", 1, arguments));
     let argument = &arguments[0];
+    if let Object::String(text) = argument.as_ref() {
+        return string_char(text.chars().last(), "last");
+    }
     let value = end_flow!(expecting_array!(argument, "last", 1));
     value
         .into_iter()
@@ -163,6 +253,9 @@ fn apply_last(arguments: &[Rc<Object>]) -> BuiltInResult {
 fn apply_first(arguments: &[Rc<Object>]) -> BuiltInResult {
     end_flow!(accept_n_arguments("first", 1, arguments));
     let argument = &arguments[0];
+    if let Object::String(text) = argument.as_ref() {
+        return string_char(text.chars().next(), "first");
+    }
     let value = end_flow!(expecting_array!(argument, "first", 1));
     value
         .get(0)
@@ -173,22 +266,78 @@ fn apply_first(arguments: &[Rc<Object>]) -> BuiltInResult {
         })
 }
 
+fn string_char(found: Option<char>, name: &str) -> BuiltInResult {
+    found.map(|c| value!(Object::Char(c))).unwrap_or_else(|| {
+        BuiltInResult::Failure(format!("Cannot get {name} character for empty string"))
+    })
+}
+
+fn apply_chars(arguments: &[Rc<Object>]) -> BuiltInResult {
+    end_flow!(accept_n_arguments("chars", 1, arguments));
+    let argument = &arguments[0];
+    match argument.as_ref() {
+        Object::String(text) => value!(Object::Array {
+            elements: text.chars().map(|c| Rc::new(Object::Char(c))).collect()
+        }),
+        _ => BuiltInResult::Failure(format!(
+            "Invalid argument 1 for chars: {}({}) expected String",
+            super::type_of(argument),
+            argument
+        )),
+    }
+}
+
+fn apply_set_operation<F>(name: &str, arguments: &[Rc<Object>], keep: F) -> BuiltInResult
+where
+    F: Fn(bool, bool) -> bool,
+{
+    end_flow!(accept_n_arguments(name, 2, arguments));
+    let left_argument = &arguments[0];
+    let left = end_flow!(expecting_set!(left_argument, name, 1));
+    let right_argument = &arguments[1];
+    let right = end_flow!(expecting_set!(right_argument, name, 2));
+    let result: std::collections::HashMap<HashValue, Rc<Object>> = left
+        .iter()
+        .chain(right.iter())
+        .filter(|(key, _)| keep(left.contains_key(key), right.contains_key(key)))
+        .map(|(key, value)| (*key, value.clone()))
+        .collect();
+    value!(Object::Set(result))
+}
+
+fn apply_contains(arguments: &[Rc<Object>]) -> BuiltInResult {
+    end_flow!(accept_n_arguments("contains", 2, arguments));
+    let argument = &arguments[0];
+    let set = end_flow!(expecting_set!(argument, "contains", 1));
+    BuiltInResult::Value(boolean_value(set.contains_key(&hash(&arguments[1]))))
+}
+
+//`__str__` overrides are applied by evaluator or vm before the call, as for `puts`
+fn apply_str(arguments: &[Rc<Object>]) -> BuiltInResult {
+    end_flow!(accept_n_arguments("str", 1, arguments));
+    value!(Object::String(arguments[0].to_string()))
+}
+
 fn apply_len(arguments: &[Rc<Object>]) -> BuiltInResult {
     end_flow!(accept_n_arguments("len", 1, arguments));
     let argument = &arguments[0];
     match argument.as_ref() {
-        super::Object::Array { elements } => BuiltInResult::Value(int_value(elements.len() as i64)),
-        super::Object::String(s) => BuiltInResult::Value(int_value(s.len() as i64)),
+        super::Object::Array { elements } | super::Object::Tuple { elements } => {
+            BuiltInResult::Value(int_value(elements.len() as i64))
+        }
+        super::Object::Set(set) => BuiltInResult::Value(int_value(set.len() as i64)),
+        //counted in Unicode scalar values, not in bytes of UTF-8
+        super::Object::String(s) => BuiltInResult::Value(int_value(s.chars().count() as i64)),
         super::Object::Int(_) | super::Object::Boolean(_) | super::Object::Null => {
             return BuiltInResult::Failure(format!(
-                "Invalid argument for len: {}({}) expected Array or String",
+                "Invalid argument for len: {}({}) expected Array, Tuple, Set or String",
                 super::type_of(argument),
                 argument.to_string()
             ));
         }
         _ => {
             return BuiltInResult::Failure(format!(
-                "Invalid argument for len: {}({}) expected Array or String",
+                "Invalid argument for len: {}({}) expected Array, Tuple, Set or String",
                 super::type_of(argument),
                 argument.to_string()
             ));
@@ -222,6 +371,15 @@ pub fn parse_built_in_function(function_name: &str) -> Option<BuiltInFunction> {
         "push" => Some(BuiltInFunction::Push),
         "puts" => Some(BuiltInFunction::Puts),
         "quote" => Some(BuiltInFunction::Quote),
+        "next" => Some(BuiltInFunction::Next),
+        "done" => Some(BuiltInFunction::Done),
+        "take" => Some(BuiltInFunction::Take),
+        "chars" => Some(BuiltInFunction::Chars),
+        "union" => Some(BuiltInFunction::Union),
+        "intersection" => Some(BuiltInFunction::Intersection),
+        "difference" => Some(BuiltInFunction::Difference),
+        "contains" => Some(BuiltInFunction::Contains),
+        "str" => Some(BuiltInFunction::Str),
         _ => None,
     }
 }
@@ -236,6 +394,15 @@ impl Display for BuiltInFunction {
             BuiltInFunction::Push => write!(f, "push"),
             BuiltInFunction::Puts => write!(f, "puts"),
             BuiltInFunction::Quote => write!(f, "quote"),
+            BuiltInFunction::Next => write!(f, "next"),
+            BuiltInFunction::Done => write!(f, "done"),
+            BuiltInFunction::Take => write!(f, "take"),
+            BuiltInFunction::Chars => write!(f, "chars"),
+            BuiltInFunction::Union => write!(f, "union"),
+            BuiltInFunction::Intersection => write!(f, "intersection"),
+            BuiltInFunction::Difference => write!(f, "difference"),
+            BuiltInFunction::Contains => write!(f, "contains"),
+            BuiltInFunction::Str => write!(f, "str"),
         }
     }
 }
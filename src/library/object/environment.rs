@@ -6,6 +6,7 @@ use super::Object;
 pub struct Environment {
     variables: std::collections::HashMap<String, Rc<Object>>,
     outer: Option<Rc<RefCell<Environment>>>,
+    strict: bool, //operators refuse to coerce values of different types
 }
 
 impl Environment {
@@ -13,19 +14,42 @@ impl Environment {
         Environment {
             variables: std::collections::HashMap::new(),
             outer: None,
+            strict: false,
         }
     }
     pub fn enclosed(outer: Rc<RefCell<Environment>>) -> Self {
+        let strict = outer.borrow().strict;
         Environment {
             variables: std::collections::HashMap::new(),
             outer: Some(outer.clone()),
+            strict,
         }
     }
 
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
     pub fn set(&mut self, name: String, value: Rc<Object>) {
         self.variables.insert(name, value);
     }
 
+    //changes the variable in the scope which defined it, so closures sharing it see the value
+    pub fn assign(&mut self, name: &str, value: Rc<Object>) -> bool {
+        if let Some(existing) = self.variables.get_mut(name) {
+            *existing = value;
+            return true;
+        }
+        match &self.outer {
+            Some(outer_env) => outer_env.borrow_mut().assign(name, value),
+            None => false,
+        }
+    }
+
     pub fn get(&self, name: &str) -> Option<Rc<Object>> {
         if let Some(value) = self.variables.get(name) {
             return Some(value.clone());
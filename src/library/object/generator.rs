@@ -0,0 +1,52 @@
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
+use crate::ast::{expression::Expression, statements::Statement};
+
+use super::{CompiledFunctionEntry, Environment, Object, Upvalue};
+
+pub type Generator = Rc<RefCell<GeneratorState>>;
+
+//always resumed by the backend which created it
+pub enum GeneratorState {
+    Tree(SuspendedTree),
+    Frame(SuspendedFrame),
+    Running,
+    Done,
+}
+
+impl Debug for GeneratorState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneratorState::Tree(tree) => write!(f, "Tree(at {})", tree.position),
+            GeneratorState::Frame(frame) => write!(f, "Frame(at {})", frame.instruction_pointer),
+            GeneratorState::Running => write!(f, "Running"),
+            GeneratorState::Done => write!(f, "Done"),
+        }
+    }
+}
+
+//body flattened into steps, so evaluation can stop after any `yield` without the Rust stack
+pub struct SuspendedTree {
+    pub steps: Rc<Vec<GeneratorStep>>,
+    pub position: usize,
+    pub scopes: Vec<Rc<RefCell<Environment>>>,
+    pub delegate: Option<Generator>, //generator of `yield*` which still has values
+}
+
+pub enum GeneratorStep {
+    Run(Statement),
+    Yield(Expression),
+    Delegate(Expression),
+    JumpUnless(Expression, usize),
+    Jump(usize),
+    EnterBlock(Vec<Statement>), //function declarations of the block
+    LeaveBlock,
+}
+
+pub struct SuspendedFrame {
+    pub function: CompiledFunctionEntry,
+    pub free: Vec<Upvalue>,
+    pub instruction_pointer: usize,
+    pub stack: Vec<Object>,           //locals and values above them
+    pub cells: Vec<(Upvalue, usize)>, //captured locals, closed until the frame is back
+}
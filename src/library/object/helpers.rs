@@ -23,6 +23,15 @@ pub fn int_value(int: i64) -> Rc<Object> {
     Rc::new(Object::Int(int))
 }
 
+//indexed by Unicode scalar values from 1 like arrays, negative index counts from the end
+pub fn char_at(text: &str, index: i64) -> Option<char> {
+    match index {
+        0 => None,
+        index if index < 0 => text.chars().rev().nth(index.unsigned_abs() as usize - 1),
+        index => text.chars().nth((index - 1) as usize),
+    }
+}
+
 pub fn false_value() -> Rc<Object> {
     Rc::new(FALSE)
 }
@@ -6,13 +6,19 @@ use crate::code::Instructions;
 use crate::{ast::statements::Statement, join_collection, tokens::Token};
 mod builtins;
 mod environment;
+mod generator;
 mod helpers;
 mod object_pool;
+mod operators;
+mod symbols;
 pub use builtins::BuiltInFunction;
 pub use builtins::BuiltInResult;
 pub use builtins::parse_built_in_function;
 pub use environment::{Environment, new_environment};
+pub use generator::*;
 pub use helpers::*;
+pub use operators::{display_method, find_overload, strict_violation};
+pub use symbols::{intern, symbol_name};
 #[cfg(test)]
 mod testing;
 
@@ -20,6 +26,8 @@ mod testing;
 pub enum Object {
     Int(i64),
     String(String),
+    Char(char),  //single Unicode scalar value of a string
+    Symbol(u32), //index of interned name
     Boolean(bool),
     ReturnValue(Rc<Object>),
     Error {
@@ -32,6 +40,7 @@ pub enum Object {
         parameters: Vec<Identifier>,
         body: Rc<Statement>,
         env: Rc<RefCell<Environment>>,
+        generator: bool,
     },
     Macro {
         parameters: Vec<Identifier>,
@@ -42,21 +51,50 @@ pub enum Object {
     Array {
         elements: Vec<Rc<Object>>,
     },
+    Tuple {
+        elements: Vec<Rc<Object>>,
+    },
     //TODO: Implement collision mechanics, probably using a linked list
     HashMap(std::collections::HashMap<HashValue, Rc<HashEntry>>),
+    Set(std::collections::HashMap<HashValue, Rc<Object>>),
     Quote(Rc<Expression>),
     CompiledFunction(CompiledFunctionEntry),
     Closure {
         function: CompiledFunctionEntry,
-        free: Vec<Object>,
+        free: Vec<Upvalue>,
     },
+    Generator(Generator),
+}
+
+//points at the stack slot while the frame lives, the value moves into the cell when it ends
+#[derive(Clone)]
+pub enum UpvalueCell {
+    Open(usize),
+    Closed(Object),
 }
 
+//closures capture each other, printing only the type avoids looping forever
+impl std::fmt::Debug for UpvalueCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpvalueCell::Open(index) => write!(f, "Open({index})"),
+            UpvalueCell::Closed(object @ Object::Closure { .. }) => {
+                write!(f, "Closed({})", type_of(object))
+            }
+            UpvalueCell::Closed(object) => write!(f, "Closed({object:?})"),
+        }
+    }
+}
+
+pub type Upvalue = Rc<RefCell<UpvalueCell>>;
+
 #[derive(Debug, Clone)]
 pub struct CompiledFunctionEntry {
     pub instructions: Instructions,
     pub number_of_locals: usize,
     pub number_of_parameters: usize,
+    pub generator: bool,
+    pub positions: Vec<(usize, (usize, usize))>, //line and column of failing instructions
 }
 
 impl PartialEq for Object {
@@ -64,6 +102,12 @@ impl PartialEq for Object {
         match (self, other) {
             (Self::Int(l0), Self::Int(r0)) => l0 == r0,
             (Self::String(l0), Self::String(r0)) => l0 == r0,
+            (Self::Char(l0), Self::Char(r0)) => l0 == r0,
+            (Self::Symbol(l0), Self::Symbol(r0)) => l0 == r0,
+            (Self::Tuple { elements: l0 }, Self::Tuple { elements: r0 }) => l0 == r0,
+            (Self::Set(l0), Self::Set(r0)) => {
+                l0.len() == r0.len() && l0.keys().all(|key| r0.contains_key(key))
+            }
             (Self::Boolean(l0), Self::Boolean(r0)) => l0 == r0,
             (Self::ReturnValue(l0), Self::ReturnValue(r0)) => l0 == r0,
             (
@@ -79,6 +123,7 @@ impl PartialEq for Object {
                 },
             ) => l_message == r_message && l_line == r_line && l_column == r_column,
             (Self::Function { .. }, Self::Function { .. }) => false,
+            (Self::Generator(l0), Self::Generator(r0)) => Rc::ptr_eq(l0, r0),
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
@@ -108,15 +153,18 @@ pub fn type_of(object: &Object) -> String {
     match object {
         Object::Int(_) => "Int".to_string(),
         Object::String(_) => "String".to_string(),
+        Object::Char(_) => "Char".to_string(),
+        Object::Symbol(_) => "Symbol".to_string(),
         Object::Boolean(_) => "Boolean".to_string(),
         Object::ReturnValue(_) => "ReturnValue".to_string(),
         Object::Error { .. } => "Error".to_string(),
         Object::Null => "Null".to_string(),
         Object::Function {
             parameters,
-            body: _,
-            env: _,
+            generator: false,
+            ..
         } => join_collection!(parameters, ", "),
+        Object::Function { parameters, .. } => format!("*{}", join_collection!(parameters, ", ")),
         Object::Macro {
             parameters,
             body: _,
@@ -126,12 +174,15 @@ pub fn type_of(object: &Object) -> String {
             "BuiltInFunction: ".to_string() + &built_in_function.to_string()
         }
         Object::Array { .. } => "Array".to_string(),
+        Object::Tuple { .. } => "Tuple".to_string(),
         Object::HashMap(_) => "HashMap".to_string(),
+        Object::Set(_) => "Set".to_string(),
         Object::Quote(_) => "Quote: ".to_string(),
         Object::CompiledFunction(v) => format!("CompiledFunction({})", v.number_of_parameters),
         Object::Closure { function, free: _ } => {
             format!("Closure({})", function.number_of_parameters)
         }
+        Object::Generator(_) => "Generator".to_string(),
     }
 }
 impl Display for Object {
@@ -139,6 +190,8 @@ impl Display for Object {
         match self {
             Object::Int(i) => write!(f, "{}", i),
             Object::String(s) => write!(f, "{}", s),
+            Object::Char(c) => write!(f, "{}", c),
+            Object::Symbol(index) => write!(f, ":{}", symbol_name(*index)),
             Object::Boolean(value) => write!(f, "{}", value),
             Object::Null => write!(f, "NULL"),
             Object::ReturnValue(object) => write!(f, "{}", object),
@@ -154,6 +207,11 @@ impl Display for Object {
                 let elements_str: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
                 write!(f, "[{}]", join_collection!(elements_str, ", "))
             }
+            Object::Tuple { elements } if elements.len() == 1 => write!(f, "({},)", elements[0]),
+            Object::Tuple { elements } => {
+                let elements_str: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "({})", join_collection!(elements_str, ", "))
+            }
             Object::HashMap(map) => {
                 let entries: Vec<String> = map
                     .iter()
@@ -161,6 +219,11 @@ impl Display for Object {
                     .collect();
                 write!(f, "{{{}}}", join_collection!(entries, ", "))
             }
+            Object::Set(set) => {
+                let elements: Vec<String> =
+                    sorted_elements(set).iter().map(|e| e.to_string()).collect();
+                write!(f, "#{{{}}}", join_collection!(elements, ", "))
+            }
             Object::Quote(statement) => write!(f, "Quote: {}", statement),
             Object::CompiledFunction(v) => write!(
                 f,
@@ -172,11 +235,12 @@ impl Display for Object {
                 "Closure({}\n, instructions:\n{}\n, locals: {}, free: {free:?})",
                 function.number_of_parameters, function.instructions, function.number_of_locals
             ),
+            Object::Generator(_) => write!(f, "{}", type_of(self)),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct HashValue(i64);
 impl Display for HashValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -184,6 +248,16 @@ impl Display for HashValue {
     }
 }
 
+//integers by value, the rest grouped by type and ordered by text
+pub fn sorted_elements(set: &std::collections::HashMap<HashValue, Rc<Object>>) -> Vec<Rc<Object>> {
+    let mut elements: Vec<Rc<Object>> = set.values().cloned().collect();
+    elements.sort_by(|left, right| match (left.as_ref(), right.as_ref()) {
+        (Object::Int(l), Object::Int(r)) => l.cmp(r),
+        _ => (type_of(left), left.to_string()).cmp(&(type_of(right), right.to_string())),
+    });
+    elements
+}
+
 #[derive(Debug, Clone)]
 pub struct HashEntry {
     pub key: Rc<Object>,
@@ -195,6 +269,11 @@ pub fn hash(object: &Object) -> HashValue {
     match object {
         Object::Int(i) => i.hash(&mut hasher),
         Object::String(s) => s.hash(&mut hasher),
+        Object::Char(c) => c.hash(&mut hasher),
+        Object::Symbol(index) => {
+            "symbol".hash(&mut hasher);
+            index.hash(&mut hasher);
+        }
         Object::Boolean(b) => b.hash(&mut hasher),
         Object::ReturnValue(rv) => panic!("Cannot, hash ReturnValue directly: {}", rv),
         Object::Error {
@@ -207,11 +286,7 @@ pub fn hash(object: &Object) -> HashValue {
             column.hash(&mut hasher);
         }
         Object::Null => 0.hash(&mut hasher),
-        Object::Function {
-            parameters,
-            body: _,
-            env: _,
-        } => {
+        Object::Function { parameters, .. } => {
             parameters.iter().for_each(|p| p.name.hash(&mut hasher));
         }
 
@@ -227,7 +302,19 @@ pub fn hash(object: &Object) -> HashValue {
         Object::Array { elements } => {
             elements.iter().for_each(|e| hash(e).0.hash(&mut hasher));
         }
+        //same elements in an array are a different key
+        Object::Tuple { elements } => {
+            "tuple".hash(&mut hasher);
+            elements.iter().for_each(|e| hash(e).0.hash(&mut hasher));
+        }
         Object::HashMap(map) => panic!("Cannot hash HashMap directly: {}", map.len()),
+        //hashes of elements are sorted, so the order of insertion does not matter
+        Object::Set(set) => {
+            "set".hash(&mut hasher);
+            let mut keys: Vec<&HashValue> = set.keys().collect();
+            keys.sort();
+            keys.iter().for_each(|key| key.hash(&mut hasher));
+        }
         Object::Quote(statement) => panic!("Cannot hash Quote directly: {}", statement),
         Object::CompiledFunction(v) => panic!(
             "Cannot hash CompiledFunction directly: {} {}",
@@ -237,6 +324,8 @@ pub fn hash(object: &Object) -> HashValue {
             "Cannot hash Closure: {} {}, {free:?}",
             function.instructions, function.number_of_parameters
         ),
+        //generators are equal only to themselves
+        Object::Generator(generator) => (Rc::as_ptr(generator) as usize).hash(&mut hasher),
     }
     HashValue(hasher.finish() as i64)
 }
@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+use crate::ast::expression::InfixOperatorType;
+
+use super::{Object, boolean_value, hash, is_truthy, type_of};
+
+//map overrides an operator with a function under `__add__`, `__sub__`, `__mul__`, `__div__`,
+//`__eq__` for `==` and `!=`, `__lt__` for `<` and `>`, or `__str__` for its text
+pub struct Overload {
+    pub function: Rc<Object>,
+    pub arguments: Vec<Rc<Object>>,
+    result: OverloadResult,
+}
+
+enum OverloadResult {
+    Value,
+    Negated,
+    //text of the value is joined with the string on the other side of `+`
+    Joined { before: String, after: String },
+}
+
+impl Overload {
+    pub fn finish(&self, result: Rc<Object>) -> Rc<Object> {
+        if let Object::Error { .. } = result.as_ref() {
+            return result;
+        }
+        match &self.result {
+            OverloadResult::Value => result,
+            OverloadResult::Negated => boolean_value(!is_truthy(&result)),
+            OverloadResult::Joined { before, after } => {
+                Rc::new(Object::String(format!("{before}{result}{after}")))
+            }
+        }
+    }
+}
+
+pub fn find_overload(
+    operator: &InfixOperatorType,
+    left: &Rc<Object>,
+    right: &Rc<Object>,
+) -> Option<Overload> {
+    if *operator == InfixOperatorType::Plus
+        && let Some(joined) = find_joined(left, right)
+    {
+        return Some(joined);
+    }
+    let (name, arguments, result) = match operator {
+        InfixOperatorType::Plus => ("__add__", [left, right], OverloadResult::Value),
+        InfixOperatorType::Minus => ("__sub__", [left, right], OverloadResult::Value),
+        InfixOperatorType::Multiply => ("__mul__", [left, right], OverloadResult::Value),
+        InfixOperatorType::Divide => ("__div__", [left, right], OverloadResult::Value),
+        InfixOperatorType::Equal => ("__eq__", [left, right], OverloadResult::Value),
+        InfixOperatorType::NotEqual => ("__eq__", [left, right], OverloadResult::Negated),
+        InfixOperatorType::LessThan => ("__lt__", [left, right], OverloadResult::Value),
+        InfixOperatorType::GreaterThan => ("__lt__", [right, left], OverloadResult::Value),
+    };
+    let function = method(arguments[0], name).or_else(|| method(arguments[1], name))?;
+    Some(Overload {
+        function,
+        arguments: arguments.into_iter().cloned().collect(),
+        result,
+    })
+}
+
+//string added to a value with `__str__` is joined with its text before trying `__add__`
+fn find_joined(left: &Rc<Object>, right: &Rc<Object>) -> Option<Overload> {
+    let (value, result) = match (left.as_ref(), right.as_ref()) {
+        (Object::String(text), _) => (
+            right,
+            OverloadResult::Joined {
+                before: text.clone(),
+                after: String::new(),
+            },
+        ),
+        (_, Object::String(text)) => (
+            left,
+            OverloadResult::Joined {
+                before: String::new(),
+                after: text.clone(),
+            },
+        ),
+        _ => return None,
+    };
+    display_method(value).map(|function| Overload {
+        function,
+        arguments: vec![value.clone()],
+        result,
+    })
+}
+
+pub fn display_method(value: &Object) -> Option<Rc<Object>> {
+    method(value, "__str__")
+}
+
+fn method(value: &Object, name: &str) -> Option<Rc<Object>> {
+    match value {
+        Object::HashMap(map) => map
+            .get(&hash(&Object::String(name.to_string())))
+            .map(|entry| entry.value.clone()),
+        _ => None,
+    }
+}
+
+//strict mode never converts values to make them fit, repeating strings and maps are exceptions
+pub fn strict_violation(
+    operator: &InfixOperatorType,
+    left: &Object,
+    right: &Object,
+) -> Option<String> {
+    let allowed = match (left, right) {
+        (Object::HashMap(_), _) | (_, Object::HashMap(_)) => true,
+        (Object::String(_), Object::Int(_)) => *operator == InfixOperatorType::Multiply,
+        _ => std::mem::discriminant(left) == std::mem::discriminant(right),
+    };
+    if allowed {
+        return None;
+    }
+    Some(format!(
+        "Strict mode does not allow {} on {}({}) and {}({})",
+        operator,
+        type_of(left),
+        left,
+        type_of(right),
+        right
+    ))
+}
@@ -0,0 +1,36 @@
+use std::{cell::RefCell, collections::HashMap};
+
+//symbol value is an index here, comparing and hashing tags does not touch the text
+#[derive(Default)]
+struct SymbolTable {
+    names: Vec<String>,
+    indexes: HashMap<String, u32>,
+}
+
+thread_local! {
+    static SYMBOLS: RefCell<SymbolTable> = RefCell::new(SymbolTable::default());
+}
+
+pub fn intern(name: &str) -> u32 {
+    SYMBOLS.with(|symbols| {
+        let mut symbols = symbols.borrow_mut();
+        if let Some(index) = symbols.indexes.get(name) {
+            return *index;
+        }
+        let index = symbols.names.len() as u32;
+        symbols.names.push(name.to_string());
+        symbols.indexes.insert(name.to_string(), index);
+        index
+    })
+}
+
+pub fn symbol_name(index: u32) -> String {
+    SYMBOLS.with(|symbols| {
+        symbols
+            .borrow()
+            .names
+            .get(index as usize)
+            .cloned()
+            .unwrap_or_else(|| panic!("Symbol {index} was never interned"))
+    })
+}
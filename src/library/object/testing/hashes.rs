@@ -3,6 +3,15 @@ use std::rc::Rc;
 use crate::object::Object;
 use crate::object::hash;
 
+fn set(values: &[i64]) -> Object {
+    Object::Set(
+        values
+            .iter()
+            .map(|value| (hash(&Object::Int(*value)), Rc::new(Object::Int(*value))))
+            .collect(),
+    )
+}
+
 macro_rules! should_be_same_cache {
     ($($name:ident: ($input:expr, $other:expr),)*) => {
         $(
@@ -44,6 +53,8 @@ should_be_same_cache! {
     same_larger_integer: (&Object::Int(1000), Rc::new(Object::Int(1000)).as_ref()),
     same_boolean_true: (&Object::Boolean(true), Rc::new(Object::Boolean(true)).as_ref()),
     same_boolean_false: (&Object::Boolean(false), Rc::new(Object::Boolean(false)).as_ref()),
+    same_symbol: (&Object::Symbol(crate::object::intern("ok")), &Object::Symbol(crate::object::intern("ok"))),
+    same_set_in_other_order: (&set(&[1, 2, 3]), &set(&[3, 1, 2])),
 }
 
 should_be_different_cache! {
@@ -54,5 +65,7 @@ should_be_different_cache! {
     different_false_and_0: (&Object::Boolean(false), &Object::Int(0)),
     different_true_and_1: (&Object::Boolean(true), &Object::Int(1)),
     different_null_and_0: (&Object::Null, &Object::Int(0)),
+    different_symbols: (&Object::Symbol(crate::object::intern("ok")), &Object::Symbol(crate::object::intern("error"))),
+    different_set_and_tuple: (&set(&[1, 2]), &Object::Tuple { elements: vec![Rc::new(Object::Int(1)), Rc::new(Object::Int(2))] }),
 
 }
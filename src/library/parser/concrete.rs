@@ -0,0 +1,52 @@
+use std::rc::Rc;
+
+use crate::{
+    ast::concrete::{SyntaxElement, SyntaxNode},
+    lexers::Lexer,
+    tokens::Token,
+};
+
+use super::{ParseError, Parser};
+
+pub fn parse_lossless(source: &str) -> (SyntaxNode, Vec<ParseError>) {
+    let mut parser = Parser::lossless(Lexer::from_source(source));
+    let tree = parser.parse_concrete_program();
+    (tree, parser.errors)
+}
+
+impl<'a> Parser<'a> {
+    pub fn lossless(mut lexer: Lexer<'a>) -> Self {
+        lexer.set_lossless(true);
+        Self::new(lexer)
+    }
+
+    //has to be called before anything else is parsed
+    pub fn parse_concrete_program(&mut self) -> SyntaxNode {
+        let first: Vec<Rc<Token>> = std::iter::once(self.current_token.clone())
+            .chain(self.peek_token.clone())
+            .collect();
+        self.recorded = Some(first);
+        let mut children = vec![];
+        let mut start = self.position;
+        while !self.is_finished() {
+            let statement = self.parse_statement();
+            //tokens skipped after an error belong to the invalid node
+            if statement.is_none() {
+                self.synchronize();
+            }
+            let tokens = &self.recorded_tokens()[start..=self.position];
+            children.push(SyntaxElement::Node(SyntaxNode::statement(
+                statement, tokens,
+            )));
+            start = self.position + 1;
+            self.save_next_token();
+        }
+        let rest = self.recorded_tokens()[start..].iter().cloned();
+        children.extend(rest.map(SyntaxElement::Token));
+        SyntaxNode::program(children)
+    }
+
+    fn recorded_tokens(&self) -> &[Rc<Token>] {
+        self.recorded.as_deref().unwrap_or_default()
+    }
+}
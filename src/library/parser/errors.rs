@@ -0,0 +1,134 @@
+use std::fmt::Display;
+
+use crate::{
+    join_collection,
+    lines::TokenPosition,
+    tokens::{PureTokenKind, Token, TokenKind},
+};
+
+use super::Precedence;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnexpectedToken {
+        expected: Vec<PureTokenKind>, //any of them would do
+        found: TokenKind,
+        position: Option<TokenPosition>,
+    },
+    MissingExpression {
+        found: TokenKind,
+        position: Option<TokenPosition>,
+    },
+    MissingType {
+        found: TokenKind,
+        position: Option<TokenPosition>,
+    },
+    UnknownType {
+        name: String,
+        position: Option<TokenPosition>,
+    },
+    InvalidToken {
+        problem: String, //as reported by the lexer
+        position: Option<TokenPosition>,
+    },
+    InvalidPattern {
+        pattern: String,
+        position: Option<TokenPosition>,
+    },
+    MisplacedYield {
+        position: Option<TokenPosition>,
+    },
+    YieldInExpression {
+        position: Option<TokenPosition>,
+    },
+    InvalidPrecedence {
+        precedence: i64,
+        position: Option<TokenPosition>,
+    },
+    BuiltinOperator {
+        operator: String,
+        position: Option<TokenPosition>,
+    },
+}
+
+impl ParseError {
+    pub(super) fn unexpected(expected: &[PureTokenKind], found: &Token) -> Self {
+        ParseError::UnexpectedToken {
+            expected: expected.to_vec(),
+            found: found.kind.clone(),
+            position: found.context,
+        }
+    }
+
+    pub fn position(&self) -> Option<TokenPosition> {
+        match self {
+            ParseError::UnexpectedToken { position, .. }
+            | ParseError::MissingExpression { position, .. }
+            | ParseError::MissingType { position, .. }
+            | ParseError::UnknownType { position, .. }
+            | ParseError::InvalidToken { position, .. }
+            | ParseError::InvalidPattern { position, .. }
+            | ParseError::InvalidPrecedence { position, .. }
+            | ParseError::BuiltinOperator { position, .. }
+            | ParseError::MisplacedYield { position }
+            | ParseError::YieldInExpression { position } => *position,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::UnexpectedToken {
+                expected, found, ..
+            } => format!(
+                "Expected {}, got {}",
+                join_collection!(expected, " or "),
+                describe(found)
+            ),
+            ParseError::MissingExpression { found, .. } => {
+                format!("Expected expression, got {}", describe(found))
+            }
+            ParseError::MissingType { found, .. } => {
+                format!("Expected type, got {}", describe(found))
+            }
+            ParseError::UnknownType { name, .. } => format!("Unknown type {}", name),
+            ParseError::InvalidToken { problem, .. } => format!("Invalid token: {}", problem),
+            ParseError::InvalidPattern { pattern, .. } => {
+                format!("Expected tuple of identifiers after let, got {}", pattern)
+            }
+            ParseError::MisplacedYield { .. } => {
+                String::from("yield is allowed only as statement of generator body")
+            }
+            ParseError::YieldInExpression { .. } => {
+                String::from("yield inside of if which is part of expression")
+            }
+            ParseError::InvalidPrecedence { precedence, .. } => format!(
+                "Precedence of operator must be from 1 to {}, got {}",
+                Precedence::PREFIX.0 - 1,
+                precedence
+            ),
+            ParseError::BuiltinOperator { operator, .. } => {
+                format!("Operator {} is built in and cannot be declared", operator)
+            }
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.position() {
+            Some(position) => write!(f, "{}: {}", position, self.message()),
+            None => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+fn describe(found: &TokenKind) -> String {
+    match found {
+        TokenKind::Identifier(name) => format!("identifier {}", name),
+        TokenKind::Integer(value) => format!("integer {}", value),
+        TokenKind::StringLiteral(value) => format!("string \"{}\"", value),
+        TokenKind::Symbol(name) => format!("symbol :{}", name),
+        TokenKind::Operator(operator) => format!("operator {}", operator),
+        kind => PureTokenKind::from(kind).to_string(),
+    }
+}
@@ -1,66 +1,107 @@
+mod concrete;
+mod errors;
+mod operators;
 #[cfg(test)]
 mod parser_tests;
+mod types;
 
-use std::rc::Rc;
+use std::{io::Read, rc::Rc};
 
 use crate::{
     ast::{
-        self,
-        expression::{
-            Expression, InfixOperatorType, function_literal, identifier, if_expression,
-            macro_literal,
-        },
-        statements::{Program, Statement},
+        expression::{Expression, function_literal, identifier, if_expression, macro_literal},
+        statements::{Associativity, Fixity, Program, Statement},
+        types::{Signature, TypeAnnotation},
     },
-    lexers::Lexer,
+    lexers::{Lexer, is_builtin_operator},
+    lines::SourceSpan,
     tokens::{PureTokenKind, Token, TokenKind},
 };
 
-pub struct Parser {
-    lexer: Lexer,
-    pub errors: Vec<String>,
+pub use concrete::parse_lossless;
+pub use errors::ParseError;
+pub(crate) use operators::{Precedence, operator_precedence};
+
+use operators::{Grammar, Rule, prefix_operator};
+
+pub struct Parser<'a> {
+    lexer: Lexer<'a>,
+    pub errors: Vec<ParseError>,
     current_token: Rc<Token>,
     peek_token: Option<Rc<Token>>,
+    ended: bool,     //lexer ran out of tokens and the end of input was given out
+    position: usize, //index of current token in the input
+    recorded: Option<Vec<Rc<Token>>>, //all tokens read, kept for the lossless tree
+    yield_allowed: bool, //yield is a statement of generator body or of branches of its `if`
+    yields: usize,   //yields found in current function
+    grammar: Grammar<'a>,
 }
 
-impl Parser {
-    pub fn from_string(source: &str) -> Self {
-        let mut lexer = Lexer::new();
-        for line in source.lines() {
-            lexer.process(line);
-        }
-        lexer.process(";"); // Ensure the last line is processed
-        Self::new(lexer)
+impl<'a> Parser<'a> {
+    pub fn from_string(source: &'a str) -> Self {
+        Self::new(Lexer::from_source(source))
+    }
+
+    pub fn from_reader(reader: impl Read + 'static) -> Self {
+        Self::new(Lexer::from_reader(reader))
     }
 
-    pub fn errors(&self) -> &Vec<String> {
+    pub fn errors(&self) -> &Vec<ParseError> {
         &self.errors
     }
 
-    pub fn new(mut lexer: Lexer) -> Self {
-        let current = lexer.next();
-        let peek = lexer.next();
-        if current.is_none() {
-            panic!("Lexer is empty");
-        }
-        Self {
+    pub fn new(mut lexer: Lexer<'a>) -> Self {
+        let mut ended = false;
+        let current =
+            Self::pull(&mut lexer, &mut ended).unwrap_or_else(|| Rc::new(lexer.end_of_input()));
+        let peek = Self::pull(&mut lexer, &mut ended);
+        let mut parser = Self {
             lexer,
             errors: Vec::new(),
-            current_token: current.unwrap(),
+            current_token: current,
             peek_token: peek,
+            ended,
+            position: 0,
+            recorded: None,
+            yield_allowed: false,
+            yields: 0,
+            grammar: Grammar::new(),
+        };
+        parser.report_invalid_token();
+        parser
+    }
+
+    //single end of input token follows the last statement, parsing stops there
+    fn pull(lexer: &mut Lexer<'_>, ended: &mut bool) -> Option<Rc<Token>> {
+        if let Some(token) = lexer.next() {
+            return Some(token);
         }
+        if *ended {
+            return None;
+        }
+        *ended = true;
+        Some(Rc::new(lexer.end_of_input()))
     }
 
     fn next_token(&mut self) {
-        let next = self.peek_token.take().expect("No next token");
-        self.peek_token = self.lexer.next();
+        let Some(next) = self.peek_token.take() else {
+            return;
+        };
+        self.peek_token = Self::pull(&mut self.lexer, &mut self.ended);
         self.current_token = next;
+        self.position += 1;
+        if let (Some(recorded), Some(peek)) = (&mut self.recorded, &self.peek_token) {
+            recorded.push(peek.clone());
+        }
+        self.report_invalid_token();
+    }
 
-        if let TokenKind::Invalid(value) = &self.current_token.kind {
-            if let Some(context) = self.current_token.context {
-                self.errors
-                    .push(format!("Invalid token at {}: {}", context, value))
-            }
+    fn report_invalid_token(&mut self) {
+        if let TokenKind::Invalid(problem) = &self.current_token.kind {
+            self.errors.push(ParseError::InvalidToken {
+                problem: problem.clone(),
+                position: self.current_token.context,
+            });
         }
     }
 
@@ -80,52 +121,164 @@ impl Parser {
             statements: Vec::new(),
         };
         while !self.is_finished() {
-            let statement = self.parse_statement();
-            if let Some(statement) = statement {
-                program.statements.push(statement);
+            match self.parse_statement() {
+                Some(statement) => program.statements.push(statement),
+                None => self.synchronize(),
             }
             self.save_next_token();
         }
         program
     }
 
+    //stops at `;`, or before `let`, `return` or a closing `}`, brackets are skipped whole
+    fn synchronize(&mut self) {
+        let mut depth = 0;
+        while !self.is_finished() {
+            match PureTokenKind::from(&self.current_token.kind) {
+                PureTokenKind::Semicolon | PureTokenKind::RightBrace if depth == 0 => return,
+                PureTokenKind::LeftParen
+                | PureTokenKind::LeftBracket
+                | PureTokenKind::LeftBrace
+                | PureTokenKind::SetStart => depth += 1,
+                PureTokenKind::RightParen
+                | PureTokenKind::RightBracket
+                | PureTokenKind::RightBrace => depth -= 1,
+                _ => {}
+            }
+            let boundary = [
+                PureTokenKind::Let,
+                PureTokenKind::Return,
+                PureTokenKind::RightBrace,
+            ];
+            if depth <= 0 && boundary.iter().any(|kind| self.peek_token_is(kind)) {
+                return;
+            }
+            self.save_next_token();
+        }
+    }
+
     fn parse_statement(&mut self) -> Option<Statement> {
-        match self.current_token.kind {
-            TokenKind::Let => self.parse_let_statement(),
-            TokenKind::Return => self.parse_return_statement(),
-            _ => self.parse_expression_statement(),
+        let token = self.current_token.clone();
+        match token.kind {
+            TokenKind::Let => self.without_yield(Self::parse_let_statement),
+            TokenKind::Return => self.without_yield(Self::parse_return_statement),
+            TokenKind::Identifier(_) if self.peek_token_is(&PureTokenKind::Assign) => {
+                self.without_yield(Self::parse_assign_statement)
+            }
+            TokenKind::Function if self.is_function_declaration() => {
+                self.parse_function_declaration()
+            }
+            TokenKind::Identifier(ref name)
+                if (name == "infix" || name == "infixr")
+                    && self.peek_token_is(&PureTokenKind::Integer) =>
+            {
+                self.without_yield(Self::parse_operator_declaration)
+            }
+            TokenKind::Yield => self.parse_yield_statement(),
+            TokenKind::At => self.without_yield(Self::parse_decorated_let),
+            TokenKind::If => {
+                //branches of `if` can yield only when the `if` is whole statement, otherwise
+                //generator could not be resumed inside of them
+                let yields = self.yields;
+                let statement = self.parse_expression_statement()?;
+                if let Statement::AExpression {
+                    expression, token, ..
+                } = &statement
+                    && !matches!(expression, Expression::AIf { .. })
+                    && self.yields > yields
+                {
+                    self.errors.push(ParseError::YieldInExpression {
+                        position: token.context,
+                    });
+                }
+                Some(statement)
+            }
+            _ => self.without_yield(Self::parse_expression_statement),
+        }
+    }
+
+    fn without_yield<T>(&mut self, parse: impl FnOnce(&mut Self) -> T) -> T {
+        let allowed = std::mem::replace(&mut self.yield_allowed, false);
+        let result = parse(self);
+        self.yield_allowed = allowed;
+        result
+    }
+
+    fn is_function_declaration(&mut self) -> bool {
+        if self.peek_token_is(&PureTokenKind::Identifier) {
+            return true;
         }
+        self.peek_token_is(&PureTokenKind::Asterisk)
+            && self
+                .lexer
+                .peek()
+                .is_some_and(|token| matches!(token.kind, TokenKind::Identifier(_)))
+    }
+
+    fn parse_yield_statement(&mut self) -> Option<Statement> {
+        let yield_token = self.current_token.clone();
+        if !self.yield_allowed {
+            self.errors.push(ParseError::MisplacedYield {
+                position: yield_token.context,
+            });
+        }
+        self.yields += 1;
+        let delegate = self.peek_token_is(&PureTokenKind::Asterisk);
+        if delegate {
+            self.save_next_token();
+        }
+        self.save_next_token();
+        let value = self.without_yield(|parser| parser.parse_expression(Precedence::LOWEST))?;
+        if self.peek_token_is(&PureTokenKind::Semicolon) {
+            self.save_next_token();
+        }
+        Some(Statement::Yield {
+            span: self.span_from(&yield_token),
+            token: yield_token,
+            value,
+            delegate,
+        })
     }
 
     fn parse_let_statement(&mut self) -> Option<Statement> {
         let let_token = self.current_token.clone();
-        if !self.expect_peek_and_move_into(&PureTokenKind::Identifier) {
+        if self.peek_token_is(&PureTokenKind::LeftParen) {
+            return self.parse_destructuring_let(let_token);
+        }
+        if !self.peek_token_is(&PureTokenKind::Identifier) {
+            self.peek_error(&[PureTokenKind::Identifier, PureTokenKind::LeftParen]);
             return None;
         }
+        self.next_token();
 
         let name = Expression::Identifier(self.current_token.clone());
         let name_token = self.current_token.clone();
+        let annotation = self.parse_optional_annotation()?;
         if !self.expect_peek_and_move_into(&PureTokenKind::Assign) {
             return None;
         }
         self.save_next_token();
-        let value = self
-            .parse_expression(Precedence::Lowest)
-            .expect("Should find value");
+        let value = self.parse_expression(Precedence::LOWEST)?;
         let value = match value {
             Expression::FunctionLiteral {
                 token,
+                span,
                 parameters,
                 body,
                 name: _,
+                generator,
+                signature,
             } => Expression::FunctionLiteral {
                 token: token.clone(),
+                span,
                 parameters,
                 body,
                 name: match &name_token.kind {
                     TokenKind::Identifier(name) => Some(name.to_string()),
                     _ => None,
                 },
+                generator,
+                signature,
             },
             _ => value,
         };
@@ -133,12 +286,217 @@ impl Parser {
             self.save_next_token();
         }
         Some(Statement::Let {
+            span: self.span_from(&let_token),
             token: let_token,
             name,
             value: value.clone(),
+            annotation,
         })
     }
 
+    //decorated function has no name, it calls itself through the decorated binding
+    fn parse_decorated_let(&mut self) -> Option<Statement> {
+        let token = self.current_token.clone();
+        let mut decorators = vec![];
+        while self.current_token.kind == TokenKind::At {
+            self.save_next_token();
+            decorators.push(self.parse_expression(Precedence::LOWEST)?);
+            self.save_next_token();
+        }
+        if self.current_token.kind != TokenKind::Let {
+            let error = ParseError::unexpected(&[PureTokenKind::Let], &self.current_token);
+            self.errors.push(error);
+            return None;
+        }
+        let Statement::Let {
+            token: let_token,
+            name,
+            value,
+            annotation,
+            ..
+        } = self.parse_let_statement()?
+        else {
+            return None;
+        };
+        let value = match value {
+            Expression::FunctionLiteral {
+                token,
+                span,
+                parameters,
+                body,
+                generator,
+                signature,
+                ..
+            } => Expression::FunctionLiteral {
+                token,
+                span,
+                parameters,
+                body,
+                name: None,
+                generator,
+                signature,
+            },
+            _ => value,
+        };
+        let span = self.span_from(&token);
+        Some(Statement::Let {
+            token: let_token,
+            span,
+            name,
+            value: Expression::Decorated {
+                span: token.source_span().to(value.span()),
+                token,
+                decorators,
+                value: Box::new(value),
+            },
+            annotation,
+        })
+    }
+
+    fn parse_destructuring_let(&mut self, let_token: Rc<Token>) -> Option<Statement> {
+        self.save_next_token();
+        let pattern = self.parse_grouped_expression()?;
+        if !is_tuple_pattern(&pattern) {
+            self.errors.push(ParseError::InvalidPattern {
+                pattern: pattern.to_string(),
+                position: let_token.context,
+            });
+            return None;
+        }
+        let annotation = self.parse_optional_annotation()?;
+        if !self.expect_peek_and_move_into(&PureTokenKind::Assign) {
+            return None;
+        }
+        self.save_next_token();
+        let value = self.parse_expression(Precedence::LOWEST)?;
+        if self.peek_token_is(&PureTokenKind::Semicolon) {
+            self.save_next_token();
+        }
+        Some(Statement::Let {
+            span: self.span_from(&let_token),
+            token: let_token,
+            name: pattern,
+            value,
+            annotation,
+        })
+    }
+
+    fn parse_assign_statement(&mut self) -> Option<Statement> {
+        let name = Expression::Identifier(self.current_token.clone());
+        self.save_next_token();
+        let assign_token = self.current_token.clone();
+        self.save_next_token();
+        let value = self.parse_expression(Precedence::LOWEST)?;
+        if self.peek_token_is(&PureTokenKind::Semicolon) {
+            self.save_next_token();
+        }
+        Some(Statement::Assign {
+            span: name.span().to(self.current_token.source_span()),
+            token: assign_token,
+            name,
+            value,
+        })
+    }
+
+    fn parse_function_declaration(&mut self) -> Option<Statement> {
+        let function_token = self.current_token.clone();
+        let generator = self.peek_token_is(&PureTokenKind::Asterisk);
+        if generator {
+            self.save_next_token();
+        }
+        self.save_next_token();
+        let name = identifier(self.current_token.clone());
+        let value = match self.parse_function_literal(function_token.clone(), generator)? {
+            Expression::FunctionLiteral {
+                token,
+                span,
+                parameters,
+                body,
+                name: _,
+                generator,
+                signature,
+            } => Expression::FunctionLiteral {
+                token,
+                span,
+                parameters,
+                body,
+                name: Some(name.to_string()),
+                generator,
+                signature,
+            },
+            other => other,
+        };
+        if self.peek_token_is(&PureTokenKind::Semicolon) {
+            self.save_next_token();
+        }
+        Some(Statement::Function {
+            span: self.span_from(&function_token),
+            token: function_token,
+            name,
+            value,
+        })
+    }
+
+    fn parse_operator_declaration(&mut self) -> Option<Statement> {
+        let token = self.current_token.clone();
+        let associativity = match &token.kind {
+            TokenKind::Identifier(name) if name == "infixr" => Associativity::Right,
+            _ => Associativity::Left,
+        };
+        self.lexer.expect_operator();
+        self.next_token();
+        let precedence_token = self.current_token.clone();
+        let TokenKind::Integer(precedence) = precedence_token.kind else {
+            return None;
+        };
+        if !self.expect_peek_and_move_into(&PureTokenKind::Operator) {
+            return None;
+        }
+        let operator = self.current_token.clone();
+        let text = operator.kind.literal();
+        if !(1..i64::from(Precedence::PREFIX.0)).contains(&precedence) {
+            self.errors.push(ParseError::InvalidPrecedence {
+                precedence,
+                position: precedence_token.context,
+            });
+            return None;
+        }
+        if is_builtin_operator(&text) {
+            self.errors.push(ParseError::BuiltinOperator {
+                operator: text,
+                position: operator.context,
+            });
+            return None;
+        }
+        let fixity = Fixity {
+            precedence: precedence as u8,
+            associativity,
+        };
+        //declared before its function is parsed, so the function can use it too
+        self.lexer.declare_operator(&text);
+        self.grammar.declare(&text, fixity);
+        if !self.expect_peek_and_move_into(&PureTokenKind::Assign) {
+            return None;
+        }
+        self.save_next_token();
+        let value = self.parse_expression(Precedence::LOWEST)?;
+        if self.peek_token_is(&PureTokenKind::Semicolon) {
+            self.save_next_token();
+        }
+        let name = Token::at(operator.source_span(), TokenKind::Identifier(text));
+        Some(Statement::Operator {
+            span: self.span_from(&token),
+            token,
+            fixity,
+            name: identifier(Rc::new(name)),
+            value,
+        })
+    }
+
+    fn span_from(&self, first: &Token) -> SourceSpan {
+        first.source_span().to(self.current_token.source_span())
+    }
+
     fn peek_token_is(&self, pure_token_kind: &PureTokenKind) -> bool {
         if let Some(peek) = &self.peek_token {
             let existing: PureTokenKind = (&peek.kind).into();
@@ -157,175 +515,161 @@ impl Parser {
             self.next_token();
             true
         } else {
-            self.peek_error(pure_token_kind);
+            self.peek_error(&[*pure_token_kind]);
             false
         }
     }
 
-    fn peek_error(&mut self, expected: &PureTokenKind) {
-        let error = format!(
-            "Expected next token to be {:?}, got {:?} instead",
-            expected, self.peek_token
-        );
-        self.errors.push(error);
+    fn peek_error(&mut self, expected: &[PureTokenKind]) {
+        let found = self.peek_token.as_ref().unwrap_or(&self.current_token);
+        self.errors.push(ParseError::unexpected(expected, found));
     }
 
     fn parse_return_statement(&mut self) -> Option<Statement> {
         let return_token = self.current_token.clone();
         self.save_next_token();
-        let value = self.parse_expression(Precedence::Lowest);
-        value.as_ref()?;
+        let return_value = self.parse_expression(Precedence::LOWEST)?;
         if self.peek_token_is(&PureTokenKind::Semicolon) {
             self.save_next_token();
         }
         Some(Statement::Return {
+            span: self.span_from(&return_token),
             token: return_token,
-            return_value: value.unwrap(),
+            return_value,
         })
     }
 
     fn parse_expression_statement(&mut self) -> Option<Statement> {
         let expression_token = self.current_token.clone();
-        let expression = self.parse_expression(Precedence::Lowest);
-        expression.as_ref()?;
+        let expression = self.parse_expression(Precedence::LOWEST)?;
         if self.peek_token_is(&PureTokenKind::Semicolon) {
             self.save_next_token();
         }
         Some(Statement::AExpression {
+            span: self.span_from(&expression_token),
             token: expression_token,
-            expression: expression.unwrap(),
+            expression,
         })
     }
 
-    fn parse_prefix(&mut self) -> Option<Expression> {
-        match self.current_token.kind {
-            TokenKind::Identifier(_) => {
-                let identifier = Expression::Identifier(self.current_token.clone());
-                Some(identifier)
-            }
-            TokenKind::Integer(_value) => {
-                let integer = Expression::IntegerLiteral(self.current_token.clone());
-                Some(integer)
-            }
-            TokenKind::Negation => self.parse_prefix_expression(),
-            TokenKind::Minus => self.parse_prefix_expression(),
-            TokenKind::True | TokenKind::False => self.parse_boolean(),
-            TokenKind::StringLiteral(_) => self.parse_string_literal(),
-            TokenKind::LeftParen => self.parse_grouped_expression(),
-            TokenKind::If => self.parse_if_expression(),
-            TokenKind::Function => self.parse_function_expression(),
-            TokenKind::Macro => self.parse_macro_expression(),
-            TokenKind::LeftBracket => self.parse_array_literal(),
-            TokenKind::LeftBrace => self.parse_map_literal(),
-            _ => None,
-        }
-    }
-
-    fn infix(&mut self, left_exp: Expression) -> Option<Expression> {
-        match self.peek_token.as_ref().unwrap().kind {
-            TokenKind::Plus
-            | TokenKind::Minus
-            | TokenKind::Slash
-            | TokenKind::Asterisk
-            | TokenKind::Equal
-            | TokenKind::Inequal
-            | TokenKind::LessThen
-            | TokenKind::GreaterThen => {
-                self.save_next_token();
-                self.parse_infix_expression(left_exp)
-            }
-            //it easier to act like it is a infix operator, like call expression
-            TokenKind::LeftBracket => {
-                self.save_next_token();
-                self.parse_index_expression(left_exp)
-            }
-            TokenKind::LeftParen => {
-                self.save_next_token();
-                self.parse_call_expression(left_exp)
-            }
-            _ => None,
-        }
+    fn parse_identifier(&mut self) -> Option<Expression> {
+        Some(Expression::Identifier(self.current_token.clone()))
+    }
+
+    fn parse_integer_literal(&mut self) -> Option<Expression> {
+        Some(Expression::IntegerLiteral(self.current_token.clone()))
+    }
+
+    fn parse_symbol_literal(&mut self) -> Option<Expression> {
+        Some(Expression::SymbolLiteral(self.current_token.clone()))
     }
 
     fn parse_expression(&mut self, precedence: Precedence) -> Option<Expression> {
-        let mut maybe_prefix = self.parse_prefix();
-        if maybe_prefix.is_none() {
-            self.errors.push(format!(
-                "Unexpected {:?} found at {:?}. Details: No prefix parse function found",
-                self.current_token.kind,
-                self.current_token.context?.to_string()
-            ));
+        //operators after the prefix make nodes starting where it starts, before its parentheses
+        let start = self.current_token.clone();
+        let Some(prefix) = self.grammar.prefix(&start.kind) else {
+            self.errors.push(ParseError::MissingExpression {
+                found: self.current_token.kind.clone(),
+                position: self.current_token.context,
+            });
             return None;
-        }
-        let mut left_exp = maybe_prefix.take().expect("Prefix really not found");
-        while !self.peek_token_is(&PureTokenKind::Semicolon)
-            && !self.is_finished()
-            && precedence < precedence_from(self.peek_token.as_ref().unwrap())
-        {
-            let mut infix = self.infix(left_exp);
-            infix.as_ref()?;
-            let infix_really = infix.take().expect("Infix really not found");
-            left_exp = infix_really;
+        };
+        let mut left_exp = prefix(self)?;
+        while !self.peek_token_is(&PureTokenKind::Semicolon) {
+            let Some(rule) = self
+                .peek_token
+                .as_ref()
+                .and_then(|peek| self.grammar.rule(&peek.kind))
+            else {
+                break;
+            };
+            if rule.precedence() <= precedence {
+                break;
+            }
+            self.save_next_token();
+            left_exp = match rule {
+                Rule::Postfix { parse, .. } => parse(self, left_exp, &start)?,
+                Rule::Infix {
+                    precedence,
+                    associativity,
+                    parse,
+                } => {
+                    let operator = self.current_token.clone();
+                    self.save_next_token();
+                    //right operand of `infixr` takes in operators of the same precedence
+                    let right = self.parse_expression(match associativity {
+                        Associativity::Left => precedence,
+                        Associativity::Right => Precedence(precedence.0 - 1),
+                    })?;
+                    parse(operator, left_exp, right, self.span_from(&start))
+                }
+            };
         }
         Some(left_exp)
     }
 
     fn parse_prefix_expression(&mut self) -> Option<Expression> {
-        let operator = match self.current_token.kind {
-            TokenKind::Negation => ast::expression::PrefixOperatorType::Bang,
-            TokenKind::Minus => ast::expression::PrefixOperatorType::Minus,
-            _ => panic!("Unknown prefix operator"),
-        };
+        let operator = prefix_operator(&self.current_token.kind)?;
         let current_token = self.current_token.clone();
         self.save_next_token();
-        let right = self.parse_expression(Precedence::Prefix);
+        let right = self.parse_expression(Precedence::PREFIX)?;
         Some(Expression::PrefixOperator {
+            span: self.span_from(&current_token),
             token: current_token,
             operator,
-            right: right.map(Box::new).unwrap(),
+            right: Box::new(right),
         })
     }
 
-    fn parse_infix_expression(&mut self, left: Expression) -> Option<Expression> {
-        let current_token = self.current_token.clone();
-        let precedence = precedence_from(current_token.as_ref());
-        let operator = token_into_operator(current_token.as_ref()).expect("Operator not found");
-        self.save_next_token();
-
-        self.parse_expression(precedence)
-            .map(|right| Expression::Infix {
-                token: current_token,
-                left: Box::new(left),
-                operator,
-                right: Box::new(right),
-            })
-    }
-
     fn parse_boolean(&mut self) -> Option<Expression> {
-        let boolean = match self.current_token.kind {
-            TokenKind::True => true,
-            TokenKind::False => false,
-            _ => panic!("Unknown boolean"),
-        };
         let current_token = self.current_token.clone();
         Some(Expression::BooleanLiteral {
+            value: current_token.kind == TokenKind::True,
             token: current_token,
-            value: boolean,
         })
     }
 
-    fn parse_string_literal(&self) -> Option<Expression> {
+    fn parse_string_literal(&mut self) -> Option<Expression> {
         let current_token = self.current_token.clone();
         Some(Expression::StringLiteral(current_token))
     }
 
+    //comma inside makes a tuple, `()` is empty and `(a,)` has one element
     fn parse_grouped_expression(&mut self) -> Option<Expression> {
+        let token = self.current_token.clone();
+        if self.peek_token_is(&PureTokenKind::RightParen) {
+            self.save_next_token();
+            return Some(Expression::TupleLiteral {
+                span: self.span_from(&token),
+                token,
+                elements: vec![],
+            });
+        }
         self.save_next_token();
-        let expression = self.parse_expression(Precedence::Lowest);
+        let expression = self.parse_expression(Precedence::LOWEST)?;
+        if !self.peek_token_is(&PureTokenKind::Comma) {
+            if !self.expect_peek_and_move_into(&PureTokenKind::RightParen) {
+                return None;
+            }
+            return Some(expression);
+        }
+        let mut elements = vec![expression];
+        while self.peek_token_is(&PureTokenKind::Comma) {
+            self.save_next_token();
+            if self.peek_token_is(&PureTokenKind::RightParen) {
+                break;
+            }
+            self.save_next_token();
+            elements.push(self.parse_expression(Precedence::LOWEST)?);
+        }
         if !self.expect_peek_and_move_into(&PureTokenKind::RightParen) {
             return None;
         }
-        Some(expression.unwrap())
+        Some(Expression::TupleLiteral {
+            span: self.span_from(&token),
+            token,
+            elements,
+        })
     }
 
     fn parse_if_expression(&mut self) -> Option<Expression> {
@@ -334,8 +678,7 @@ impl Parser {
             return None;
         }
         self.save_next_token();
-        let condition = self.parse_expression(Precedence::Lowest);
-        condition.as_ref()?;
+        let condition = self.without_yield(|parser| parser.parse_expression(Precedence::LOWEST))?;
         if !self.expect_peek_and_move_into(&PureTokenKind::RightParen) {
             return None;
         }
@@ -353,8 +696,9 @@ impl Parser {
             None
         };
         Some(if_expression(
-            current_token,
-            condition.unwrap(),
+            current_token.clone(),
+            self.span_from(&current_token),
+            condition,
             consequence,
             alternative,
         ))
@@ -365,13 +709,24 @@ impl Parser {
         let mut statements = Vec::new();
         self.save_next_token();
         while !self.is_finished() && !self.current_token_is(&PureTokenKind::RightBrace) {
-            let statement = self.parse_statement();
-            if let Some(statement) = statement {
-                statements.push(statement);
+            match self.parse_statement() {
+                Some(statement) => statements.push(statement),
+                None => {
+                    self.synchronize();
+                    //statement broken right at the end of the block
+                    if self.current_token_is(&PureTokenKind::RightBrace) {
+                        break;
+                    }
+                }
             }
             self.save_next_token();
         }
+        if !self.current_token_is(&PureTokenKind::RightBrace) {
+            let error = ParseError::unexpected(&[PureTokenKind::RightBrace], &self.current_token);
+            self.errors.push(error);
+        }
         Statement::Block {
+            span: self.span_from(&current_token),
             token: current_token,
             statements: Rc::new(statements),
         }
@@ -379,20 +734,68 @@ impl Parser {
 
     fn parse_function_expression(&mut self) -> Option<Expression> {
         let current_token = self.current_token.clone();
+        let generator = self.peek_token_is(&PureTokenKind::Asterisk);
+        if generator {
+            self.save_next_token();
+        }
+        self.parse_function_literal(current_token, generator)
+    }
+
+    fn parse_function_literal(
+        &mut self,
+        current_token: Rc<Token>,
+        generator: bool,
+    ) -> Option<Expression> {
         if !self.expect_peek_and_move_into(&PureTokenKind::LeftParen) {
             return None;
         }
-        let parameters = self.parse_function_parameters();
+        let (parameters, annotations) = self.parse_function_parameters()?;
         if !self.expect_peek_and_move_into(&PureTokenKind::RightParen) {
-            self.errors
-                .push("Expected right parenthesis after function parameters".to_string());
             return None;
         }
+        let result = if self.peek_token_is(&PureTokenKind::Arrow) {
+            self.save_next_token();
+            self.save_next_token();
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
         if !self.expect_peek_and_move_into(&PureTokenKind::LeftBrace) {
             return None;
         }
+        let outer = (self.yield_allowed, self.yields);
+        (self.yield_allowed, self.yields) = (generator, 0);
         let body = self.parse_block_statement();
-        Some(function_literal(current_token, Rc::new(parameters), body))
+        (self.yield_allowed, self.yields) = outer;
+        let span = self.span_from(&current_token);
+        let function = function_literal(current_token, span, Rc::new(parameters), body, generator);
+        if result.is_none() && annotations.iter().all(Option::is_none) {
+            return Some(function);
+        }
+        let signature = Signature {
+            parameters: annotations,
+            result,
+        };
+        Some(match function {
+            Expression::FunctionLiteral {
+                token,
+                span,
+                parameters,
+                body,
+                name,
+                generator,
+                ..
+            } => Expression::FunctionLiteral {
+                token,
+                span,
+                parameters,
+                body,
+                name,
+                generator,
+                signature: Some(Rc::new(signature)),
+            },
+            other => other,
+        })
     }
 
     fn parse_macro_expression(&mut self) -> Option<Expression> {
@@ -400,206 +803,144 @@ impl Parser {
         if !self.expect_peek_and_move_into(&PureTokenKind::LeftParen) {
             return None;
         }
-        let parameters = self.parse_function_parameters();
+        let (parameters, _) = self.parse_function_parameters()?;
         if !self.expect_peek_and_move_into(&PureTokenKind::RightParen) {
-            self.errors
-                .push("Expected right parenthesis after function parameters".to_string());
             return None;
         }
         if !self.expect_peek_and_move_into(&PureTokenKind::LeftBrace) {
             return None;
         }
         let body = self.parse_block_statement();
-        Some(macro_literal(current_token, Rc::new(parameters), body))
+        let span = self.span_from(&current_token);
+        Some(macro_literal(
+            current_token,
+            span,
+            Rc::new(parameters),
+            body,
+        ))
     }
 
-    fn parse_function_parameters(&mut self) -> Vec<Expression> {
-        if self.peek_token_is(&PureTokenKind::RightParen) {
-            return vec![];
-        }
-        let mut arguments: Vec<Expression> = vec![];
-        while !(self.is_finished() || self.current_token_is(&PureTokenKind::RightParen)) {
-            if self.expect_peek_and_move_into(&PureTokenKind::Identifier) {
-                let identifier = identifier(self.current_token.clone());
-                arguments.push(identifier);
-            } else {
-                self.errors.push(format!(
-                    "Expected identifier, got {:?}",
-                    self.current_token.kind
-                ));
+    fn parse_function_parameters(
+        &mut self,
+    ) -> Option<(Vec<Expression>, Vec<Option<TypeAnnotation>>)> {
+        let mut arguments = vec![];
+        let mut annotations = vec![];
+        while !self.peek_token_is(&PureTokenKind::RightParen) {
+            if !self.expect_peek_and_move_into(&PureTokenKind::Identifier) {
+                return None;
             }
+            arguments.push(identifier(self.current_token.clone()));
+            annotations.push(self.parse_optional_annotation()?);
             if self.peek_token_is(&PureTokenKind::Comma) {
                 self.save_next_token();
-            } else if self.peek_token_is(&PureTokenKind::RightParen) {
-                break;
-            } else {
-                self.errors.push(format!(
-                    "Expected comma or right parenthesis, got {:?}",
-                    self.peek_token.as_ref().unwrap().kind
-                ));
-                break;
+            } else if !self.peek_token_is(&PureTokenKind::RightParen) {
+                self.peek_error(&[PureTokenKind::Comma, PureTokenKind::RightParen]);
+                return None;
             }
         }
-        arguments
+        Some((arguments, annotations))
     }
 
-    fn parse_call_expression(&mut self, left_exp: Expression) -> Option<Expression> {
+    fn parse_call_expression(&mut self, left_exp: Expression, start: &Token) -> Option<Expression> {
         let current_token = self.current_token.clone();
-        let arguments = self.parse_expression_list(&PureTokenKind::RightParen);
+        let arguments = self.parse_expression_list(&PureTokenKind::RightParen)?;
         Some(Expression::Call {
             token: current_token,
+            span: self.span_from(start),
             function: Box::new(left_exp),
             arguments,
         })
     }
 
-    fn parse_expression_list(&mut self, ending: &PureTokenKind) -> Vec<Expression> {
-        if self.peek_token_is(ending) {
-            self.save_next_token();
-            return vec![];
-        }
-        let mut arguments: Vec<Expression> = vec![];
-        self.save_next_token();
-        arguments.push(
-            self.parse_expression(Precedence::Lowest)
-                .expect("Expected expression after left parenthesis"),
-        );
-        while self.peek_token_is(&PureTokenKind::Comma) {
-            self.save_next_token();
+    //`ending` becomes the current token
+    fn parse_expression_list(&mut self, ending: &PureTokenKind) -> Option<Vec<Expression>> {
+        let mut elements = vec![];
+        while !self.peek_token_is(ending) {
             self.save_next_token();
-            if let Some(argument) = self.parse_expression(Precedence::Lowest) {
-                arguments.push(argument);
-            } else {
-                self.errors
-                    .push("Expected expression after comma".to_string());
+            elements.push(self.parse_expression(Precedence::LOWEST)?);
+            if self.peek_token_is(&PureTokenKind::Comma) {
+                self.save_next_token();
+            } else if !self.peek_token_is(ending) {
+                self.peek_error(&[PureTokenKind::Comma, *ending]);
+                return None;
             }
         }
-        if !self.expect_peek_and_move_into(ending) {
-            self.errors
-                .push("Expected right parenthesis after call arguments".to_string());
-        }
-        arguments
+        self.save_next_token();
+        Some(elements)
     }
 
     fn parse_array_literal(&mut self) -> Option<Expression> {
-        let elements = self.parse_expression_list(&PureTokenKind::RightBracket);
+        let start = self.current_token.clone();
+        let elements = self.parse_expression_list(&PureTokenKind::RightBracket)?;
         let current_token = self.current_token.clone();
         Some(Expression::ArrayLiteral {
             token: current_token,
+            span: self.span_from(&start),
+            elements,
+        })
+    }
+
+    fn parse_set_literal(&mut self) -> Option<Expression> {
+        let token = self.current_token.clone();
+        let elements = self.parse_expression_list(&PureTokenKind::RightBrace)?;
+        Some(Expression::SetLiteral {
+            span: self.span_from(&token),
+            token,
             elements,
         })
     }
 
-    fn parse_index_expression(&mut self, left_exp: Expression) -> Option<Expression> {
+    fn parse_index_expression(
+        &mut self,
+        left_exp: Expression,
+        start: &Token,
+    ) -> Option<Expression> {
         self.save_next_token();
-        let index = self.parse_expression(Precedence::Lowest);
-        if index.is_none() {
-            return None;
-        }
+        let index = self.parse_expression(Precedence::LOWEST)?;
         if !self.expect_peek_and_move_into(&PureTokenKind::RightBracket) {
-            self.errors
-                .push("Expected right bracket after index expression".to_string());
             return None;
         }
-        return Some(Expression::Index {
+        Some(Expression::Index {
             token: self.current_token.clone(),
+            span: self.span_from(start),
             array: Box::new(left_exp),
-            index: Box::new(index.unwrap()),
-        });
+            index: Box::new(index),
+        })
     }
 
     fn parse_map_literal(&mut self) -> Option<Expression> {
-        // empty map literal } immediately after {
-        if self.peek_token_is(&PureTokenKind::RightBrace) {
+        let start = self.current_token.clone();
+        let mut elements = vec![];
+        while !self.peek_token_is(&PureTokenKind::RightBrace) {
             self.save_next_token();
-            return Some(Expression::MapLiteral {
-                token: self.current_token.clone(),
-                elements: vec![],
-            });
-        }
-        let mut expresssions: Vec<(Expression, Expression)> = vec![];
-
-        while !self.is_finished() && !self.peek_token_is(&PureTokenKind::RightBrace) {
-            self.save_next_token();
-            if self.peek_token_is(&PureTokenKind::RightBrace) {
-                self.save_next_token();
-                break;
-            }
-            let key = self.parse_expression(Precedence::Lowest);
+            let key = self.parse_expression(Precedence::LOWEST)?;
             if !self.expect_peek_and_move_into(&PureTokenKind::Collon) {
                 return None;
             }
             self.save_next_token();
-            let value = self.parse_expression(Precedence::Lowest);
-            if value.is_none() {
-                self.errors
-                    .push("Expected expression after colon in map literal".to_string());
-                return None;
-            }
-
-            expresssions.push((key.unwrap(), value.unwrap()));
-            if self.is_finished() {
-                self.errors
-                    .push("Unexpected end of input in map literal".to_string());
-                return None;
-            }
-            if self.peek_token_is(&PureTokenKind::RightBrace) {
+            let value = self.parse_expression(Precedence::LOWEST)?;
+            elements.push((key, value));
+            if self.peek_token_is(&PureTokenKind::Comma) {
                 self.save_next_token();
-                break;
-            }
-            if !self.expect_peek_and_move_into(&PureTokenKind::Comma) {
-                self.errors
-                    .push("Expected comma after map entry".to_string());
+            } else if !self.peek_token_is(&PureTokenKind::RightBrace) {
+                self.peek_error(&[PureTokenKind::Comma, PureTokenKind::RightBrace]);
                 return None;
             }
         }
-        return Some(Expression::MapLiteral {
+        self.save_next_token();
+        Some(Expression::MapLiteral {
             token: self.current_token.clone(),
-            elements: expresssions,
-        });
-    }
-}
-
-fn token_into_operator(token: &Token) -> Option<InfixOperatorType> {
-    let pure_token_kind: PureTokenKind = (&token.kind).into();
-    match pure_token_kind {
-        PureTokenKind::Plus => Some(InfixOperatorType::Plus),
-        PureTokenKind::Minus => Some(InfixOperatorType::Minus),
-        PureTokenKind::Slash => Some(InfixOperatorType::Divide),
-        PureTokenKind::Asterisk => Some(InfixOperatorType::Multiply),
-        PureTokenKind::Equal => Some(InfixOperatorType::Equal),
-        PureTokenKind::Inequal => Some(InfixOperatorType::NotEqual),
-        PureTokenKind::LessThen => Some(InfixOperatorType::LessThan),
-        PureTokenKind::GreaterThen => Some(InfixOperatorType::GreaterThan),
-        _ => None,
+            span: self.span_from(&start),
+            elements,
+        })
     }
 }
 
-fn precedence_from(token: &Token) -> Precedence {
-    let pure_token_kind: PureTokenKind = (&token.kind).into();
-    match pure_token_kind {
-        PureTokenKind::Plus => Precedence::Sum,
-        PureTokenKind::Minus => Precedence::Sum,
-        PureTokenKind::Slash => Precedence::Product,
-        PureTokenKind::Asterisk => Precedence::Product,
-        PureTokenKind::Equal => Precedence::Equals,
-        PureTokenKind::Inequal => Precedence::Equals,
-        PureTokenKind::LessThen => Precedence::LessThan,
-        PureTokenKind::GreaterThen => Precedence::LessThan,
-        PureTokenKind::LeftParen => Precedence::Call,
-        PureTokenKind::LeftBracket => Precedence::Index,
-        _ => Precedence::Lowest,
+fn is_tuple_pattern(pattern: &Expression) -> bool {
+    match pattern {
+        Expression::TupleLiteral { elements, .. } => elements.iter().all(|element| {
+            matches!(element, Expression::Identifier(_)) || is_tuple_pattern(element)
+        }),
+        _ => false,
     }
 }
-
-#[derive(Debug, PartialEq, PartialOrd)]
-enum Precedence {
-    Lowest,
-    Equals,
-    LessThan,
-    Sum,
-    Product,
-    Prefix,
-    Call,
-    Index,
-}
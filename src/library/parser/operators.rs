@@ -0,0 +1,232 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    ast::{
+        expression::{Expression, InfixOperatorType, PrefixOperatorType, identifier},
+        statements::{Associativity, Fixity},
+    },
+    lines::SourceSpan,
+    tokens::{PureTokenKind, Token, TokenKind},
+};
+
+use super::Parser;
+
+//declared operators take 1 up to `PREFIX`, so prefix, calls and indexes bind tighter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Precedence(pub(crate) u8);
+
+impl Precedence {
+    pub(crate) const LOWEST: Precedence = Precedence(0);
+    pub(crate) const EQUALS: Precedence = Precedence(40);
+    pub(crate) const LESS_THAN: Precedence = Precedence(50);
+    pub(crate) const SUM: Precedence = Precedence(60);
+    pub(crate) const PRODUCT: Precedence = Precedence(70);
+    pub(crate) const PREFIX: Precedence = Precedence(80);
+    pub(crate) const CALL: Precedence = Precedence(90);
+    pub(crate) const INDEX: Precedence = Precedence(100);
+}
+
+//the only place where tokens and precedences of binary operators are
+const BINARY: [(PureTokenKind, InfixOperatorType, Precedence); 8] = [
+    (
+        PureTokenKind::Equal,
+        InfixOperatorType::Equal,
+        Precedence::EQUALS,
+    ),
+    (
+        PureTokenKind::Inequal,
+        InfixOperatorType::NotEqual,
+        Precedence::EQUALS,
+    ),
+    (
+        PureTokenKind::LessThen,
+        InfixOperatorType::LessThan,
+        Precedence::LESS_THAN,
+    ),
+    (
+        PureTokenKind::GreaterThen,
+        InfixOperatorType::GreaterThan,
+        Precedence::LESS_THAN,
+    ),
+    (
+        PureTokenKind::Plus,
+        InfixOperatorType::Plus,
+        Precedence::SUM,
+    ),
+    (
+        PureTokenKind::Minus,
+        InfixOperatorType::Minus,
+        Precedence::SUM,
+    ),
+    (
+        PureTokenKind::Asterisk,
+        InfixOperatorType::Multiply,
+        Precedence::PRODUCT,
+    ),
+    (
+        PureTokenKind::Slash,
+        InfixOperatorType::Divide,
+        Precedence::PRODUCT,
+    ),
+];
+
+const UNARY: [(PureTokenKind, PrefixOperatorType); 2] = [
+    (PureTokenKind::Negation, PrefixOperatorType::Bang),
+    (PureTokenKind::Minus, PrefixOperatorType::Minus),
+];
+
+pub(super) type PrefixParselet<'a> = fn(&mut Parser<'a>) -> Option<Expression>;
+
+pub(super) type InfixParselet = fn(Rc<Token>, Expression, Expression, SourceSpan) -> Expression;
+
+//the current token opens it, `start` is the first token of the operand
+pub(super) type PostfixParselet<'a> = fn(&mut Parser<'a>, Expression, &Token) -> Option<Expression>;
+
+#[derive(Clone, Copy)]
+pub(super) enum Rule<'a> {
+    Infix {
+        precedence: Precedence,
+        associativity: Associativity,
+        parse: InfixParselet,
+    },
+    Postfix {
+        precedence: Precedence,
+        parse: PostfixParselet<'a>,
+    },
+}
+
+impl Rule<'_> {
+    pub(super) fn precedence(&self) -> Precedence {
+        match self {
+            Rule::Infix { precedence, .. } | Rule::Postfix { precedence, .. } => *precedence,
+        }
+    }
+}
+
+//declared operators are added while parsing, from their declaration to the end of input
+pub(super) struct Grammar<'a> {
+    prefix: HashMap<PureTokenKind, PrefixParselet<'a>>,
+    rules: HashMap<PureTokenKind, Rule<'a>>,
+    declared: HashMap<String, Rule<'a>>,
+}
+
+impl<'a> Grammar<'a> {
+    pub(super) fn new() -> Self {
+        let mut grammar = Grammar {
+            prefix: HashMap::new(),
+            rules: HashMap::new(),
+            declared: HashMap::new(),
+        };
+        let prefix: [(PureTokenKind, PrefixParselet<'a>); 13] = [
+            (PureTokenKind::Identifier, Parser::parse_identifier),
+            (PureTokenKind::Integer, Parser::parse_integer_literal),
+            (PureTokenKind::True, Parser::parse_boolean),
+            (PureTokenKind::False, Parser::parse_boolean),
+            (PureTokenKind::StringLiteral, Parser::parse_string_literal),
+            (PureTokenKind::Symbol, Parser::parse_symbol_literal),
+            (PureTokenKind::LeftParen, Parser::parse_grouped_expression),
+            (PureTokenKind::If, Parser::parse_if_expression),
+            (PureTokenKind::Function, Parser::parse_function_expression),
+            (PureTokenKind::Macro, Parser::parse_macro_expression),
+            (PureTokenKind::LeftBracket, Parser::parse_array_literal),
+            (PureTokenKind::LeftBrace, Parser::parse_map_literal),
+            (PureTokenKind::SetStart, Parser::parse_set_literal),
+        ];
+        grammar.prefix.extend(prefix);
+        for (kind, _) in UNARY {
+            grammar.prefix.insert(kind, Parser::parse_prefix_expression);
+        }
+        for (kind, _, precedence) in BINARY {
+            let rule = Rule::Infix {
+                precedence,
+                associativity: Associativity::Left,
+                parse: binary,
+            };
+            grammar.rules.insert(kind, rule);
+        }
+        //it easier to act like calls and indexes are operators after their operand
+        let postfix: [(PureTokenKind, Precedence, PostfixParselet<'a>); 2] = [
+            (
+                PureTokenKind::LeftParen,
+                Precedence::CALL,
+                Parser::parse_call_expression,
+            ),
+            (
+                PureTokenKind::LeftBracket,
+                Precedence::INDEX,
+                Parser::parse_index_expression,
+            ),
+        ];
+        for (kind, precedence, parse) in postfix {
+            grammar
+                .rules
+                .insert(kind, Rule::Postfix { precedence, parse });
+        }
+        grammar
+    }
+
+    pub(super) fn prefix(&self, kind: &TokenKind) -> Option<PrefixParselet<'a>> {
+        self.prefix.get(&PureTokenKind::from(kind)).copied()
+    }
+
+    pub(super) fn rule(&self, kind: &TokenKind) -> Option<Rule<'a>> {
+        match kind {
+            TokenKind::Operator(operator) => self.declared.get(operator).copied(),
+            kind => self.rules.get(&PureTokenKind::from(kind)).copied(),
+        }
+    }
+
+    pub(super) fn declare(&mut self, operator: &str, fixity: Fixity) {
+        let rule = Rule::Infix {
+            precedence: Precedence(fixity.precedence),
+            associativity: fixity.associativity,
+            parse: declared,
+        };
+        self.declared.insert(operator.to_string(), rule);
+    }
+}
+
+fn binary(token: Rc<Token>, left: Expression, right: Expression, span: SourceSpan) -> Expression {
+    let operator = BINARY
+        .iter()
+        .find(|(kind, ..)| *kind == PureTokenKind::from(&token.kind))
+        .map(|(_, operator, _)| operator.clone())
+        .expect("Only binary operators have this parselet");
+    Expression::Infix {
+        token,
+        span,
+        left: Box::new(left),
+        operator,
+        right: Box::new(right),
+    }
+}
+
+//`a <+> b` is the call `<+>(a, b)`
+fn declared(token: Rc<Token>, left: Expression, right: Expression, span: SourceSpan) -> Expression {
+    let name = Token::at(
+        token.source_span(),
+        TokenKind::Identifier(token.kind.literal()),
+    );
+    Expression::Call {
+        token,
+        span,
+        function: Box::new(identifier(Rc::new(name))),
+        arguments: vec![left, right],
+    }
+}
+
+pub(super) fn prefix_operator(kind: &TokenKind) -> Option<PrefixOperatorType> {
+    UNARY
+        .iter()
+        .find(|(unary, _)| *unary == PureTokenKind::from(kind))
+        .map(|(_, operator)| operator.clone())
+}
+
+//printing uses it to leave out parentheses
+pub(crate) fn operator_precedence(operator: &InfixOperatorType) -> Precedence {
+    BINARY
+        .iter()
+        .find(|(_, binary, _)| binary == operator)
+        .map(|(.., precedence)| *precedence)
+        .expect("Every operator is in the table")
+}
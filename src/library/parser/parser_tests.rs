@@ -1,12 +1,13 @@
 use core::panic;
 
-use super::Parser;
+use super::{ParseError, Parser};
 use crate::ast::expression::InfixOperatorType;
 use crate::ast::{
     expression::{Expression, PrefixOperatorType},
-    statements::Statement,
+    statements::{Associativity, Statement},
 };
-use crate::tokens::TokenKind;
+use crate::lines::{SourceSpan, TextPosition, TokenPosition};
+use crate::tokens::{PureTokenKind, TokenKind};
 use crate::{join_collection, print_bash_error};
 #[macro_export]
 macro_rules! check_expression_value {
@@ -70,6 +71,94 @@ fn return_parsing() {
     assert_eq!(program.statements[2].to_string(), "return 838383");
 }
 
+#[test]
+fn assign_parsing() {
+    let input = r#"
+    x = 5;
+    y = x + 1
+    x == y
+    "#;
+    let mut parser = Parser::from_string(input);
+    let program = parser.parse_program();
+    check_parser_errors(&parser);
+    assert_eq!(program.statements.len(), 3);
+    assert_eq!(program.statements[0].to_string(), "x=5");
+    assert_eq!(program.statements[1].to_string(), "y=(x + 1)");
+    assert!(matches!(
+        program.statements[2],
+        Statement::AExpression { .. }
+    ));
+}
+
+#[test]
+fn function_declaration_parsing() {
+    let input = r#"
+    fn add(a, b) { a + b }
+    fn(x) { x };
+    "#;
+    let mut parser = Parser::from_string(input);
+    let program = parser.parse_program();
+    check_parser_errors(&parser);
+    assert_eq!(program.statements.len(), 2);
+    assert_eq!(program.statements[0].to_string(), "fn add(a, b){ (a + b) }");
+    match &program.statements[0] {
+        Statement::Function {
+            value: Expression::FunctionLiteral { name, .. },
+            ..
+        } => assert_eq!(name.as_deref(), Some("add")),
+        other => panic!("Expected function declaration got {other:?}"),
+    }
+    assert!(matches!(
+        program.statements[1],
+        Statement::AExpression { .. }
+    ));
+}
+
+#[test]
+fn generator_parsing() {
+    let input = r#"
+    fn* numbers(n) { yield n; if (n > 1) { yield* numbers(n - 1); } }
+    let g = fn*() { yield 1 };
+    "#;
+    let mut parser = Parser::from_string(input);
+    let program = parser.parse_program();
+    check_parser_errors(&parser);
+    assert_eq!(program.statements.len(), 2);
+    assert_eq!(
+        program.statements[0].to_string(),
+        "fn* numbers(n){ yield n\nif ((n > 1)){yield* numbers((n - 1))} }"
+    );
+    match &program.statements[1] {
+        Statement::Let {
+            value: Expression::FunctionLiteral { generator, .. },
+            ..
+        } => assert!(generator),
+        other => panic!("Expected let with generator got {other:?}"),
+    }
+}
+
+#[test]
+fn misplaced_yield_parsing() {
+    let inputs = [
+        "yield 1;",
+        "fn() { yield 1 }",
+        "fn*() { fn() { yield 1 } }",
+        "fn*() { if (true) { yield 1 } + 1 }",
+    ];
+    for input in inputs {
+        let mut parser = Parser::from_string(input);
+        parser.parse_program();
+        assert!(
+            parser.errors.iter().any(|error| matches!(
+                error,
+                ParseError::MisplacedYield { .. } | ParseError::YieldInExpression { .. }
+            )),
+            "Expected yield error for {input}, got {:?}",
+            parser.errors
+        );
+    }
+}
+
 #[test]
 fn parse_identifier() {
     let input = r#"
@@ -80,10 +169,7 @@ fn parse_identifier() {
     check_parser_errors(&parser);
     assert_eq!(program.statements.len(), 1);
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => {
+        Statement::AExpression { expression, .. } => {
             check_if_identifiers_equals(expression, "foobar".to_string());
         }
         _ => panic!("Expected ExpressionStatement"),
@@ -101,10 +187,7 @@ fn parse_number() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => {
+        Statement::AExpression { expression, .. } => {
             check_if_integer_literal_equals(expression, 5);
         }
         _ => panic!("Expected ExpressionStatement"),
@@ -121,10 +204,7 @@ fn parse_boolean() {
         assert_eq!(program.statements.len(), 1);
 
         match &program.statements[0] {
-            Statement::AExpression {
-                token: _,
-                expression,
-            } => {
+            Statement::AExpression { expression, .. } => {
                 check_if_boolean_literal_equals(expression, value);
             }
             _ => panic!("Expected ExpressionStatement"),
@@ -142,10 +222,7 @@ fn parse_string() {
         assert_eq!(program.statements.len(), 1);
 
         match &program.statements[0] {
-            Statement::AExpression {
-                token: _,
-                expression,
-            } => {
+            Statement::AExpression { expression, .. } => {
                 check_if_strings_equals(expression, value);
             }
             _ => panic!("Expected ExpressionStatement"),
@@ -166,14 +243,9 @@ fn parse_prefix() {
         assert_eq!(program.statements.len(), 1);
 
         match &program.statements[0] {
-            Statement::AExpression {
-                token: _,
-                expression,
-            } => match expression {
+            Statement::AExpression { expression, .. } => match expression {
                 Expression::PrefixOperator {
-                    token: _,
-                    operator,
-                    right,
+                    operator, right, ..
                 } => {
                     assert_eq!(operator, &expected_operator);
                     check_if_integer_literal_equals(right, expected_value);
@@ -207,7 +279,7 @@ fn check_if_boolean_literal_equals(expression: &Expression, expected_value: bool
     }
 }
 
-fn check_if_integer_literal_equals(expression: &Expression, expected_value: u32) {
+fn check_if_integer_literal_equals(expression: &Expression, expected_value: i64) {
     check_expression_value!(expression, IntegerLiteral, Integer, expected_value);
 }
 
@@ -230,15 +302,12 @@ fn parse_infix_expression() {
         assert_eq!(program.statements.len(), 1);
 
         match &program.statements[0] {
-            Statement::AExpression {
-                token: _,
-                expression,
-            } => match expression {
+            Statement::AExpression { expression, .. } => match expression {
                 Expression::Infix {
-                    token: _,
                     left,
                     operator,
                     right,
+                    ..
                 } => {
                     assert_eq!(operator, &expected_operator);
                     check_if_integer_literal_equals(left, expected_left);
@@ -266,15 +335,12 @@ fn parse_infix_expression_boolean() {
         assert_eq!(program.statements.len(), 1);
 
         match &program.statements[0] {
-            Statement::AExpression {
-                token: _,
-                expression,
-            } => match expression {
+            Statement::AExpression { expression, .. } => match expression {
                 Expression::Infix {
-                    token: _,
                     left,
                     operator,
                     right,
+                    ..
                 } => {
                     assert_eq!(operator, &expected_operator);
                     check_if_boolean_literal_equals(left, expected_left);
@@ -327,24 +393,22 @@ fn parse_if_condition() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        Statement::AExpression { expression, .. } => match expression {
             Expression::AIf {
                 consequence,
                 token,
                 condition,
                 alternative,
+                ..
             } => {
                 assert!(alternative.is_none());
                 assert_eq!(token.kind, TokenKind::If);
                 match condition.as_ref() {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         assert_eq!(*operator, InfixOperatorType::LessThan);
                         check_if_identifiers_equals(&left, "x".to_string());
@@ -375,23 +439,21 @@ fn parse_if_else_condition() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        Statement::AExpression { expression, .. } => match expression {
             Expression::AIf {
                 consequence,
                 token,
                 condition,
                 alternative,
+                ..
             } => {
                 assert_eq!(token.kind, TokenKind::If);
                 match condition.as_ref() {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         assert_eq!(*operator, InfixOperatorType::LessThan);
                         check_if_identifiers_equals(&left, "x".to_string());
@@ -404,10 +466,7 @@ fn parse_if_else_condition() {
                         Statement::Block { statements, .. } => {
                             assert_eq!(statements.len(), 1);
                             match &statements[0] {
-                                Statement::AExpression {
-                                    token: _,
-                                    expression,
-                                } => {
+                                Statement::AExpression { expression, .. } => {
                                     check_if_identifiers_equals(expression, "y".to_string());
                                 }
                                 _ => panic!("Expected ExpressionStatement in alternative"),
@@ -440,15 +499,14 @@ fn parse_function_literal() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        Statement::AExpression { expression, .. } => match expression {
             Expression::FunctionLiteral {
-                token: _,
                 parameters,
                 body,
                 name: _,
+                generator: _,
+                signature: _,
+                ..
             } => {
                 assert_eq!(parameters.len(), 2);
                 assert_eq!(parameters[0].to_string(), "x");
@@ -458,18 +516,15 @@ fn parse_function_literal() {
                     _ => panic!("Expected BlockStatement in function body"),
                 };
                 let expression = match block {
-                    Statement::AExpression {
-                        token: _,
-                        expression,
-                    } => expression,
+                    Statement::AExpression { expression, .. } => expression,
                     _ => panic!("Expected ExpressionStatement in function body"),
                 };
                 match expression {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         assert_eq!(*operator, InfixOperatorType::Plus);
                         check_if_identifiers_equals(left, "x".to_string());
@@ -493,23 +548,20 @@ fn parse_call_expression() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        Statement::AExpression { expression, .. } => match expression {
             Expression::Call {
-                token: _,
                 function,
                 arguments,
+                ..
             } => {
                 assert_eq!(function.to_string(), "add");
                 assert_eq!(arguments.len(), 2);
                 match &arguments[0] {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         assert_eq!(*operator, InfixOperatorType::Plus);
                         check_if_identifiers_equals(left, "x".to_string());
@@ -534,14 +586,11 @@ fn parse_call_expression_with_literals() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        Statement::AExpression { expression, .. } => match expression {
             Expression::Call {
-                token: _,
                 function,
                 arguments,
+                ..
             } => {
                 assert_eq!(function.to_string(), "add");
                 assert_eq!(arguments.len(), 2);
@@ -563,19 +612,16 @@ fn parse_array_literal() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
-            Expression::ArrayLiteral { token: _, elements } => {
+        Statement::AExpression { expression, .. } => match expression {
+            Expression::ArrayLiteral { elements, .. } => {
                 assert_eq!(elements.len(), 3);
                 check_if_integer_literal_equals(&elements[0], 1);
                 match &elements[1] {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         assert_eq!(*operator, InfixOperatorType::Multiply);
                         check_if_integer_literal_equals(left, 2);
@@ -585,10 +631,10 @@ fn parse_array_literal() {
                 }
                 match &elements[2] {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         assert_eq!(*operator, InfixOperatorType::Minus);
                         check_if_integer_literal_equals(left, 3);
@@ -612,22 +658,15 @@ fn parse_index_expression() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
-            Expression::Index {
-                token: _,
-                array,
-                index,
-            } => {
+        Statement::AExpression { expression, .. } => match expression {
+            Expression::Index { array, index, .. } => {
                 assert_eq!(array.to_string(), "myArray");
                 match index.as_ref() {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         assert_eq!(*operator, InfixOperatorType::Plus);
                         check_if_integer_literal_equals(left, 1);
@@ -650,11 +689,8 @@ fn parse_map_literal() {
     check_parser_errors(&parser);
     assert_eq!(program.statements.len(), 1);
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
-            Expression::MapLiteral { token: _, elements } => {
+        Statement::AExpression { expression, .. } => match expression {
+            Expression::MapLiteral { elements, .. } => {
                 assert_eq!(elements.len(), 3);
                 for (key, value) in elements {
                     match key {
@@ -664,10 +700,10 @@ fn parse_map_literal() {
                                 "two" => check_if_integer_literal_equals(value, 2),
                                 "three" => match value {
                                     Expression::Infix {
-                                        token: _,
                                         left,
                                         operator,
                                         right,
+                                        ..
                                     } => {
                                         assert_eq!(*operator, InfixOperatorType::Plus);
                                         check_if_integer_literal_equals(left, 3);
@@ -697,11 +733,8 @@ fn parse_empty_map_literal() {
     check_parser_errors(&parser);
     assert_eq!(program.statements.len(), 1);
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
-            Expression::MapLiteral { token: _, elements } => {
+        Statement::AExpression { expression, .. } => match expression {
+            Expression::MapLiteral { elements, .. } => {
                 assert_eq!(elements.len(), 0);
             }
             _ => panic!("Expected MapLiteral, got {:?}", expression),
@@ -719,17 +752,10 @@ fn parse_getting_by_key() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
-            Expression::Index {
-                token: _,
-                array,
-                index,
-            } => {
+        Statement::AExpression { expression, .. } => match expression {
+            Expression::Index { array, index, .. } => {
                 match array.as_ref() {
-                    Expression::MapLiteral { token: _, elements } => {
+                    Expression::MapLiteral { elements, .. } => {
                         assert_eq!(elements.len(), 2);
                     }
                     _ => panic!("Expected MapLiteral for array, got {:?}", array),
@@ -751,14 +777,9 @@ fn parse_macro() {
     assert_eq!(program.statements.len(), 1);
 
     match &program.statements[0] {
-        Statement::AExpression {
-            token: _,
-            expression,
-        } => match expression {
+        Statement::AExpression { expression, .. } => match expression {
             Expression::MacroLiteral {
-                token: _,
-                parameters,
-                body,
+                parameters, body, ..
             } => {
                 assert_eq!(parameters.len(), 2);
                 assert_eq!(parameters[0].to_string(), "x");
@@ -768,18 +789,15 @@ fn parse_macro() {
                     _ => panic!("Expected BlockStatement in function body"),
                 };
                 let expression = match block {
-                    Statement::AExpression {
-                        token: _,
-                        expression,
-                    } => expression,
+                    Statement::AExpression { expression, .. } => expression,
                     _ => panic!("Expected ExpressionStatement in function body"),
                 };
                 match expression {
                     Expression::Infix {
-                        token: _,
                         left,
                         operator,
                         right,
+                        ..
                     } => {
                         assert_eq!(*operator, InfixOperatorType::Plus);
                         check_if_identifiers_equals(left, "x".to_string());
@@ -805,15 +823,18 @@ fn parse_named_function_literal() {
     let statement = &program.statements[0];
     match statement {
         Statement::Let {
-            token: _,
             name: _,
             value,
+            annotation: _,
+            ..
         } => match value {
             Expression::FunctionLiteral {
-                token: _,
                 parameters: _,
                 body: _,
                 name,
+                generator: _,
+                signature: _,
+                ..
             } => match name {
                 Some(x) => assert_eq!("myFunction", x),
                 None => panic!("Function was not named"),
@@ -832,3 +853,404 @@ fn check_parser_errors(parser: &Parser) {
         );
     }
 }
+
+#[test]
+fn tuple_parsing() {
+    let tests = [
+        ("(1, 2)", "(1, 2)"),
+        ("(1,)", "(1,)"),
+        ("()", "()"),
+        ("(1)", "1"),
+        ("(1 + 2, (3, 4),)", "((1 + 2), (3, 4))"),
+        ("let (a, (b, c)) = t;", "let (a, (b, c))=t"),
+    ];
+    for (input, expected) in tests {
+        let mut parser = Parser::from_string(input);
+        let program = parser.parse_program();
+        check_parser_errors(&parser);
+        assert_eq!(program.to_string(), expected);
+    }
+}
+
+#[test]
+fn invalid_destructuring_parsing() {
+    let mut parser = Parser::from_string("let (a, 1) = t;");
+    parser.parse_program();
+    assert!(
+        parser
+            .errors
+            .iter()
+            .any(|error| matches!(error, ParseError::InvalidPattern { .. })),
+        "Got {:?}",
+        parser.errors
+    );
+}
+
+#[test]
+fn set_parsing() {
+    let tests = [
+        ("#{}", "#{}"),
+        ("#{1}", "#{1}"),
+        ("#{1 + 2, \"a\", (1, 2)}", "#{(1 + 2), a, (1, 2)}"),
+    ];
+    for (input, expected) in tests {
+        let mut parser = Parser::from_string(input);
+        let program = parser.parse_program();
+        check_parser_errors(&parser);
+        assert_eq!(program.to_string(), expected);
+    }
+}
+
+#[test]
+fn symbol_parsing() {
+    let tests = [
+        ("f(:ok)", "f(:ok)"),
+        ("{:ok: 1, a: :b}", "{:ok: 1, a: :b}"),
+        ("x == :done", "(x == :done)"),
+    ];
+    for (input, expected) in tests {
+        let mut parser = Parser::from_string(input);
+        let program = parser.parse_program();
+        check_parser_errors(&parser);
+        assert_eq!(program.to_string(), expected);
+    }
+}
+
+#[test]
+fn decorated_let_parsing() {
+    let tests = [
+        ("@memoize let f = fn(x) { x };", "let f=@memoize fn(x){ x }"),
+        (
+            "@trace(1) @memoize let f = fn(x) { x };",
+            "let f=@trace(1) @memoize fn(x){ x }",
+        ),
+    ];
+    for (input, expected) in tests {
+        let mut parser = Parser::from_string(input);
+        let program = parser.parse_program();
+        check_parser_errors(&parser);
+        assert_eq!(program.to_string(), expected);
+    }
+    let mut parser = Parser::from_string("@memoize fn(x) { x };");
+    parser.parse_program();
+    assert!(
+        parser.errors.iter().any(|error| matches!(
+            error,
+            ParseError::UnexpectedToken { expected, .. } if expected == &[PureTokenKind::Let]
+        )),
+        "Got {:?}",
+        parser.errors
+    );
+}
+
+#[test]
+fn type_annotation_parsing() {
+    let tests = [
+        ("let x: Int = 1;", "let x: Int=1"),
+        (
+            "let (a, b): (Int, String) = t;",
+            "let (a, b): (Int, String)=t",
+        ),
+        (
+            "let f = fn(a: String, b: [Int]) -> Bool { true };",
+            "let f=fn(a: String, b: [Int]) -> Bool{ true }",
+        ),
+        ("fn g(a, b: #{Symbol}) { a }", "fn g(a, b: #{Symbol}){ a }"),
+        (
+            "let m: {String: (Int,)} = {};",
+            "let m: {String: (Int,)}={}",
+        ),
+        (
+            "let h: fn(Int, Any) -> fn() -> Null = fn() -> Char { c };",
+            "let h: fn(Int, Any) -> fn() -> Null=fn() -> Char{ c }",
+        ),
+    ];
+    for (input, expected) in tests {
+        let mut parser = Parser::from_string(input);
+        let program = parser.parse_program();
+        check_parser_errors(&parser);
+        assert_eq!(program.to_string(), expected);
+    }
+}
+
+#[test]
+fn invalid_type_annotation_parsing() {
+    let mut parser = Parser::from_string("let x: Number = 1;");
+    parser.parse_program();
+    assert_eq!(
+        parser.errors[0].to_string(),
+        "(1,8)->(1,13): Unknown type Number"
+    );
+}
+
+#[test]
+fn parsing_without_trailing_separator() {
+    let mut parser = Parser::from_string("let a = 1; a");
+    let program = parser.parse_program();
+    check_parser_errors(&parser);
+    assert_eq!(program.to_string(), "let a=1\na");
+
+    let mut parser = Parser::from_string("");
+    assert!(parser.parse_program().statements.is_empty());
+    check_parser_errors(&parser);
+}
+
+#[test]
+fn parsing_from_reader() {
+    let source = "let add = fn(a, b) { a + b };\n".repeat(1_000) + "add(1, 2)";
+    let mut parser = Parser::from_reader(std::io::Cursor::new(source.into_bytes()));
+    let program = parser.parse_program();
+    check_parser_errors(&parser);
+    assert_eq!(program.statements.len(), 1_001);
+    assert_eq!(program.statements[1_000].to_string(), "add(1, 2)");
+}
+
+#[test]
+fn unfinished_program() {
+    let mut parser = Parser::from_string("1 + ");
+    parser.parse_program();
+    let end = TextPosition::new(1, 5);
+    assert_eq!(
+        parser.errors,
+        vec![ParseError::MissingExpression {
+            found: TokenKind::EndOfInput,
+            position: Some(TokenPosition::new(end, end)),
+        }]
+    );
+    assert_eq!(
+        parser.errors[0].to_string(),
+        "(1,5): Expected expression, got end of input"
+    );
+}
+
+#[test]
+fn trailing_comma_parsing() {
+    let tests = [
+        ("f(1, 2,)", "f(1, 2)"),
+        ("[1,]", "[1]"),
+        ("#{1, 2,}", "#{1, 2}"),
+        ("{\"a\": 1, \"b\": 2,}", "{a: 1, b: 2}"),
+        ("fn(a, b,) { a }", "fn(a, b){ a }"),
+        ("macro(a,) { a }", "macro(a){ a }"),
+    ];
+    for (input, expected) in tests {
+        let mut parser = Parser::from_string(input);
+        let program = parser.parse_program();
+        check_parser_errors(&parser);
+        assert_eq!(program.to_string(), expected);
+    }
+}
+
+#[test]
+fn recovering_from_errors() {
+    let mut parser = Parser::from_string("let a = ; let b = 1 +; let c = 3; c");
+    let program = parser.parse_program();
+    assert_eq!(parser.errors.len(), 2, "{:?}", parser.errors);
+    assert_eq!(
+        parser.errors[0].to_string(),
+        "(1,9): Expected expression, got `;`"
+    );
+    assert_eq!(program.to_string(), "let c=3\nc");
+
+    let mut parser = Parser::from_string("let f = fn() { x + }; let y = 1; y");
+    let program = parser.parse_program();
+    assert_eq!(parser.errors.len(), 1, "{:?}", parser.errors);
+    assert_eq!(program.to_string(), "let f=fn(){  }\nlet y=1\ny");
+}
+
+#[test]
+fn reporting_expected_tokens() {
+    let tests = [
+        ("let = 1", "(1,5): Expected identifier or `(`, got `=`"),
+        ("let a 1", "(1,7): Expected `=`, got integer 1"),
+        ("f(1 2)", "(1,5): Expected `,` or `)`, got integer 2"),
+        ("{\"a\" 1}", "(1,6): Expected `:`, got integer 1"),
+        ("let f: = 1", "(1,8): Expected type, got `=`"),
+    ];
+    for (input, expected) in tests {
+        let mut parser = Parser::from_string(input);
+        parser.parse_program();
+        assert!(!parser.errors.is_empty(), "for {}", input);
+        assert_eq!(parser.errors[0].to_string(), expected, "for {}", input);
+    }
+}
+
+#[test]
+fn reporting_invalid_tokens() {
+    let problem = "Invalid token: Number is out of range, largest allowed is 9223372036854775807";
+    let tests = [
+        ("0xFFFFFFFFFFFFFFFFFF", "(1,1)->(1,20)"),
+        ("let a = 1; 0xFFFFFFFFFFFFFFFFFF", "(1,12)->(1,31)"),
+    ];
+    for (input, position) in tests {
+        let mut parser = Parser::from_string(input);
+        parser.parse_program();
+        assert_eq!(
+            parser.errors[0].to_string(),
+            format!("{}: {}", position, problem),
+            "for {}",
+            input
+        );
+    }
+}
+
+#[test]
+fn unfinished_inputs_do_not_panic() {
+    let inputs = [
+        "let a = ",
+        "let a = ;",
+        "f(",
+        "[1,",
+        "{\"a\": }",
+        "#{1,",
+        "let f = @memoize fn(x) { x }",
+        "-",
+        "fn(a, ",
+        "if (a) {",
+        "let (a, 1) = b",
+        "a[",
+    ];
+    for input in inputs {
+        let mut parser = Parser::from_string(input);
+        parser.parse_program();
+        assert!(!parser.errors.is_empty(), "for {}", input);
+    }
+}
+
+#[test]
+fn spans_of_nodes() {
+    let source = "let x = (a + b) * -c;\nf(1, [2, 3])[1]\nif (x) { y } else { z }";
+    let mut parser = Parser::from_string(source);
+    let program = parser.parse_program();
+    check_parser_errors(&parser);
+    let text = |span: SourceSpan| span.text(source).unwrap_or_default();
+
+    let statements = &program.statements;
+    assert_eq!(text(statements[0].span()), "let x = (a + b) * -c;");
+    assert_eq!(text(statements[1].span()), "f(1, [2, 3])[1]");
+    assert_eq!(text(statements[2].span()), "if (x) { y } else { z }");
+
+    let Statement::Let { value, .. } = &statements[0] else {
+        panic!("Expected let, got {}", statements[0]);
+    };
+    let Expression::Infix { left, right, .. } = value else {
+        panic!("Expected infix, got {}", value);
+    };
+    assert_eq!(text(value.span()), "(a + b) * -c");
+    assert_eq!(text(left.span()), "a + b");
+    assert_eq!(text(right.span()), "-c");
+    let start = TextPosition::new(1, 9);
+    let end = TextPosition::new(1, 20);
+    assert_eq!(value.span().position, Some(TokenPosition::new(start, end)));
+
+    let Statement::AExpression {
+        expression: Expression::Index { array, .. },
+        ..
+    } = &statements[1]
+    else {
+        panic!("Expected index, got {}", statements[1]);
+    };
+    let Expression::Call { arguments, .. } = array.as_ref() else {
+        panic!("Expected call, got {}", array);
+    };
+    assert_eq!(text(array.span()), "f(1, [2, 3])");
+    assert_eq!(text(arguments[1].span()), "[2, 3]");
+}
+
+#[test]
+fn declared_operators_parsing() {
+    let declarations = "infix 60 <+> = add; infixr 75 ** = pow; infix 30 |> = apply;";
+    let inputs = [
+        ("a <+> b", "(a <+> b)"),
+        ("a <+> b + c", "((a <+> b) + c)"),
+        ("a + b <+> c * d", "((a + b) <+> (c * d))"),
+        ("a ** b ** c", "(a ** (b ** c))"),
+        ("a * b ** c", "(a * (b ** c))"),
+        ("-a ** f(b)[0]", "((-a) ** (f(b)[0]))"),
+        ("a |> f |> g", "((a |> f) |> g)"),
+        ("a == b |> f", "((a == b) |> f)"),
+        ("a<+>b < c", "((a <+> b) < c)"),
+        ("a <- b", "(a < (-b))"),
+    ];
+    for (input, expected) in inputs {
+        let source = format!("{} {}", declarations, input);
+        let mut parser = Parser::from_string(&source);
+        let program = parser.parse_program();
+        check_parser_errors(&parser);
+        assert_eq!(program.statements.len(), 4, "for {}", input);
+        assert_eq!(program.statements[3].to_string(), expected, "for {}", input);
+    }
+}
+
+#[test]
+fn operator_declaration_parsing() {
+    let mut parser = Parser::from_string("infixr 20 <|> = fn(a, b) { a <|> b }; 1 <|> 2");
+    let program = parser.parse_program();
+    check_parser_errors(&parser);
+    match &program.statements[0] {
+        Statement::Operator {
+            fixity,
+            name,
+            value,
+            ..
+        } => {
+            assert_eq!(fixity.precedence, 20);
+            assert_eq!(fixity.associativity, Associativity::Right);
+            assert_eq!(name.to_string(), "<|>");
+            assert_eq!(value.to_string(), "fn(a, b){ (a <|> b) }");
+        }
+        statement => panic!("Expected operator declaration, got {:?}", statement),
+    }
+    match &program.statements[1] {
+        Statement::AExpression {
+            expression:
+                Expression::Call {
+                    function,
+                    arguments,
+                    ..
+                },
+            ..
+        } => {
+            assert_eq!(function.to_string(), "<|>");
+            assert_eq!(join_collection!(arguments, ", "), "1, 2");
+        }
+        statement => panic!("Expected call of the operator, got {:?}", statement),
+    }
+    assert_eq!(
+        program.to_string(),
+        "infixr 20 <|> = fn(a, b){ (a <|> b) }\n(1 <|> 2)"
+    );
+    //identifiers named like the declaration keywords still work
+    let mut parser = Parser::from_string("let infix = 1; infix + 2");
+    parser.parse_program();
+    check_parser_errors(&parser);
+}
+
+#[test]
+fn invalid_operator_declarations() {
+    let tests = [
+        (
+            "infix 0 <+> = f",
+            "(1,7): Precedence of operator must be from 1 to 79, got 0",
+        ),
+        (
+            "infix 80 <+> = f",
+            "(1,7)->(1,8): Precedence of operator must be from 1 to 79, got 80",
+        ),
+        (
+            "infix 60 == = f",
+            "(1,10)->(1,11): Operator == is built in and cannot be declared",
+        ),
+        (
+            "infix 60 abc = f",
+            "(1,10)->(1,12): Expected operator, got identifier abc",
+        ),
+        ("infix 60 <+> f", "(1,14): Expected `=`, got identifier f"),
+    ];
+    for (input, expected) in tests {
+        let mut parser = Parser::from_string(input);
+        parser.parse_program();
+        assert!(!parser.errors.is_empty(), "for {}", input);
+        assert_eq!(parser.errors[0].to_string(), expected, "for {}", input);
+    }
+}
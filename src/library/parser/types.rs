@@ -0,0 +1,100 @@
+use crate::{
+    ast::types::TypeAnnotation,
+    tokens::{PureTokenKind, TokenKind},
+};
+
+use super::{ParseError, Parser};
+
+impl Parser<'_> {
+    //starts at the first token of the annotation and ends at its last
+    pub(super) fn parse_type(&mut self) -> Option<TypeAnnotation> {
+        let token = self.current_token.clone();
+        match &token.kind {
+            TokenKind::Identifier(name) => TypeAnnotation::named(name).or_else(|| {
+                self.errors.push(ParseError::UnknownType {
+                    name: name.clone(),
+                    position: token.context,
+                });
+                None
+            }),
+            TokenKind::LeftBracket => {
+                self.save_next_token();
+                let element = self.parse_type()?;
+                self.expect_peek_and_move_into(&PureTokenKind::RightBracket)
+                    .then(|| TypeAnnotation::Array(Box::new(element)))
+            }
+            TokenKind::SetStart => {
+                self.save_next_token();
+                let element = self.parse_type()?;
+                self.expect_peek_and_move_into(&PureTokenKind::RightBrace)
+                    .then(|| TypeAnnotation::Set(Box::new(element)))
+            }
+            TokenKind::LeftBrace => {
+                self.save_next_token();
+                let key = self.parse_type()?;
+                if !self.expect_peek_and_move_into(&PureTokenKind::Collon) {
+                    return None;
+                }
+                self.save_next_token();
+                let value = self.parse_type()?;
+                self.expect_peek_and_move_into(&PureTokenKind::RightBrace)
+                    .then(|| TypeAnnotation::Map(Box::new(key), Box::new(value)))
+            }
+            TokenKind::LeftParen => {
+                let elements = self.parse_type_list()?;
+                Some(TypeAnnotation::Tuple(elements))
+            }
+            TokenKind::Function => {
+                if !self.expect_peek_and_move_into(&PureTokenKind::LeftParen) {
+                    return None;
+                }
+                let parameters = self.parse_type_list()?;
+                if !self.expect_peek_and_move_into(&PureTokenKind::Arrow) {
+                    return None;
+                }
+                self.save_next_token();
+                let result = self.parse_type()?;
+                Some(TypeAnnotation::Function(parameters, Box::new(result)))
+            }
+            _ => {
+                self.errors.push(ParseError::MissingType {
+                    found: token.kind.clone(),
+                    position: token.context,
+                });
+                None
+            }
+        }
+    }
+
+    fn parse_type_list(&mut self) -> Option<Vec<TypeAnnotation>> {
+        let mut types = vec![];
+        if self.peek_token_is(&PureTokenKind::RightParen) {
+            self.save_next_token();
+            return Some(types);
+        }
+        loop {
+            self.save_next_token();
+            types.push(self.parse_type()?);
+            if self.peek_token_is(&PureTokenKind::Comma) {
+                self.save_next_token();
+                if self.peek_token_is(&PureTokenKind::RightParen) {
+                    self.save_next_token();
+                    return Some(types);
+                }
+            } else {
+                return self
+                    .expect_peek_and_move_into(&PureTokenKind::RightParen)
+                    .then_some(types);
+            }
+        }
+    }
+
+    pub(super) fn parse_optional_annotation(&mut self) -> Option<Option<TypeAnnotation>> {
+        if !self.peek_token_is(&PureTokenKind::Collon) {
+            return Some(None);
+        }
+        self.save_next_token();
+        self.save_next_token();
+        self.parse_type().map(Some)
+    }
+}
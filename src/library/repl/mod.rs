@@ -1,6 +1,7 @@
 use std::io::{self, BufRead};
 
 use crate::{
+    checker::check,
     evaluator::{define_macros, expand_macros},
     lexers::Lexer,
     object::new_environment,
@@ -27,6 +28,14 @@ pub fn start() {
             }
             continue;
         }
+        let diagnostics = check(&program);
+        if !diagnostics.is_empty() {
+            println!("Type errors found in the program:");
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic);
+            }
+            continue;
+        }
 
         println!("Parsed program: {}", program);
         let macro_defined = define_macros(program, macro_environemnt.clone());
@@ -1,21 +1,75 @@
 use std::fmt::Display;
 
-use crate::lines::TokenPosition;
+use crate::lines::{SourceSpan, Span, TokenPosition};
 
 #[derive(Debug)]
 pub struct Token {
     pub context: Option<TokenPosition>,
+    pub span: Option<Span>, //bytes of the source, missing for tokens made by macros
     pub kind: TokenKind,
+    pub layout: Option<Box<Layout>>, //only kept by lexer in lossless mode
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub trivia: Vec<Trivia>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trivia {
+    Whitespace(String),
+    Newline(String), // `\n` or `\r\n`
+    Comment(String), // `//` up to the end of line
+    Skipped(String), // characters which do not belong to any token
+}
+
+impl Trivia {
+    pub fn text(&self) -> &str {
+        match self {
+            Trivia::Whitespace(text)
+            | Trivia::Newline(text)
+            | Trivia::Comment(text)
+            | Trivia::Skipped(text) => text,
+        }
+    }
 }
 
 impl Token {
     pub(crate) fn new(context: TokenPosition, token_kind: TokenKind) -> Self {
         Self {
             context: Some(context),
+            span: None,
             kind: token_kind,
+            layout: None,
         }
     }
 
+    //placed where the node it belongs to was
+    pub(crate) fn at(span: SourceSpan, token_kind: TokenKind) -> Self {
+        Self {
+            context: span.position,
+            span: span.bytes,
+            kind: token_kind,
+            layout: None,
+        }
+    }
+
+    pub fn source_text(&self) -> String {
+        match &self.layout {
+            Some(layout) => {
+                let mut text: String = layout.trivia.iter().map(Trivia::text).collect();
+                text.push_str(&layout.text);
+                text
+            }
+            None => self.kind.literal(),
+        }
+    }
+
+    pub fn source_span(&self) -> SourceSpan {
+        SourceSpan::new(self.context, self.span)
+    }
+
     pub fn short(&self) -> String {
         self.kind.literal()
     }
@@ -23,8 +77,8 @@ impl Token {
     pub fn position(&self) -> (usize, usize) {
         if let Some(context) = &self.context {
             (
-                context.start.line_number.0.into(),
-                context.start.column_number.0.into(),
+                context.start.line_number.0 as usize,
+                context.start.column_number.0 as usize,
             )
         } else {
             (0, 0)
@@ -52,7 +106,9 @@ pub enum TokenKind {
     Invalid(String),
     Identifier(String),
     StringLiteral(String),
-    Integer(u32),
+    Symbol(String),
+    Integer(i64),
+    Operator(String), //declared by the script
     Minus,
 
     Comma,
@@ -61,6 +117,7 @@ pub enum TokenKind {
     RightParen,
     LeftBrace,
     RightBrace,
+    SetStart,
     LeftBracket,
     RightBracket,
 
@@ -84,6 +141,10 @@ pub enum TokenKind {
     Return,
     Collon,
     Macro,
+    Yield,
+    At,
+    Arrow,
+    EndOfInput,
 }
 
 impl TokenKind {
@@ -92,13 +153,16 @@ impl TokenKind {
             TokenKind::Invalid(s) => format!("Invalid({})", s),
             TokenKind::Identifier(s) => s.to_string(),
             TokenKind::StringLiteral(s) => s.to_string(),
+            TokenKind::Symbol(s) => format!(":{}", s),
             TokenKind::Integer(i) => i.to_string(),
+            TokenKind::Operator(s) => s.to_string(),
             TokenKind::Comma => ",".to_string(),
             TokenKind::Semicolon => ";".to_string(),
             TokenKind::LeftParen => "(".to_string(),
             TokenKind::RightParen => ")".to_string(),
             TokenKind::LeftBrace => "{".to_string(),
             TokenKind::RightBrace => "}".to_string(),
+            TokenKind::SetStart => "#{".to_string(),
             TokenKind::Function => "function".to_string(),
             TokenKind::Let => "let".to_string(),
             TokenKind::Assign => "=".to_string(),
@@ -120,6 +184,10 @@ impl TokenKind {
             TokenKind::RightBracket => "]".to_string(),
             TokenKind::Collon => ":".to_string(),
             TokenKind::Macro => "macro".to_string(),
+            TokenKind::Yield => "yield".to_string(),
+            TokenKind::At => "@".to_string(),
+            TokenKind::Arrow => "->".to_string(),
+            TokenKind::EndOfInput => "end of input".to_string(),
         }
     }
 }
@@ -135,13 +203,16 @@ impl From<&TokenKind> for PureTokenKind {
             TokenKind::Invalid(_) => PureTokenKind::Invalid,
             TokenKind::Identifier(_) => PureTokenKind::Identifier,
             TokenKind::StringLiteral(_) => PureTokenKind::StringLiteral,
+            TokenKind::Symbol(_) => PureTokenKind::Symbol,
             TokenKind::Integer(_) => PureTokenKind::Integer,
+            TokenKind::Operator(_) => PureTokenKind::Operator,
             TokenKind::Comma => PureTokenKind::Comma,
             TokenKind::Semicolon => PureTokenKind::Semicolon,
             TokenKind::LeftParen => PureTokenKind::LeftParen,
             TokenKind::RightParen => PureTokenKind::RightParen,
             TokenKind::LeftBrace => PureTokenKind::LeftBrace,
             TokenKind::RightBrace => PureTokenKind::RightBrace,
+            TokenKind::SetStart => PureTokenKind::SetStart,
             TokenKind::Function => PureTokenKind::Function,
             TokenKind::Let => PureTokenKind::Let,
             TokenKind::Assign => PureTokenKind::Assign,
@@ -163,16 +234,22 @@ impl From<&TokenKind> for PureTokenKind {
             TokenKind::RightBracket => PureTokenKind::RightBracket,
             TokenKind::Collon => PureTokenKind::Collon,
             TokenKind::Macro => PureTokenKind::Macro,
+            TokenKind::Yield => PureTokenKind::Yield,
+            TokenKind::At => PureTokenKind::At,
+            TokenKind::Arrow => PureTokenKind::Arrow,
+            TokenKind::EndOfInput => PureTokenKind::EndOfInput,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum PureTokenKind {
     Invalid,
     Identifier,
     StringLiteral,
+    Symbol,
     Integer,
+    Operator,
 
     Comma,
     Semicolon,
@@ -180,6 +257,7 @@ pub enum PureTokenKind {
     RightParen,
     LeftBrace,
     RightBrace,
+    SetStart,
 
     Function,
     Let,
@@ -204,4 +282,55 @@ pub enum PureTokenKind {
     RightBracket,
     Collon,
     Macro,
+    Yield,
+    At,
+    Arrow,
+    EndOfInput,
+}
+
+//keywords and symbols are quoted as written
+impl Display for PureTokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let written = match self {
+            PureTokenKind::Invalid => return write!(f, "invalid token"),
+            PureTokenKind::Identifier => return write!(f, "identifier"),
+            PureTokenKind::StringLiteral => return write!(f, "string"),
+            PureTokenKind::Symbol => return write!(f, "symbol"),
+            PureTokenKind::Integer => return write!(f, "integer"),
+            PureTokenKind::Operator => return write!(f, "operator"),
+            PureTokenKind::EndOfInput => return write!(f, "end of input"),
+            PureTokenKind::Comma => ",",
+            PureTokenKind::Semicolon => ";",
+            PureTokenKind::LeftParen => "(",
+            PureTokenKind::RightParen => ")",
+            PureTokenKind::LeftBrace => "{",
+            PureTokenKind::RightBrace => "}",
+            PureTokenKind::SetStart => "#{",
+            PureTokenKind::Function => "fn",
+            PureTokenKind::Let => "let",
+            PureTokenKind::Assign => "=",
+            PureTokenKind::Plus => "+",
+            PureTokenKind::Minus => "-",
+            PureTokenKind::Equal => "==",
+            PureTokenKind::Inequal => "!=",
+            PureTokenKind::Negation => "!",
+            PureTokenKind::LessThen => "<",
+            PureTokenKind::GreaterThen => ">",
+            PureTokenKind::Slash => "/",
+            PureTokenKind::Asterisk => "*",
+            PureTokenKind::True => "true",
+            PureTokenKind::False => "false",
+            PureTokenKind::If => "if",
+            PureTokenKind::Else => "else",
+            PureTokenKind::Return => "return",
+            PureTokenKind::LeftBracket => "[",
+            PureTokenKind::RightBracket => "]",
+            PureTokenKind::Collon => ":",
+            PureTokenKind::Macro => "macro",
+            PureTokenKind::Yield => "yield",
+            PureTokenKind::At => "@",
+            PureTokenKind::Arrow => "->",
+        };
+        write!(f, "`{}`", written)
+    }
 }
@@ -46,6 +46,10 @@ pub(crate) fn binary(left: Object, right: Object, operator: InfixOperatorType) -
                 InfixOperatorType::Plus => Object::String(l.to_string() + &r),
                 _ => panic!("Don't know how to deal with {l:?} and {r:?} for {operator:?}"),
             },
+            Object::Char(l) => match operator {
+                InfixOperatorType::Plus => Object::String(l.to_string() + &r),
+                _ => panic!("Don't know how to deal with {l:?} and {r:?} for {operator:?}"),
+            },
 
             _ => panic!(
                 "Don't know how to deal with {left:?} and {:?} for {operator:?}",
@@ -53,6 +57,30 @@ pub(crate) fn binary(left: Object, right: Object, operator: InfixOperatorType) -
             ),
         },
 
+        Object::Char(r) => match left {
+            Object::Char(l) => match operator {
+                InfixOperatorType::Plus => Object::String(format!("{l}{r}")),
+                InfixOperatorType::NotEqual => wrap_boolean(l != r),
+                InfixOperatorType::Equal => wrap_boolean(l == r),
+                InfixOperatorType::GreaterThan => wrap_boolean(l > r),
+                _ => panic!("Don't know how to deal with {l:?} and {r:?} for {operator:?}"),
+            },
+            Object::String(l) => match operator {
+                InfixOperatorType::Plus => Object::String(l + &r.to_string()),
+                _ => panic!("Don't know how to deal with {l:?} and {r:?} for {operator:?}"),
+            },
+            _ => panic!("Don't know how to deal with {left:?} and {r:?} for {operator:?}"),
+        },
+        Object::Tuple { .. } | Object::Set(_) | Object::Symbol(_)
+            if core::mem::discriminant(&left) == core::mem::discriminant(&right) =>
+        {
+            match operator {
+                InfixOperatorType::NotEqual => wrap_boolean(left != right),
+                InfixOperatorType::Equal => wrap_boolean(left == right),
+                _ => panic!("Don't know how to deal with {left:?} and {right:?} for {operator:?}"),
+            }
+        }
+
         _ => panic!("Don't know how to deal with {right:?} for {operator:?}"),
     }
 }
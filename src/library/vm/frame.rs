@@ -2,13 +2,13 @@ use std::usize;
 
 use crate::{
     code::{Byte, Instructions},
-    object::{CompiledFunctionEntry, Object},
+    object::{CompiledFunctionEntry, Generator, Upvalue},
 };
 
 #[derive(Clone, Debug)]
 pub(crate) struct Closure {
     pub(crate) function: CompiledFunctionEntry,
-    pub(crate) free: Vec<Object>,
+    pub(crate) free: Vec<Upvalue>,
 }
 
 impl Closure {
@@ -22,14 +22,15 @@ pub(crate) struct Frame {
     pub(crate) closure: Closure,
     pub(crate) instruction_pointer: usize,
     pub(crate) base_pointer: usize, //position of stack before starting new frame, it is not
-                                    //instruction pointer since on stack there will be a place for local bindings so
-                                    /*
-                                     * Stack
-                                     * call() - base_pointer
-                                     * place for variable 1
-                                     * place for variable 2
-                                     * code - instruction_pointer
-                                     */
+    //instruction pointer since on stack there will be a place for local bindings so
+    /*
+     * Stack
+     * call() - base_pointer
+     * place for variable 1
+     * place for variable 2
+     * code - instruction_pointer
+     */
+    pub(crate) generator: Option<Generator>, //generator which is resumed in this frame
 }
 
 impl Frame {
@@ -38,6 +39,7 @@ impl Frame {
             closure: closure,
             instruction_pointer: 0,
             base_pointer: base_pointer,
+            generator: None,
         }
     }
 }
@@ -48,9 +50,12 @@ pub(crate) const NIL_FRAME: Frame = Frame {
             instructions: Instructions(vec![]),
             number_of_locals: 0,
             number_of_parameters: 0,
+            generator: false,
+            positions: vec![],
         },
         free: vec![],
     },
     instruction_pointer: 0,
     base_pointer: 0,
+    generator: None,
 };
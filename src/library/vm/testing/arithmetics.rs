@@ -6,6 +6,7 @@ use crate::{
 generate_vm_tests! {
     one: ("1", should_be_integer(1)),
     second: ("2", should_be_integer(2)),
+    other_bases: ("0xFF + 0b1010 + 0o7 + 1_000", should_be_integer(1272)),
     addition: ("1+2", should_be_integer(3)),
     substitution: ("1-2", should_be_integer(-1)),
     multiplication: ("2*3", should_be_integer(6)),
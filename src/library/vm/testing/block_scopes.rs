@@ -0,0 +1,43 @@
+use crate::{
+    generate_vm_tests,
+    vm::testing::setups::{run_vm_test, should_be_integer},
+};
+
+generate_vm_tests! {
+    let_in_if_does_not_leak: ("let x = 5; if (true) { let x = 10; x }; x", should_be_integer(5)),
+    let_in_if_shadows: ("let x = 5; if (true) { let x = 10; x }", should_be_integer(10)),
+    let_in_else_does_not_leak: (
+        "let x = 5; if (false) { 1 } else { let x = 10; x }; x",
+        should_be_integer(5)
+    ),
+    shadowing_reads_outer: ("let x = 5; if (true) { let x = x + 1; x }", should_be_integer(6)),
+    nested_blocks: (
+        "let x = 1; if (true) { let x = 2; if (true) { let x = 3; x }; x }",
+        should_be_integer(2)
+    ),
+    block_in_function: (r#"
+        let f = fn(a) {
+            let x = 1;
+            if (a > 0) { let x = 10; x + a } else { x }
+        };
+        f(1) + f(0)
+    "#, should_be_integer(12)),
+    sibling_blocks_reuse_slots: (r#"
+        let f = fn() {
+            let a = 1;
+            if (true) { let b = 2; a + b };
+            if (true) { let c = 3; a + c }
+        };
+        f()
+    "#, should_be_integer(4)),
+    closure_captures_block_variable: (r#"
+        let f = if (true) { let hidden = 42; fn() { hidden } };
+        f()
+    "#, should_be_integer(42)),
+    closure_captures_local_block_variable: (r#"
+        let outer = fn() {
+            if (true) { let hidden = 7; fn() { hidden } }
+        };
+        outer()()
+    "#, should_be_integer(7)),
+}
@@ -0,0 +1,49 @@
+use crate::{
+    generate_vm_tests,
+    vm::testing::setups::{run_vm_test, should_be_integer},
+};
+
+generate_vm_tests! {
+    called_before_declaration: ("let r = twice(3); fn twice(x) { x * 2 } r", should_be_integer(6)),
+    mutual_recursion: (r#"
+        fn even(n) { if (n == 0) { 1 } else { odd(n - 1) } }
+        fn odd(n) { if (n == 0) { 0 } else { even(n - 1) } }
+        even(10) + odd(7)
+    "#, should_be_integer(2)),
+    local_mutual_recursion: (r#"
+        let count = fn(limit) {
+            fn ping(n) { if (n == limit) { n } else { pong(n + 1) } }
+            fn pong(n) { ping(n + 1) }
+            ping(0)
+        };
+        count(6)
+    "#, should_be_integer(6)),
+    nested_declaration_before_sibling: (r#"
+        let f = fn() {
+            fn a() { b() + 1 }
+            fn b() { 41 }
+            a()
+        };
+        f()
+    "#, should_be_integer(42)),
+    hoisted_inside_block: ("if (true) { let r = f(); fn f() { 3 } r }", should_be_integer(3)),
+    closure_calls_enclosing_function: (r#"
+        fn countdown(n) {
+            let step = fn() { countdown(n - 1) };
+            if (n == 0) { 0 } else { step() + 1 }
+        }
+        countdown(5)
+    "#, should_be_integer(5)),
+    free_variable_through_two_closures: (r#"
+        fn outer() {
+            let x = 7;
+            let mid = fn() {
+                let y = x;
+                let inner = fn() { x };
+                inner() + y
+            };
+            mid()
+        }
+        outer()
+    "#, should_be_integer(14)),
+}
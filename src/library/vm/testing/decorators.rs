@@ -0,0 +1,60 @@
+use crate::{
+    generate_vm_tests,
+    vm::testing::setups::{run_vm_test, should_be_integer},
+};
+
+generate_vm_tests! {
+    decorator_wraps_function: (r#"
+        let add_one = fn(f) { fn(x) { f(x) + 1 } };
+        @add_one let f = fn(x) { x * 10 };
+        f(2)
+    "#, should_be_integer(21)),
+    nearest_decorator_is_applied_first: (r#"
+        let add_one = fn(f) { fn(x) { f(x) + 1 } };
+        let double = fn(f) { fn(x) { f(x) * 2 } };
+        @add_one @double let f = fn(x) { x };
+        f(3)
+    "#, should_be_integer(7)),
+    decorator_with_arguments: (r#"
+        let times = fn(n) { fn(f) { fn(x) { f(x) * n } } };
+        @times(3) let f = fn(x) { x + 1 };
+        f(1)
+    "#, should_be_integer(6)),
+    decorator_runs_at_definition: (r#"
+        let defined = 0;
+        let register = fn(f) { defined = defined + 1; f };
+        @register let f = fn(x) { x };
+        @register let g = fn(x) { x };
+        defined
+    "#, should_be_integer(2)),
+    recursion_goes_through_decorator: (r#"
+        let calls = 0;
+        let counted = fn(f) { fn(n) { calls = calls + 1; f(n) } };
+        @counted let fact = fn(n) { if (n < 2) { 1 } else { n * fact(n - 1) } };
+        let result = fact(5);
+        result * 10 + calls
+    "#, should_be_integer(1205)),
+    decorators_are_evaluated_before_value: (r#"
+        let order = 0;
+        let step = fn(n) { order = order * 10 + n; fn(f) { order = order * 10 + n + 5; f } };
+        @step(1) @step(2) let f = step(3);
+        order
+    "#, should_be_integer(12376)),
+    decorated_local: (r#"
+        let outer = fn() {
+            let double = fn(f) { fn(x) { f(x) * 2 } };
+            @double let f = fn(x) { x + 1 };
+            f(4)
+        };
+        outer()
+    "#, should_be_integer(10)),
+    recursive_local: (r#"
+        let outer = fn() {
+            let calls = 0;
+            let counted = fn(f) { fn(n) { calls = calls + 1; f(n) } };
+            @counted let sum = fn(n) { if (n < 1) { 0 } else { n + sum(n - 1) } };
+            sum(3) * 10 + calls
+        };
+        outer()
+    "#, should_be_integer(64)),
+}
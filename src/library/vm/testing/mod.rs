@@ -1,13 +1,25 @@
 mod arithmetics;
 mod arrays;
+mod block_scopes;
 mod builtins;
 mod closures;
 mod conditionals;
+mod declared_functions;
+mod decorators;
 mod functions;
 mod functions_with_arguments;
+mod generators;
 mod hashmaps;
 mod indexes;
 mod let_statements;
 mod local_bindings;
+mod mutable_closures;
+mod operators;
+mod sets;
 mod setups;
+mod strict;
 mod string_arithmethics;
+mod strings;
+mod symbols;
+mod tail_calls;
+mod tuples;
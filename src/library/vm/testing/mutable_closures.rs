@@ -0,0 +1,76 @@
+use crate::{
+    generate_vm_tests,
+    vm::testing::setups::{run_vm_test, should_be_integer},
+};
+
+generate_vm_tests! {
+    global_assignment: ("let a = 1; a = a + 1; a", should_be_integer(2)),
+    counter: (r#"
+        let makeCounter = fn() {
+            let count = 0;
+            fn() { count = count + 1; count }
+        };
+        let counter = makeCounter();
+        counter();
+        counter();
+        counter()
+    "#, should_be_integer(3)),
+    closures_share_variable: (r#"
+        let pair = fn() {
+            let value = 0;
+            let inc = fn() { value = value + 10; };
+            let get = fn() { value };
+            [inc, get]
+        };
+        let p = pair();
+        p[1]();
+        p[1]();
+        p[2]()
+    "#, should_be_integer(20)),
+    defining_function_sees_write: (r#"
+        let f = fn() {
+            let x = 1;
+            let set = fn() { x = 5; };
+            set();
+            x
+        };
+        f()
+    "#, should_be_integer(5)),
+    closure_sees_later_assignment: (r#"
+        let f = fn() {
+            let x = 1;
+            let get = fn() { x };
+            x = 2;
+            get()
+        };
+        f()
+    "#, should_be_integer(2)),
+    closure_sees_redefinition: (r#"
+        let f = fn() {
+            let x = 1;
+            let get = fn() { x };
+            let x = 3;
+            get()
+        };
+        f()
+    "#, should_be_integer(3)),
+    write_through_nested_closure: (r#"
+        let outer = fn() {
+            let n = 0;
+            let middle = fn() { fn() { n = n + 1; n } };
+            let inc = middle();
+            inc();
+            inc();
+            n
+        };
+        outer()
+    "#, should_be_integer(2)),
+    block_variable_survives_slot_reuse: (r#"
+        let f = fn() {
+            let g = if (true) { let hidden = 1; fn() { hidden } };
+            if (true) { let other = 100; other };
+            g()
+        };
+        f()
+    "#, should_be_integer(1)),
+}
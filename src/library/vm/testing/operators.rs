@@ -0,0 +1,43 @@
+use crate::{
+    generate_vm_tests,
+    vm::testing::setups::{run_vm_test, should_be_boolean, should_be_integer, should_be_string},
+};
+
+macro_rules! with_vector {
+    ($program:literal) => {
+        concat!(
+            r#"
+            fn vector(x, y) {
+                {
+                    "x": x,
+                    "y": y,
+                    "__add__": fn(a, b) { vector(a["x"] + b["x"], a["y"] + b["y"]) },
+                    "__mul__": fn(a, n) { vector(a["x"] * n, a["y"] * n) },
+                    "__eq__": fn(a, b) { if (a["x"] == b["x"]) { a["y"] == b["y"] } else { false } },
+                    "__lt__": fn(a, b) { a["x"] * a["x"] + a["y"] * a["y"] < b["x"] * b["x"] + b["y"] * b["y"] },
+                    "__str__": fn(v) { "(" + v["x"] + ", " + v["y"] + ")" }
+                }
+            }
+            "#,
+            $program
+        )
+    };
+}
+
+generate_vm_tests! {
+    overloaded_addition: (with_vector!(r#"let v = vector(1, 2) + vector(3, 4); v["x"] * 10 + v["y"]"#), should_be_integer(46)),
+    overloaded_multiplication: (with_vector!(r#"let v = vector(1, 2) * 3; v["x"] * 10 + v["y"]"#), should_be_integer(36)),
+    overloaded_equality: (with_vector!("vector(1, 2) == vector(1, 2)"), should_be_boolean(true)),
+    overloaded_inequality: (with_vector!("vector(1, 2) != vector(2, 1)"), should_be_boolean(true)),
+    overloaded_less_than: (with_vector!("vector(1, 1) < vector(2, 2)"), should_be_boolean(true)),
+    overloaded_greater_than: (with_vector!("vector(1, 1) > vector(2, 2)"), should_be_boolean(false)),
+    overloaded_inside_function: (with_vector!(r#"let sum = fn(a, b) { (a + b)["x"] }; sum(vector(1, 0), vector(2, 0)) * 2"#), should_be_integer(6)),
+    overloaded_display: (with_vector!("str(vector(1, 2))"), should_be_string("(1, 2)")),
+    overloaded_display_in_concatenation: (with_vector!(r#""v = " + vector(1, 2) + "!""#), should_be_string("v = (1, 2)!")),
+}
+
+generate_vm_tests! {
+    declared_operator: ("infix 60 <+> = fn(a, b) { a * 10 + b }; 1 <+> 2 <+> 3", should_be_integer(123)),
+    declared_right_associative: ("infixr 70 ^^ = fn(a, b) { a - b }; 10 ^^ 4 ^^ 1 * 2", should_be_integer(8)),
+    declared_inside_function: ("let f = fn(x) { infix 50 <> = fn(a, b) { a != b }; x <> 1 }; f(2)", should_be_boolean(true)),
+}
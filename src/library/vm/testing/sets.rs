@@ -0,0 +1,18 @@
+use crate::{
+    generate_vm_tests,
+    vm::testing::setups::{run_vm_test, should_be_boolean, should_be_integer},
+};
+
+generate_vm_tests! {
+    duplicates_are_removed: ("len(#{1, 2, 2, 1, 3})", should_be_integer(3)),
+    empty_set: ("len(#{})", should_be_integer(0)),
+    contains_element: ("contains(#{1, \"a\"}, \"a\")", should_be_boolean(true)),
+    does_not_contain_element: ("contains(#{1, 2}, 3)", should_be_boolean(false)),
+    union_of_sets: ("len(union(#{1, 2}, #{2, 3}))", should_be_integer(3)),
+    intersection_of_sets: ("len(intersection(#{1, 2, 3}, #{2, 3, 4}))", should_be_integer(2)),
+    difference_of_sets: ("len(difference(#{1, 2, 3}, #{2}))", should_be_integer(2)),
+    sets_are_equal_in_any_order: ("#{1, 2, 3} == #{3, 2, 1}", should_be_boolean(true)),
+    sets_are_different: ("#{1, 2} != #{1}", should_be_boolean(true)),
+    set_as_map_key: ("{#{1, 2}: 5}[#{2, 1}]", should_be_integer(5)),
+    set_of_strings: ("len(#{\"a\", \"b\", \"a\"})", should_be_integer(2)),
+}
@@ -0,0 +1,88 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    code::compile,
+    evaluator::evaluate,
+    generate_vm_tests,
+    object::{Environment, Object},
+    vm::{
+        VM,
+        testing::setups::{
+            parse_program, run_vm_test, should_be_error, should_be_integer, should_be_string,
+        },
+    },
+};
+
+fn strict_error(expected: &'static str) -> impl Fn(&str) -> Result<(), String> {
+    move |message: &str| {
+        if message == expected {
+            Result::Ok(())
+        } else {
+            Result::Err(format!("Expecting {expected}"))
+        }
+    }
+}
+
+generate_vm_tests! {
+    boolean_and_integer: ("\"use strict\"; true + 1",
+        should_be_error(strict_error("Strict mode does not allow + on Boolean(true) and Int(1)"))),
+    string_and_null: ("\"use strict\"; let n = if (false) { 1 }; \"a\" + n",
+        should_be_error(strict_error("Strict mode does not allow + on String(a) and Null(NULL)"))),
+    integer_and_string: ("\"use strict\"; 1 + \"a\"",
+        should_be_error(strict_error("Strict mode does not allow + on Int(1) and String(a)"))),
+    char_and_string: ("\"use strict\"; \"a\"[1] + \"b\"",
+        should_be_error(strict_error("Strict mode does not allow + on Char(a) and String(b)"))),
+    same_types_are_allowed: ("\"use strict\"; 1 + 2 * 3", should_be_integer(7)),
+    repeating_string_is_allowed: ("\"use strict\"; \"ab\" * 2", should_be_string("abab")),
+    coercion_without_strict_mode: ("1 + \"a\"", should_be_string("1a")),
+    program_stops_at_violation: ("\"use strict\"; let x = true + 1; 5",
+        should_be_error(strict_error("Strict mode does not allow + on Boolean(true) and Int(1)"))),
+    program_stops_inside_function: ("\"use strict\"; let f = fn() { let y = 1 - \"a\"; 7 }; f(); 5",
+        should_be_error(strict_error("Strict mode does not allow - on Int(1) and String(a)"))),
+}
+
+fn run(input: &str) -> Object {
+    let mut vm = VM::new(compile(parse_program(input)).unwrap());
+    vm.run();
+    vm.last_poped_stack_element().unwrap()
+}
+
+#[test]
+fn error_points_at_operator() {
+    let error = run("\"use strict\";\nlet f = fn(a) {\n  a > \"b\"\n};\nf(1);\n5");
+    assert_eq!(
+        error,
+        Object::Error {
+            message: "Strict mode does not allow > on Int(1) and String(b)".to_string(),
+            line: 3,
+            column: 5,
+        }
+    );
+}
+
+#[test]
+fn same_result_as_evaluator() {
+    let programs = [
+        "\"use strict\"; let x = true + 1; 5",
+        "\"use strict\"; let f = fn(a) { let y = a * true; 7 }; f(1); 5",
+        "\"use strict\"; let f = fn(a) { a + 1 }; f(1) + f(\"a\")",
+        "\"use strict\"; (1, 2) == [1, 2]",
+        "let f = fn(a) { a + 1 }; f(\"a\")",
+    ];
+    for input in programs {
+        let environment = Rc::new(RefCell::new(Environment::new()));
+        let evaluated = evaluate(&parse_program(input), environment);
+        assert_eq!(run(input), *evaluated, "for {input}");
+    }
+}
+
+#[test]
+fn strict_engine() {
+    let byte_code = compile(parse_program("\"a\" + 1")).unwrap();
+    let mut vm = VM::new(byte_code);
+    vm.set_strict(true);
+    vm.run();
+    should_be_error(strict_error(
+        "Strict mode does not allow + on String(a) and Int(1)",
+    ))(&vm.last_poped_stack_element().unwrap());
+}
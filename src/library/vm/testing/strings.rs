@@ -0,0 +1,16 @@
+use crate::{
+    generate_vm_tests,
+    vm::testing::setups::{run_vm_test, should_be_integer, should_be_null, should_be_string},
+};
+
+generate_vm_tests! {
+    len_counts_characters: (r#"len("żółw")"#, should_be_integer(4)),
+    index_is_by_character: (r#"if ("żółw"[2] == "ó"[1]) { 1 } else { 0 }"#, should_be_integer(1)),
+    negative_index: (r#"if ("żółw"[-1] == "w"[1]) { 1 } else { 0 }"#, should_be_integer(1)),
+    index_after_end: (r#""żół"[4]"#, should_be_null()),
+    chars_splits_into_characters: (r#"len(chars("żółw"))"#, should_be_integer(4)),
+    chars_are_ordered: (r#"if ("a"[1] < "b"[1]) { 1 } else { 0 }"#, should_be_integer(1)),
+    char_joins_into_string: (r#""żółw"[1] + "ółw""#, should_be_string("żółw")),
+    rest_keeps_characters: (r#"rest("żółw")"#, should_be_string("ółw")),
+    unicode_identifiers: ("let żółw = 2; let _x = 3; żółw * _x", should_be_integer(6)),
+}
@@ -0,0 +1,17 @@
+use crate::{
+    generate_vm_tests,
+    vm::testing::setups::{run_vm_test, should_be_boolean, should_be_integer},
+};
+
+generate_vm_tests! {
+    same_symbols_are_equal: (":ok == :ok", should_be_boolean(true)),
+    different_symbols: (":ok != :error", should_be_boolean(true)),
+    symbol_as_map_key: ("let m = {:ok: 1, :error: 2}; m[:error]", should_be_integer(2)),
+    map_with_identifier_keys: ("let a = 1; let b = 5; {a:b}[1]", should_be_integer(5)),
+    symbols_in_set: ("len(#{:a, :b, :a})", should_be_integer(2)),
+    tagged_result: (r#"
+        let check = fn(x) { if (x > 0) { (:ok, x) } else { (:error, 0) } };
+        let (tag, value) = check(3);
+        if (tag == :ok) { value } else { 0 - 1 }
+    "#, should_be_integer(3)),
+}
@@ -0,0 +1,58 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    code::compile,
+    evaluator::evaluate,
+    generate_vm_tests,
+    object::{Environment, Object},
+    vm::{
+        VM,
+        testing::setups::{
+            parse_program, run_vm_test, should_be_boolean, should_be_integer, should_be_string,
+        },
+    },
+};
+
+generate_vm_tests! {
+    len_of_tuple: ("len((1, 2, 3))", should_be_integer(3)),
+    index_of_tuple: ("(4, 5, 6)[2]", should_be_integer(5)),
+    negative_index_of_tuple: ("(4, 5, 6)[-1]", should_be_integer(6)),
+    grouped_expression_is_not_tuple: ("(1 + 2) * 3", should_be_integer(9)),
+    tuples_are_equal: ("(1, \"a\") == (1, \"a\")", should_be_boolean(true)),
+    tuples_are_different: ("(1, 2) != (2, 1)", should_be_boolean(true)),
+    tuple_as_map_key: ("{(1, 2): 5, (2, 1): 7}[(1, 2)]", should_be_integer(5)),
+    destructuring_let: ("let (a, b) = (3, 4); a * 10 + b", should_be_integer(34)),
+    nested_destructuring: ("let (a, (b, c)) = (1, (2, 3)); a * 100 + b * 10 + c", should_be_integer(123)),
+    local_destructuring: ("let pair = fn(x) { let (a, b) = (x, x * 2); a + b }; pair(3)", should_be_integer(9)),
+    tuple_element_is_kept: ("let (_, s) = (1, \"two\"); s", should_be_string("two")),
+}
+
+#[test]
+fn destructuring_mismatch_stops_with_error_of_evaluator() {
+    let tests = [
+        (
+            "let (a, b) = (1, 2, 3); a",
+            "Cannot destructure Tuple((1, 2, 3)) into tuple of 2 elements",
+        ),
+        (
+            "let (a, b) = 1; a",
+            "Cannot destructure Int(1) into tuple of 2 elements",
+        ),
+        (
+            "let f = fn(x) {\n  let (a, (b, c)) = x;\n  a\n};\nf((1, 2)); 5",
+            "Cannot destructure Int(2) into tuple of 2 elements",
+        ),
+    ];
+    for (input, message) in tests {
+        let mut vm = VM::new(compile(parse_program(input)).unwrap());
+        vm.run();
+        let result = vm.last_poped_stack_element().unwrap();
+        let Object::Error { message: found, .. } = &result else {
+            panic!("Expecting error got {:?} for {}", result, input);
+        };
+        assert_eq!(found, message, "for {}", input);
+        let environment = Rc::new(RefCell::new(Environment::new()));
+        let evaluated = evaluate(&parse_program(input), environment);
+        assert_eq!(result, *evaluated, "for {}", input);
+    }
+}
@@ -1,6 +1,10 @@
 use crate::{
-    code::read_u_8,
-    object::{BuiltInFunction, HashEntry, HashValue, hash},
+    code::{Byte, read_u_8},
+    object::{
+        BuiltInFunction, CompiledFunctionEntry, Generator, GeneratorState, HashEntry, HashValue,
+        SuspendedFrame, Upvalue, UpvalueCell, char_at, display_method, find_overload, hash,
+        strict_violation, type_of,
+    },
     vm::{
         FALSE, NIL, TRUE,
         frame::{Closure, Frame, NIL_FRAME},
@@ -8,7 +12,7 @@ use crate::{
         wrap_boolean,
     },
 };
-use std::{collections::HashMap, panic, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, panic, rc::Rc};
 
 use crate::{
     ast::expression::{InfixOperatorType, PrefixOperatorType},
@@ -33,6 +37,10 @@ pub struct VM {
     globals: [Object; GLOBALS_SIZE],
     frames: [Frame; FRAME_SIZE],
     frame_index: usize,
+    open_upvalues: Vec<Upvalue>, //cells still pointing at stack slots of living frames
+    captured: Vec<Upvalue>,      //cells waiting for next closure to be created
+    strict: bool,                //operators refuse to coerce values of different types
+    halted: Option<Object>,      //error which stopped the program
 }
 
 impl VM {
@@ -52,13 +60,19 @@ impl VM {
             globals: std::array::from_fn(|_| NIL),
             frames: std::array::from_fn(|_| NIL_FRAME),
             frame_index: 0,
+            open_upvalues: vec![],
+            captured: vec![],
+            strict: byte_code.strict,
+            halted: None,
         };
         let function = crate::object::CompiledFunctionEntry {
             instructions: byte_code.instructions,
             number_of_locals: 0,
             number_of_parameters: 0,
+            generator: false,
+            positions: byte_code.positions,
         };
-        let free: Vec<Object> = vec![];
+        let free: Vec<Upvalue> = vec![];
         let closure = Closure {
             function: function,
             free: free,
@@ -68,6 +82,16 @@ impl VM {
     }
 
     pub fn run(&mut self) {
+        self.execute(0);
+    }
+
+    //also for programs without the directive
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    //resumed generators run in a nested call, which returns once they yield or return
+    fn execute(&mut self, stop: usize) {
         let mut move_instruction_pointer: usize;
         while self.current_frame().instruction_pointer < self.current_frame().closure.bytes().len()
         {
@@ -152,6 +176,43 @@ impl VM {
                     self.stack_pointer = self.stack_pointer - number_of_elements;
                     self.push(array);
                 }
+                TUPLE => {
+                    let number_of_elements = read_u_16(&bytes[instruction_pointer + 1..]) as usize;
+                    self.current_frame().instruction_pointer += 2;
+                    let tuple = match self
+                        .build_array(self.stack_pointer - number_of_elements, self.stack_pointer)
+                    {
+                        Object::Array { elements } => Object::Tuple { elements },
+                        _ => unreachable!(),
+                    };
+                    self.stack_pointer -= number_of_elements;
+                    self.push(tuple);
+                }
+                DESTRUCTURE => {
+                    let number_of_elements = read_u_16(&bytes[instruction_pointer + 1..]) as usize;
+                    let value = self.pop();
+                    match value {
+                        Object::Tuple { elements } if elements.len() == number_of_elements => {
+                            for element in elements {
+                                self.push(Rc::unwrap_or_clone(element));
+                            }
+                        }
+                        _ => self.halt(format!(
+                            "Cannot destructure {}({value}) into tuple of {number_of_elements} elements",
+                            type_of(&value)
+                        )),
+                    }
+                    //error of the instruction points at its start, so it is passed only now
+                    self.current_frame().instruction_pointer += 2;
+                }
+                SET => {
+                    let number_of_elements = read_u_16(&bytes[instruction_pointer + 1..]) as usize;
+                    self.current_frame().instruction_pointer += 2;
+                    let set =
+                        self.build_set(self.stack_pointer - number_of_elements, self.stack_pointer);
+                    self.stack_pointer -= number_of_elements;
+                    self.push(set);
+                }
                 HASH => {
                     let number_of_elements = read_u_16(&bytes[instruction_pointer + 1..]) as usize;
                     self.current_frame().instruction_pointer += 2;
@@ -166,13 +227,12 @@ impl VM {
                     self.execute_index(index, left);
                 }
                 RETURN_VALUE => {
-                    let frame = self.pop_frame();
                     let value = self.pop();
-                    self.stack_pointer = frame.base_pointer - 1;
-                    self.push(value);
+                    self.return_from_frame(value);
                 }
                 NO_RETURN => {
                     let frame = self.pop_frame();
+                    self.close_upvalues(frame.base_pointer);
                     self.stack_pointer = frame.base_pointer - 1;
                     self.push(NIL)
                 }
@@ -204,16 +264,58 @@ impl VM {
                 GET_FREE => {
                     let free_index = read_u_8(&bytes[instruction_pointer + 1..]) as usize;
                     self.current_frame().instruction_pointer += 1;
-                    let current_closure = &self.current_frame().closure;
-                    let found_free = current_closure.free.get(free_index).clone();
-                    let found = match found_free {
-                        Some(v) => v.clone(),
-                        None => panic!(
-                            "Cannot find free variable of {free_index} in {current_closure:?}"
-                        ),
+                    let cell = self.free_cell(free_index);
+                    let found = match &*cell.borrow() {
+                        UpvalueCell::Open(index) => self.stack[*index].clone(),
+                        UpvalueCell::Closed(value) => value.clone(),
                     };
                     self.push(found)
                 }
+                SET_FREE => {
+                    let free_index = read_u_8(&bytes[instruction_pointer + 1..]) as usize;
+                    self.current_frame().instruction_pointer += 1;
+                    let cell = self.free_cell(free_index);
+                    let value = self.pop();
+                    let open_index = match &mut *cell.borrow_mut() {
+                        UpvalueCell::Open(index) => Some(*index),
+                        UpvalueCell::Closed(closed) => {
+                            *closed = value.clone();
+                            None
+                        }
+                    };
+                    if let Some(index) = open_index {
+                        self.stack[index] = value;
+                    }
+                }
+                CAPTURE_LOCAL => {
+                    let local_index = read_u_8(&bytes[instruction_pointer + 1..]) as usize;
+                    self.current_frame().instruction_pointer += 1;
+                    let index = self.current_frame().base_pointer + local_index;
+                    let cell = self.open_upvalue(index);
+                    self.captured.push(cell);
+                }
+                CAPTURE_FREE => {
+                    let free_index = read_u_8(&bytes[instruction_pointer + 1..]) as usize;
+                    self.current_frame().instruction_pointer += 1;
+                    let cell = self.free_cell(free_index);
+                    self.captured.push(cell);
+                }
+                CLOSE_FREE => {
+                    let local_index = read_u_8(&bytes[instruction_pointer + 1..]) as usize;
+                    self.current_frame().instruction_pointer += 1;
+                    let index = self.current_frame().base_pointer + local_index;
+                    self.close_upvalues(index);
+                }
+                CAPTURE_CLOSURE => {
+                    //running closure never changes, so its cell can be closed right away
+                    let closure = self.current_frame().closure.clone();
+                    self.captured.push(Rc::new(RefCell::new(UpvalueCell::Closed(
+                        Object::Closure {
+                            function: closure.function,
+                            free: closure.free,
+                        },
+                    ))));
+                }
                 CURRENT_CLOSURE => {
                     let closure = self.current_frame().closure.clone();
                     self.push(Object::Closure {
@@ -226,8 +328,24 @@ impl VM {
                     self.current_frame().instruction_pointer += 1;
                     move_instruction_pointer = self.execute_call(number_of_arguments);
                 }
+                TAIL_CALL => {
+                    let number_of_arguments = read_u_8(&bytes[instruction_pointer + 1..]) as usize;
+                    self.current_frame().instruction_pointer += 1;
+                    move_instruction_pointer = self.execute_tail_call(number_of_arguments);
+                }
+                YIELD => {
+                    let value = self.pop();
+                    self.suspend_generator(instruction_pointer + 1);
+                    self.push(value);
+                }
+                DELEGATE => {
+                    move_instruction_pointer = self.execute_delegate(instruction_pointer);
+                }
                 _ => panic!("Don't know what to do with {instruction}"),
             }
+            if self.frame_index <= stop || self.halted.is_some() {
+                return;
+            }
             self.current_frame().instruction_pointer += move_instruction_pointer;
         }
     }
@@ -235,10 +353,78 @@ impl VM {
     fn binary_operation(&mut self, operator: InfixOperatorType) {
         let right = self.pop();
         let left = self.pop();
+        if self.strict
+            && let Some(message) = strict_violation(&operator, &left, &right)
+        {
+            self.halt(message);
+            return;
+        }
+        //only maps can override an operator, and none of them has a built-in rule
+        let (left, right) = match (&left, &right) {
+            (Object::HashMap(_), _) | (_, Object::HashMap(_)) => {
+                let (left, right) = (Rc::new(left), Rc::new(right));
+                if let Some(overload) = find_overload(&operator, &left, &right) {
+                    let arguments = overload.arguments.iter().map(|a| a.as_ref().clone());
+                    let function = overload.function.as_ref().clone();
+                    let result = self.call_value(function, arguments.collect());
+                    let value = overload.finish(Rc::new(result));
+                    self.push(Rc::unwrap_or_clone(value));
+                    return;
+                }
+                (Rc::unwrap_or_clone(left), Rc::unwrap_or_clone(right))
+            }
+            _ => (left, right),
+        };
         let value = binary(left, right, operator);
         self.push(value);
     }
 
+    fn halt(&mut self, message: String) {
+        let frame = &self.frames[self.frame_index - 1];
+        let (line, column) = frame
+            .closure
+            .function
+            .positions
+            .iter()
+            .find(|(offset, _)| *offset == frame.instruction_pointer)
+            .map_or((0, 0), |(_, position)| *position);
+        self.halted = Some(Object::Error {
+            message,
+            line,
+            column,
+        });
+    }
+
+    fn call_value(&mut self, function: Object, arguments: Vec<Object>) -> Object {
+        let number_of_arguments = arguments.len();
+        self.push(function);
+        for argument in arguments {
+            self.push(argument);
+        }
+        let stop = self.frame_index;
+        if self.execute_call(number_of_arguments) == 0 {
+            self.execute(stop);
+        }
+        self.pop()
+    }
+
+    fn display_arguments(&mut self, arguments: Vec<Rc<Object>>) -> Result<Vec<Rc<Object>>, Object> {
+        arguments
+            .into_iter()
+            .map(|argument| match display_method(&argument) {
+                Some(function) => {
+                    match self
+                        .call_value(function.as_ref().clone(), vec![argument.as_ref().clone()])
+                    {
+                        error @ Object::Error { .. } => Err(error),
+                        text => Ok(Rc::new(text)),
+                    }
+                }
+                None => Ok(argument),
+            })
+            .collect()
+    }
+
     fn current_frame(&mut self) -> &mut Frame {
         self.frames
             .get_mut(self.frame_index - 1)
@@ -285,6 +471,9 @@ impl VM {
     }
 
     pub(crate) fn last_poped_stack_element(&self) -> Option<Object> {
+        if let Some(error) = &self.halted {
+            return Some(error.clone());
+        }
         self.stack.get(self.stack_pointer).map(|o| o.clone())
     }
 
@@ -363,9 +552,17 @@ impl VM {
         return Object::HashMap(elements);
     }
 
+    fn build_set(&self, start_index: usize, end_index: usize) -> Object {
+        let elements = self.stack[start_index..end_index]
+            .iter()
+            .map(|element| (hash(element), Rc::new(element.clone())))
+            .collect();
+        Object::Set(elements)
+    }
+
     fn execute_index(&mut self, index: Object, left: Object) {
         match left {
-            Object::Array { elements } => {
+            Object::Array { elements } | Object::Tuple { elements } => {
                 let index_value = match index {
                     Object::Int(v) => v,
                     _ => panic!("Cannot do index {index:?} operation on array"),
@@ -373,6 +570,13 @@ impl VM {
 
                 self.push(execute_array_index(elements, index_value));
             }
+            Object::String(text) => {
+                let index_value = match index {
+                    Object::Int(v) => v,
+                    _ => panic!("Cannot do index {index:?} operation on string"),
+                };
+                self.push(char_at(&text, index_value).map_or(NIL, Object::Char));
+            }
             Object::HashMap(hash_map) => {
                 let hash = hash(&index);
                 let value = hash_map.get(&hash);
@@ -407,6 +611,10 @@ impl VM {
                     self.push(error);
                     return 1;
                 }
+                if function.generator {
+                    self.create_generator(function, free, number_of_arguments);
+                    return 1;
+                }
                 let locals = function.number_of_locals;
                 let closure = Closure { function, free };
                 let frame = Frame::new(closure, self.stack_pointer - number_of_arguments);
@@ -418,9 +626,22 @@ impl VM {
             Object::Builtin(fun) => {
                 let data =
                     &self.stack[(self.stack_pointer - number_of_arguments)..self.stack_pointer];
-                let mapped: Vec<Rc<Object>> = data.iter().map(|o| Rc::new(o.clone())).collect();
+                let mut mapped: Vec<Rc<Object>> = data.iter().map(|o| Rc::new(o.clone())).collect();
                 println!("{mapped:?}");
                 self.stack_pointer = self.stack_pointer - number_of_arguments - 1;
+                if let Some(result) = self.apply_generator_builtin(&fun, &mapped) {
+                    self.push(result);
+                    return 1;
+                }
+                if matches!(fun, BuiltInFunction::Puts | BuiltInFunction::Str) {
+                    mapped = match self.display_arguments(mapped) {
+                        Ok(displayed) => displayed,
+                        Err(error) => {
+                            self.push(error);
+                            return 1;
+                        }
+                    };
+                }
                 match fun.apply(&mapped) {
                     crate::object::BuiltInResult::Unit => self.push(Object::Null),
                     crate::object::BuiltInResult::Value(object) => {
@@ -441,18 +662,217 @@ impl VM {
         }
     }
 
+    //anything but a closure is called as usual, the return which follows gives its result
+    fn execute_tail_call(&mut self, number_of_arguments: usize) -> usize {
+        let callee_index = self.stack_pointer - number_of_arguments - 1;
+        match &self.stack[callee_index] {
+            Object::Closure { function, .. }
+                if function.number_of_parameters == number_of_arguments => {}
+            _ => return self.execute_call(number_of_arguments),
+        }
+        let base_pointer = self.current_frame().base_pointer;
+        self.close_upvalues(base_pointer);
+        for offset in 0..=number_of_arguments {
+            self.stack[base_pointer - 1 + offset] = self.stack[callee_index + offset].clone();
+        }
+        self.stack_pointer = base_pointer + number_of_arguments;
+        self.pop_frame();
+        self.execute_call(number_of_arguments)
+    }
+
+    fn return_from_frame(&mut self, value: Object) {
+        let frame = self.pop_frame();
+        self.close_upvalues(frame.base_pointer);
+        self.stack_pointer = frame.base_pointer - 1;
+        self.push(value);
+    }
+
+    fn create_generator(
+        &mut self,
+        function: CompiledFunctionEntry,
+        free: Vec<Upvalue>,
+        number_of_arguments: usize,
+    ) {
+        let mut stack =
+            self.stack[self.stack_pointer - number_of_arguments..self.stack_pointer].to_vec();
+        stack.resize(function.number_of_locals.max(number_of_arguments), NIL);
+        let generator = GeneratorState::Frame(SuspendedFrame {
+            function,
+            free,
+            instruction_pointer: 0,
+            stack,
+            cells: vec![],
+        });
+        self.stack_pointer = self.stack_pointer - number_of_arguments - 1;
+        self.push(Object::Generator(Rc::new(RefCell::new(generator))));
+    }
+
+    //error returned by the body finishes the generator as well
+    fn resume_generator(&mut self, generator: &Generator) -> Result<Option<Object>, Object> {
+        let frame = match generator.replace(GeneratorState::Running) {
+            GeneratorState::Frame(frame) => frame,
+            GeneratorState::Done => {
+                generator.replace(GeneratorState::Done);
+                return Ok(None);
+            }
+            GeneratorState::Running => return Err(vm_error("Generator is already running")),
+            GeneratorState::Tree(tree) => {
+                generator.replace(GeneratorState::Tree(tree));
+                return Err(vm_error("Generator was created by the evaluator"));
+            }
+        };
+        //takes place of the callee, so the frame has the same layout as a called one
+        self.push(Object::Generator(generator.clone()));
+        let stop = self.frame_index;
+        self.restore_generator(frame, generator.clone());
+        self.execute(stop);
+        let value = self.pop();
+        if !matches!(*generator.borrow(), GeneratorState::Running) {
+            return Ok(Some(value));
+        }
+        generator.replace(GeneratorState::Done);
+        match value {
+            Object::Error { .. } => Err(value),
+            _ => Ok(None),
+        }
+    }
+
+    fn restore_generator(&mut self, frame: SuspendedFrame, generator: Generator) {
+        let base_pointer = self.stack_pointer;
+        for value in frame.stack {
+            self.push(value);
+        }
+        for (cell, offset) in frame.cells {
+            let index = base_pointer + offset;
+            if let UpvalueCell::Closed(value) = cell.replace(UpvalueCell::Open(index)) {
+                self.stack[index] = value;
+            }
+            self.open_upvalues.push(cell);
+        }
+        self.push_frame(Frame {
+            closure: Closure {
+                function: frame.function,
+                free: frame.free,
+            },
+            instruction_pointer: frame.instruction_pointer,
+            base_pointer,
+            generator: Some(generator),
+        });
+    }
+
+    //captured locals are closed and opened again on the stack when resumed
+    fn suspend_generator(&mut self, resume_at: usize) {
+        let frame = self.pop_frame();
+        let base_pointer = frame.base_pointer;
+        let stack = self.stack[base_pointer..self.stack_pointer].to_vec();
+        let mut cells = vec![];
+        self.open_upvalues.retain(|cell| match *cell.borrow() {
+            UpvalueCell::Open(index) if index >= base_pointer => {
+                cells.push((cell.clone(), index - base_pointer));
+                false
+            }
+            _ => true,
+        });
+        for (cell, offset) in &cells {
+            cell.replace(UpvalueCell::Closed(stack[*offset].clone()));
+        }
+        self.stack_pointer = base_pointer - 1;
+        let generator = frame.generator.expect("Yield outside of generator");
+        generator.replace(GeneratorState::Frame(SuspendedFrame {
+            function: frame.closure.function,
+            free: frame.closure.free,
+            instruction_pointer: resume_at,
+            stack,
+            cells,
+        }));
+    }
+
+    //delegate stays on the stack and `yield*` runs again on every resume until it finishes
+    //as the last step its frame replaces the running one when nobody else holds it
+    fn execute_delegate(&mut self, instruction_pointer: usize) -> usize {
+        let delegate = match self.relative_stack_down(0) {
+            Object::Generator(delegate) => delegate,
+            other => {
+                self.pop();
+                let message = format!("yield* expected Generator, got {}", type_of(&other));
+                self.return_from_frame(vm_error(&message));
+                return 0;
+            }
+        };
+        let bytes = self.current_frame().closure.bytes();
+        //one reference is on the stack, the other one is `delegate`
+        if Rc::strong_count(&delegate) == 2
+            && returns_after(&bytes, instruction_pointer + 1)
+            && matches!(*delegate.borrow(), GeneratorState::Frame(_))
+            && let GeneratorState::Frame(frame) = delegate.replace(GeneratorState::Done)
+        {
+            self.pop();
+            let current = self.pop_frame();
+            self.close_upvalues(current.base_pointer);
+            self.stack_pointer = current.base_pointer;
+            let generator = current.generator.expect("Yield outside of generator");
+            self.restore_generator(frame, generator);
+            return 0;
+        }
+        match self.resume_generator(&delegate) {
+            Ok(Some(value)) => {
+                self.suspend_generator(instruction_pointer);
+                self.push(value);
+                0
+            }
+            Ok(None) => {
+                self.pop();
+                1
+            }
+            Err(error) => {
+                self.pop();
+                self.return_from_frame(error);
+                0
+            }
+        }
+    }
+
+    fn apply_generator_builtin(
+        &mut self,
+        function: &BuiltInFunction,
+        arguments: &[Rc<Object>],
+    ) -> Option<Object> {
+        let Some(Object::Generator(generator)) = arguments.first().map(|a| a.as_ref()) else {
+            return None;
+        };
+        Some(match (function, arguments.len()) {
+            (BuiltInFunction::Next, 1) => match self.resume_generator(generator) {
+                Ok(value) => value.unwrap_or(NIL),
+                Err(error) => error,
+            },
+            (BuiltInFunction::Done, 1) => {
+                wrap_boolean(matches!(*generator.borrow(), GeneratorState::Done))
+            }
+            (BuiltInFunction::Take, 2) => {
+                let Object::Int(count) = *arguments[1] else {
+                    return None;
+                };
+                let mut elements = vec![];
+                while (elements.len() as i64) < count {
+                    match self.resume_generator(generator) {
+                        Ok(Some(value)) => elements.push(Rc::new(value)),
+                        Ok(None) => break,
+                        Err(error) => return Some(error),
+                    }
+                }
+                Object::Array { elements }
+            }
+            _ => return None,
+        })
+    }
+
     fn push_closure(&mut self, index_of_constant: usize, number_of_free_variables: usize) {
         match self.constants.get(index_of_constant) {
             Some(object) => match object {
                 Object::CompiledFunction(function) => {
-                    let mut free: Vec<Object> = Vec::with_capacity(number_of_free_variables);
-                    for i in 0..number_of_free_variables {
-                        let object = &self.stack[self.stack_pointer - number_of_free_variables + i];
-                        free.push(object.clone());
-                    }
-
-                    self.stack_pointer -= number_of_free_variables; //cleaning stack by moving
-                    //pointer down
+                    let free = self
+                        .captured
+                        .split_off(self.captured.len() - number_of_free_variables);
                     self.push(Object::Closure {
                         function: function.clone(),
                         free: free,
@@ -471,6 +891,65 @@ impl VM {
             }
         }
     }
+
+    fn free_cell(&mut self, free_index: usize) -> Upvalue {
+        let current_closure = &self.current_frame().closure;
+        match current_closure.free.get(free_index) {
+            Some(v) => v.clone(),
+            None => panic!("Cannot find free variable of {free_index} in {current_closure:?}"),
+        }
+    }
+
+    //every closure capturing the slot gets the same cell, so changes are seen by all
+    fn open_upvalue(&mut self, stack_index: usize) -> Upvalue {
+        let existing = self.open_upvalues.iter().find(
+            |cell| matches!(*cell.borrow(), UpvalueCell::Open(index) if index == stack_index),
+        );
+        if let Some(cell) = existing {
+            return cell.clone();
+        }
+        let cell = Rc::new(RefCell::new(UpvalueCell::Open(stack_index)));
+        self.open_upvalues.push(cell.clone());
+        cell
+    }
+
+    //moves values of slots from given index upwards into their cells, slots can be reused after
+    fn close_upvalues(&mut self, from_stack_index: usize) {
+        let stack = &self.stack;
+        self.open_upvalues.retain(|cell| {
+            let index = match *cell.borrow() {
+                UpvalueCell::Open(index) if index >= from_stack_index => index,
+                _ => return true,
+            };
+            *cell.borrow_mut() = UpvalueCell::Closed(stack[index].clone());
+            false
+        });
+    }
+}
+
+//TODO add symbol map to show real place of error
+fn vm_error(message: &str) -> Object {
+    Object::Error {
+        message: message.to_string(),
+        line: 0,
+        column: 0,
+    }
+}
+
+//only jumps, leaving of blocks and discarded values follow up to the return
+fn returns_after(bytes: &[Byte], position: usize) -> bool {
+    let mut position = position;
+    while let Some(byte) = bytes.get(position) {
+        let instruction: u8 = byte.into();
+        match instruction {
+            RETURN_VALUE | NO_RETURN => return true,
+            NULL_OP | POP => position += 1,
+            CLOSE_FREE => position += 2,
+            JUMP => position = read_u_16(&bytes[position + 1..]) as usize,
+            _ => return false,
+        }
+    }
+    false
 }
 
 fn debug(opcode: u8) {
@@ -494,13 +973,24 @@ fn debug(opcode: u8) {
         SET_GLOBAL => "SET_GLOBAL",
         GET_GLOBAL => "GET_GLOBAL",
         ARRAY => "ARRAY",
+        TUPLE => "TUPLE",
+        DESTRUCTURE => "DESTRUCTURE",
         HASH => "HASH",
+        SET => "SET",
         INDEX => "INDEX",
         CALL => "CALL",
         RETURN_VALUE => "RETURN_VALUE",
         NO_RETURN => "NO_RETURN",
         SET_LOCAL => "SET_LOCAL",
         GET_LOCAL => "GET_LOCAL",
+        SET_FREE => "SET_FREE",
+        CAPTURE_LOCAL => "CAPTURE_LOCAL",
+        CAPTURE_FREE => "CAPTURE_FREE",
+        CLOSE_FREE => "CLOSE_FREE",
+        CAPTURE_CLOSURE => "CAPTURE_CLOSURE",
+        TAIL_CALL => "TAIL_CALL",
+        YIELD => "YIELD",
+        DELEGATE => "DELEGATE",
         _ => "NOT KNOW",
     };
     println!("{text}")
@@ -518,6 +1008,9 @@ const JUMP: u8 = OpCodes::Jump as u8;
 const JUMP_NOT_TRUTHY: u8 = OpCodes::JumpNotTruthy as u8;
 const NULL_OP: u8 = OpCodes::Null as u8;
 const ARRAY: u8 = OpCodes::Array as u8;
+const TUPLE: u8 = OpCodes::Tuple as u8;
+const SET: u8 = OpCodes::Set as u8;
+const DESTRUCTURE: u8 = OpCodes::Destructure as u8;
 const HASH: u8 = OpCodes::Hash as u8;
 const INDEX: u8 = OpCodes::Index as u8;
 const CALL: u8 = OpCodes::Call as u8;
@@ -538,3 +1031,11 @@ const SET_LOCAL: u8 = OpCodes::SetLocal as u8;
 const CLOSURE: u8 = OpCodes::Closure as u8;
 const GET_FREE: u8 = OpCodes::GetFree as u8;
 const CURRENT_CLOSURE: u8 = OpCodes::CurrentClosure as u8;
+const SET_FREE: u8 = OpCodes::SetFree as u8;
+const CAPTURE_LOCAL: u8 = OpCodes::CaptureLocal as u8;
+const CAPTURE_FREE: u8 = OpCodes::CaptureFree as u8;
+const CLOSE_FREE: u8 = OpCodes::CloseFree as u8;
+const CAPTURE_CLOSURE: u8 = OpCodes::CaptureClosure as u8;
+const TAIL_CALL: u8 = OpCodes::TailCall as u8;
+const YIELD: u8 = OpCodes::Yield as u8;
+const DELEGATE: u8 = OpCodes::Delegate as u8;
@@ -1,6 +1,179 @@
-use interpreter::start;
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::PathBuf,
+    process::ExitCode,
+};
 
-fn main() {
-    println!("Enter text (Ctrl+D to end):");
-    start();
+use clap::{Parser, Subcommand, ValueEnum};
+use interpreter::{ExportFormat, FormatOptions, check_reader, export_source, format_source, start};
+
+#[derive(Parser)]
+#[command(about = "Interpreter of the Monkey language")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Infers types of a program and reports errors found without running it
+    ///
+    /// With `-` in place of the file the program is read from standard input
+    Check { file: PathBuf },
+    /// Rewrites files in the canonical style
+    ///
+    /// With `-` in place of a file the program is read from standard input and the formatted one
+    /// is written to standard output
+    Fmt {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Only reports files which are not formatted, fails when there are any
+        #[arg(long)]
+        check: bool,
+        /// Lines longer than this are broken
+        #[arg(long, default_value_t = FormatOptions::default().width)]
+        width: usize,
+    },
+    /// Prints the syntax tree of a program for other tools
+    ///
+    /// With `-` in place of the file the program is read from standard input
+    Ast {
+        file: PathBuf,
+        #[arg(long, value_enum, default_value_t = AstFormat::Json)]
+        format: AstFormat,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AstFormat {
+    Json,
+    Sexp,
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Some(Command::Check { file }) => check(&file),
+        Some(Command::Fmt {
+            files,
+            check,
+            width,
+        }) => {
+            let options = FormatOptions {
+                width,
+                ..FormatOptions::default()
+            };
+            let results: Vec<ExitCode> = files
+                .iter()
+                .map(|file| format(file, check, &options))
+                .collect();
+            match results.contains(&ExitCode::FAILURE) {
+                true => ExitCode::FAILURE,
+                false => ExitCode::SUCCESS,
+            }
+        }
+        Some(Command::Ast { file, format }) => {
+            let format = match format {
+                AstFormat::Json => ExportFormat::Json,
+                AstFormat::Sexp => ExportFormat::SExpression,
+            };
+            export(&file, format)
+        }
+        None => {
+            println!("Enter text (Ctrl+D to end):");
+            start();
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn check(file: &PathBuf) -> ExitCode {
+    let result = if file.as_os_str() == "-" {
+        check_reader(io::stdin())
+    } else {
+        match File::open(file) {
+            Ok(input) => check_reader(input),
+            Err(error) => {
+                eprintln!("Cannot read {}: {}", file.display(), error);
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+    match result {
+        Ok(bindings) => {
+            bindings.iter().for_each(|binding| println!("{}", binding));
+            ExitCode::SUCCESS
+        }
+        Err(errors) => {
+            errors.iter().for_each(|error| eprintln!("{}", error));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn format(file: &PathBuf, check: bool, options: &FormatOptions) -> ExitCode {
+    let stdin = file.as_os_str() == "-";
+    let mut source = String::new();
+    let read = match stdin {
+        true => io::stdin().read_to_string(&mut source).map(|_| ()),
+        false => fs::read_to_string(file).map(|text| source = text),
+    };
+    if let Err(error) = read {
+        eprintln!("Cannot read {}: {}", file.display(), error);
+        return ExitCode::FAILURE;
+    }
+    let formatted = match format_source(&source, options) {
+        Ok(formatted) => formatted,
+        Err(errors) => {
+            errors
+                .iter()
+                .for_each(|error| eprintln!("{}: {}", file.display(), error));
+            return ExitCode::FAILURE;
+        }
+    };
+    if check {
+        if formatted == source {
+            return ExitCode::SUCCESS;
+        }
+        println!("{} is not formatted", file.display());
+        return ExitCode::FAILURE;
+    }
+    if stdin {
+        print!("{}", formatted);
+    } else if formatted != source
+        && let Err(error) = fs::write(file, formatted)
+    {
+        eprintln!("Cannot write {}: {}", file.display(), error);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn export(file: &PathBuf, format: ExportFormat) -> ExitCode {
+    let source = match file.as_os_str() == "-" {
+        true => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source).map(|_| source)
+        }
+        false => fs::read_to_string(file),
+    };
+    let source = match source {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("Cannot read {}: {}", file.display(), error);
+            return ExitCode::FAILURE;
+        }
+    };
+    match export_source(&source, format) {
+        Ok(tree) => {
+            println!("{}", tree);
+            ExitCode::SUCCESS
+        }
+        Err(errors) => {
+            errors
+                .iter()
+                .for_each(|error| eprintln!("{}: {}", file.display(), error));
+            ExitCode::FAILURE
+        }
+    }
 }